@@ -0,0 +1,227 @@
+use crate::config::*;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// Outcome of one finished run, read back from its output directory
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunOutcome {
+    /// Run name without its start time, shared by every run of the same scenario
+    pub group: String,
+    pub final_population: f32,
+    /// Only benchmark runs record the generation reached
+    pub max_generation: Option<u32>,
+}
+
+/// Summary over every run in a group
+#[derive(Debug, Clone, PartialEq)]
+pub struct GroupSummary {
+    pub group: String,
+    pub runs: usize,
+    /// Mean and sample standard deviation
+    pub final_population: (f32, f32),
+    pub max_generation: Option<(f32, f32)>,
+    /// Fraction of runs that ended with no animals
+    pub extinction_rate: f32,
+}
+
+impl GroupSummary {
+    pub const CSV_HEADER: &str = "group,runs,final_population_mean,final_population_sd,\
+                                  max_generation_mean,max_generation_sd,extinction_rate";
+}
+
+impl fmt::Display for GroupSummary {
+    /// One CSV row matching `CSV_HEADER`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (generation_mean, generation_sd) = self
+            .max_generation
+            .map_or((String::new(), String::new()), |(mean, sd)| {
+                (format!("{mean:.2}"), format!("{sd:.2}"))
+            });
+        write!(
+            f,
+            "\"{}\",{},{:.2},{:.2},{},{},{:.3}",
+            self.group,
+            self.runs,
+            self.final_population.0,
+            self.final_population.1,
+            generation_mean,
+            generation_sd,
+            self.extinction_rate
+        )
+    }
+}
+
+/// Run name with the trailing start time removed, so repeats of a scenario group together
+pub fn run_group(run: &str) -> &str {
+    match run.rsplit_once('-') {
+        Some((group, started))
+            if !group.is_empty() && started.chars().all(|c| c.is_ascii_digit()) =>
+        {
+            group
+        }
+        _ => run,
+    }
+}
+
+/// Value of `key` in a `key: value` benchmark score
+fn score_field<T: std::str::FromStr>(score: &str, key: &str) -> Option<T> {
+    score.lines().find_map(|line| {
+        let (name, value) = line.split_once(':')?;
+        (name.trim() == key).then(|| value.trim().parse().ok())?
+    })
+}
+
+/// Animal count in the last row of a metrics CSV
+fn final_animals(metrics: &str) -> Option<f32> {
+    let mut lines = metrics.lines();
+    let column = lines
+        .next()?
+        .split(',')
+        .position(|name| name.trim_matches('"') == "animals")?;
+    lines.last()?.split(',').nth(column)?.parse().ok()
+}
+
+/// Read a run's outcome from its benchmark score, or failing that the last sample of its
+/// metrics. None if the run wrote neither
+pub fn read_run(dir: &Path) -> Option<RunOutcome> {
+    let group = run_group(&dir.file_name()?.to_string_lossy()).to_string();
+    if let Ok(score) = std::fs::read_to_string(dir.join("benchmark.txt"))
+        && let Some(animals) = score_field::<usize>(&score, "animals")
+    {
+        return Some(RunOutcome {
+            group,
+            final_population: animals as f32,
+            max_generation: score_field(&score, "max_generation"),
+        });
+    }
+    let metrics = std::fs::read_to_string(dir.join("metrics.csv")).ok()?;
+    Some(RunOutcome {
+        group,
+        final_population: final_animals(&metrics)?,
+        max_generation: None,
+    })
+}
+
+/// Mean and sample standard deviation; the deviation of a single value is 0
+fn mean_sd(values: &[f32]) -> (f32, f32) {
+    let n = values.len() as f32;
+    let mean = values.iter().sum::<f32>() / n;
+    if values.len() < 2 {
+        return (mean, 0.0);
+    }
+    let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f32>() / (n - 1.0);
+    (mean, variance.sqrt())
+}
+
+/// Summarize outcomes per group, sorted by group name
+pub fn summarize(outcomes: &[RunOutcome]) -> Vec<GroupSummary> {
+    let mut groups: BTreeMap<&str, Vec<&RunOutcome>> = BTreeMap::new();
+    for outcome in outcomes {
+        groups.entry(&outcome.group).or_default().push(outcome);
+    }
+
+    groups
+        .into_iter()
+        .map(|(group, runs)| {
+            let populations: Vec<f32> = runs.iter().map(|run| run.final_population).collect();
+            let generations: Vec<f32> = runs
+                .iter()
+                .filter_map(|run| run.max_generation)
+                .map(|generation| generation as f32)
+                .collect();
+            let extinct = populations.iter().filter(|&&p| p == 0.0).count();
+            GroupSummary {
+                group: group.to_string(),
+                runs: runs.len(),
+                final_population: mean_sd(&populations),
+                max_generation: (!generations.is_empty()).then(|| mean_sd(&generations)),
+                extinction_rate: extinct as f32 / runs.len() as f32,
+            }
+        })
+        .collect()
+}
+
+/// `aggregate [DIR]`: summarize every run under DIR (the runs output directory by
+/// default) and print one CSV row per group. Returns false when there was nothing to
+/// summarize
+pub fn run(mut args: impl Iterator<Item = String>) -> bool {
+    let dir = args
+        .next()
+        .map(PathBuf::from)
+        .unwrap_or_else(|| Path::new(OUTPUT_DIR).join(RUN_OUTPUT_DIR));
+    for arg in args {
+        eprintln!("Ignoring unknown argument: {arg}");
+    }
+
+    let entries = match std::fs::read_dir(&dir) {
+        Ok(entries) => entries,
+        Err(err) => {
+            eprintln!("Failed to read runs from {}: {err}", dir.display());
+            return false;
+        }
+    };
+    let outcomes: Vec<RunOutcome> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir())
+        .filter_map(|path| read_run(&path))
+        .collect();
+    if outcomes.is_empty() {
+        eprintln!(
+            "No runs with benchmark.txt or metrics.csv found in {}",
+            dir.display()
+        );
+        return false;
+    }
+
+    println!("{}", GroupSummary::CSV_HEADER);
+    for summary in summarize(&outcomes) {
+        println!("{summary}");
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn runs_group_by_scenario_and_summarize() {
+        assert_eq!(run_group("predator-prey-1700000000"), "predator-prey");
+        assert_eq!(run_group("run-1700000000"), "run");
+        assert_eq!(run_group("custom"), "custom");
+
+        let score = "ticks: 100\nanimals: 12\nmean_generation: 2.50\nmax_generation: 7";
+        assert_eq!(score_field::<usize>(score, "animals"), Some(12));
+        assert_eq!(score_field::<u32>(score, "max_generation"), Some(7));
+        assert_eq!(
+            final_animals("sample,\"plants\",\"animals\"\n0,5,3\n1,6,4\n"),
+            Some(4.0)
+        );
+
+        let outcome = |group: &str, population: f32, generation: Option<u32>| RunOutcome {
+            group: group.to_string(),
+            final_population: population,
+            max_generation: generation,
+        };
+        let summaries = summarize(&[
+            outcome("b", 10.0, Some(4)),
+            outcome("a", 0.0, None),
+            outcome("b", 0.0, Some(2)),
+            outcome("b", 20.0, Some(6)),
+        ]);
+        assert_eq!(summaries.len(), 2);
+        assert_eq!(summaries[0].group, "a");
+        assert_eq!(summaries[0].final_population, (0.0, 0.0));
+        assert_eq!(summaries[0].max_generation, None);
+        assert_eq!(summaries[0].extinction_rate, 1.0);
+
+        let b = &summaries[1];
+        assert_eq!(b.runs, 3);
+        assert_eq!(b.final_population, (10.0, 10.0));
+        assert_eq!(b.max_generation, Some((4.0, 2.0)));
+        assert!((b.extinction_rate - 1.0 / 3.0).abs() < 1e-6);
+        assert_eq!(b.to_string(), "\"b\",3,10.00,10.00,4.00,2.00,0.333");
+    }
+}
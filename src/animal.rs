@@ -1,27 +1,73 @@
+use crate::SimulationTick;
+use crate::carry::{Carried, Carrying};
 use crate::config::*;
-use crate::genome::{Genome, GenomeExecutor, Sensors, Word};
+use crate::energy_flow::{EnergyFlows, Flow};
+use crate::gene_transfer::TransferredGenes;
+use crate::genome::{
+    Diet, Genome, GenomeExecutor, Mutation, MutationConfig, Quadrants, ScentChannel, Sensors,
+    SightKind, VmSettings,
+};
+use crate::meteor::{ScorchedZone, is_scorched};
+use crate::milestones::Milestone;
+use crate::pheromone::PheromoneGrid;
 use crate::plant::{Plant, PlantScent};
+use crate::sim_rng::SimRng;
+use crate::spawning::{SpawnBudget, SpawnQueue};
+use crate::species::Species;
+use crate::vm::{Body, GenomeVm, Intent};
+use bevy::ecs::system::SystemParam;
 use bevy::prelude::*;
+use bevy::utils::Parallel;
 use rand::Rng;
+use rand_distr::{Distribution, Normal};
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
 
 /// Animal component with energy and age
-#[derive(Component)]
+#[derive(Component, Reflect)]
+#[reflect(Component)]
 pub struct Animal {
     pub energy: u32,
     pub age: f32,
+    /// Set when another animal's attack drained the last of this animal's energy
+    pub killed: bool,
+    /// Share of remaining energy the offspring receives on the next split
+    pub split_ratio: f32,
+    /// Number of successful splits
+    pub offspring: u32,
+    /// Energy gained from plants and prey over the animal's lifetime
+    pub energy_eaten: u64,
 }
 
 impl Animal {
     pub fn new(energy: u32) -> Self {
-        Self { energy, age: 0.0 }
+        Self {
+            energy,
+            age: 0.0,
+            killed: false,
+            split_ratio: DEFAULT_SPLIT_RATIO,
+            offspring: 0,
+            energy_eaten: 0,
+        }
+    }
+
+    /// Cause of death for an animal that has run out of energy
+    pub fn depletion_cause(&self) -> DeathCause {
+        if self.killed {
+            DeathCause::Killed
+        } else {
+            DeathCause::Starvation
+        }
     }
 
     pub fn consume_energy(&mut self, amount: u32) {
         self.energy = self.energy.saturating_sub(amount);
     }
 
-    pub fn add_energy(&mut self, amount: u32) {
+    /// Gain energy from food, counting it towards the lifetime total
+    pub fn eat(&mut self, amount: u32) {
         self.energy += amount;
+        self.energy_eaten += amount as u64;
     }
 }
 
@@ -29,545 +75,1231 @@ impl Animal {
 #[derive(Resource)]
 pub struct MetabolismTimer(pub Timer);
 
-/// System to spawn initial test animals
+/// Reason an animal was removed from the world
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeathCause {
+    Starvation,
+    OldAge,
+    VmError,
+    Meteor,
+    Killed,
+}
+
+impl std::fmt::Display for DeathCause {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DeathCause::Starvation => write!(f, "starvation"),
+            DeathCause::OldAge => write!(f, "old age"),
+            DeathCause::VmError => write!(f, "VM error"),
+            DeathCause::Meteor => write!(f, "meteor"),
+            DeathCause::Killed => write!(f, "killed"),
+        }
+    }
+}
+
+/// Event sent when an animal splits, describing how the offspring's genome differs
+#[derive(Event)]
+pub struct AnimalSplit {
+    pub parent: Entity,
+    pub child: Entity,
+    /// Animal whose genome was recombined with the parent's, under sexual reproduction
+    pub partner: Option<Entity>,
+    pub mutations: Vec<Mutation>,
+}
+
+/// How splitting animals build their offspring's genome
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ReproductionMode {
+    /// Mutated clone of the parent
+    #[default]
+    Asexual,
+    /// Crossover with the nearest animal within MATE_DISTANCE, then mutation; falls
+    /// back to cloning when no partner is in range
+    Sexual,
+}
+
+impl ReproductionMode {
+    pub const ALL: [ReproductionMode; 2] = [ReproductionMode::Asexual, ReproductionMode::Sexual];
+}
+
+impl fmt::Display for ReproductionMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReproductionMode::Asexual => write!(f, "asexual"),
+            ReproductionMode::Sexual => write!(f, "sexual"),
+        }
+    }
+}
+
+/// Where a newborn is placed relative to its parent
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OffspringDispersal {
+    /// On the parent, facing the same way
+    Stacked,
+    /// On the parent, facing the opposite way
+    #[default]
+    Opposite,
+    /// At a random offset within OFFSPRING_SCATTER_RADIUS, facing a random way
+    Scatter,
+    /// Facing away from the parent and pushed OFFSPRING_PUSH_DISTANCE that way
+    Push,
+}
+
+impl OffspringDispersal {
+    pub const ALL: [OffspringDispersal; 4] = [
+        OffspringDispersal::Stacked,
+        OffspringDispersal::Opposite,
+        OffspringDispersal::Scatter,
+        OffspringDispersal::Push,
+    ];
+
+    /// Position and rotation of a newborn whose parent is at `position` with `rotation`
+    pub fn place(&self, position: Vec2, rotation: Quat, rng: &mut impl Rng) -> (Vec2, Quat) {
+        let opposite = rotation * Quat::from_rotation_z(std::f32::consts::PI);
+        match self {
+            OffspringDispersal::Stacked => (position, rotation),
+            OffspringDispersal::Opposite => (position, opposite),
+            OffspringDispersal::Scatter => {
+                let angle = rng.gen_range(0.0..std::f32::consts::TAU);
+                let distance = OFFSPRING_SCATTER_RADIUS * rng.r#gen::<f32>().sqrt();
+                let offset = Vec2::from_angle(angle) * distance;
+                let facing = Quat::from_rotation_z(rng.gen_range(0.0..std::f32::consts::TAU));
+                (position + offset, facing)
+            }
+            OffspringDispersal::Push => {
+                let forward = (opposite * Vec3::Y).truncate();
+                (position + forward * OFFSPRING_PUSH_DISTANCE, opposite)
+            }
+        }
+    }
+}
+
+impl fmt::Display for OffspringDispersal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OffspringDispersal::Stacked => write!(f, "stacked"),
+            OffspringDispersal::Opposite => write!(f, "opposite"),
+            OffspringDispersal::Scatter => write!(f, "scatter"),
+            OffspringDispersal::Push => write!(f, "push"),
+        }
+    }
+}
+
+/// Resource making genome bloat costly: energy per genome word added to the split and
+/// metabolism costs
+#[derive(Resource)]
+pub struct GenomeLengthCost {
+    pub split_per_word: f32,
+    pub metabolism_per_word: f32,
+}
+
+impl Default for GenomeLengthCost {
+    fn default() -> Self {
+        Self {
+            split_per_word: SPLIT_COST_PER_WORD,
+            metabolism_per_word: METABOLISM_COST_PER_WORD,
+        }
+    }
+}
+
+impl GenomeLengthCost {
+    /// Energy a split costs a parent running `genome`
+    pub fn split_cost(&self, genome: &Genome) -> u32 {
+        SPLIT_ENERGY_COST + (self.split_per_word * genome.words.len() as f32).round() as u32
+    }
+
+    /// Energy drained from an animal running `genome` per metabolism tick
+    pub fn metabolism_cost(&self, genome: &Genome) -> u32 {
+        METABOLISM_COST + (self.metabolism_per_word * genome.words.len() as f32).round() as u32
+    }
+}
+
+/// Resource controlling how animals reproduce
+#[derive(Resource)]
+pub struct ReproductionSettings {
+    pub mode: ReproductionMode,
+    pub dispersal: OffspringDispersal,
+    /// Splits fail unless a partner with more than MATE_ENERGY_COST energy is within
+    /// MATE_DISTANCE; the partner pays that energy into the offspring
+    pub mate_required: bool,
+    /// Under sexual reproduction, partners more than `max_crossover_distance` word
+    /// edits apart can't recombine and the offspring is a mutated copy of the parent
+    pub hybrid_incompatibility: bool,
+    pub max_crossover_distance: usize,
+}
+
+impl Default for ReproductionSettings {
+    fn default() -> Self {
+        Self {
+            mode: ReproductionMode::default(),
+            dispersal: OffspringDispersal::default(),
+            mate_required: MATE_REQUIRED,
+            hybrid_incompatibility: HYBRID_INCOMPATIBILITY,
+            max_crossover_distance: CROSSOVER_MAX_DISTANCE,
+        }
+    }
+}
+
+impl ReproductionSettings {
+    /// Whether a parent's genome recombines with its partner's
+    pub fn can_recombine(&self, genome: &Genome, mate: &Genome) -> bool {
+        self.mode == ReproductionMode::Sexual
+            && (!self.hybrid_incompatibility
+                || mate.diff(genome).distance() <= self.max_crossover_distance)
+    }
+}
+
+/// Event sent when an animal executes the Attack word; resolved after the VM
+/// loop because the VM cannot mutate other animals mid-iteration
+#[derive(Event, Debug, Clone, Copy)]
+pub struct AttackIntent {
+    pub attacker: Entity,
+}
+
+/// Resource controlling when animals count as starving and what that does to them
+#[derive(Resource)]
+pub struct StarvationWarning {
+    /// Animals with less energy than this are starving
+    pub threshold: u32,
+    /// Starving animals run at most STARVATION_INSTRUCTION_BUDGET instructions per frame
+    pub reduce_budget: bool,
+}
+
+impl Default for StarvationWarning {
+    fn default() -> Self {
+        Self {
+            threshold: STARVATION_THRESHOLD,
+            reduce_budget: false,
+        }
+    }
+}
+
+/// Event sent when an animal takes energy from a plant with the Eat word
+#[derive(Event, Debug, Clone, Copy)]
+pub struct PlantEaten {
+    pub animal: Entity,
+}
+
+/// Event sent when a plant is eaten down to nothing and despawned
+#[derive(Event, Debug, Clone, Copy)]
+pub struct PlantDepleted {
+    pub position: Vec2,
+}
+
+/// Event sent whenever an animal is despawned because it died
+#[derive(Event, Debug, Clone, Copy)]
+pub struct AnimalDied {
+    pub entity: Entity,
+    pub cause: DeathCause,
+    /// Where the animal was when it died
+    pub position: Vec2,
+}
+
+/// System to queue the initial test animals
 pub fn spawn_test_animals(
-    mut commands: Commands,
-    mut meshes: ResMut<Assets<Mesh>>,
-    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut queue: ResMut<SpawnQueue>,
+    spawn_settings: Res<SpawnSettings>,
+    mut rng: ResMut<SimRng>,
 ) {
-    spawn_seed_animals(
-        &mut commands,
-        &mut meshes,
-        &mut materials,
+    queue_seed_animals(
+        &mut queue,
+        &spawn_settings,
         INITIAL_ANIMAL_COUNT,
         STARTING_ANIMAL_ENERGY,
+        &mut *rng,
     );
 }
 
-/// Helper function to spawn animals with the deterministic seed genome
-pub fn spawn_seed_animals(
-    commands: &mut Commands,
-    meshes: &mut ResMut<Assets<Mesh>>,
-    materials: &mut ResMut<Assets<ColorMaterial>>,
-    count: usize,
-    energy: u32,
-) {
-    let mut rng = rand::thread_rng();
+/// Spatial arrangement of seed animals (initial, manual and failsafe spawns)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SpawnDistribution {
+    /// Uniformly over the spawn square
+    #[default]
+    Uniform,
+    /// On a ring at the edge of the spawn area
+    Ring,
+    /// Clustered around a single point
+    Cluster,
+    /// In a strip along the west edge of the spawn area
+    Edge,
+}
 
-    for _ in 0..count {
-        let x = rng.gen_range(-ANIMAL_SPAWN_RANGE..ANIMAL_SPAWN_RANGE);
-        let y = rng.gen_range(-ANIMAL_SPAWN_RANGE..ANIMAL_SPAWN_RANGE);
-        let rotation = rng.gen_range(0.0..std::f32::consts::TAU);
+impl SpawnDistribution {
+    pub const ALL: [SpawnDistribution; 4] = [
+        SpawnDistribution::Uniform,
+        SpawnDistribution::Ring,
+        SpawnDistribution::Cluster,
+        SpawnDistribution::Edge,
+    ];
+}
 
-        commands.spawn((
-            Animal::new(energy),
-            Genome::seed(),
-            GenomeExecutor::new(energy),
-            Sensors::default(),
-            Mesh2d(meshes.add(Circle::new(10.0))),
-            MeshMaterial2d(materials.add(ColorMaterial::from_color(Color::srgb(0.9, 0.3, 0.2)))),
-            Transform::from_xyz(x, y, 0.0).with_rotation(Quat::from_rotation_z(rotation)),
-        ));
+impl fmt::Display for SpawnDistribution {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SpawnDistribution::Uniform => write!(f, "uniform"),
+            SpawnDistribution::Ring => write!(f, "ring"),
+            SpawnDistribution::Cluster => write!(f, "cluster"),
+            SpawnDistribution::Edge => write!(f, "edge"),
+        }
     }
 }
 
-/// System to update sensors for all animals (4 directional smell sensors)
-pub fn update_sensors(
-    mut animals: Query<(&Transform, &mut Sensors), With<Animal>>,
-    plants: Query<&Transform, With<PlantScent>>,
-) {
-    for (animal_transform, mut sensors) in animals.iter_mut() {
-        let animal_pos = animal_transform.translation.truncate();
-
-        // Get animal's forward and right vectors
-        let forward = (animal_transform.rotation * Vec3::Y).truncate();
-        let right = (animal_transform.rotation * Vec3::X).truncate();
+impl std::str::FromStr for SpawnDistribution {
+    type Err = String;
 
-        // Initialize sensors
-        sensors.smell_front = None;
-        sensors.smell_back = None;
-        sensors.smell_left = None;
-        sensors.smell_right = None;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|distribution| distribution.to_string() == s)
+            .ok_or_else(|| format!("unknown spawn distribution '{}'", s))
+    }
+}
 
-        // Check each plant and categorize by quadrant
-        for plant_transform in plants.iter() {
-            let plant_pos = plant_transform.translation.truncate();
-            let to_plant = plant_pos - animal_pos;
-            let distance = to_plant.length();
+/// Resource controlling where seed animals are placed
+#[derive(Resource, Default)]
+pub struct SpawnSettings {
+    pub distribution: SpawnDistribution,
+    /// Center of the cluster distribution
+    pub cluster_center: Vec2,
+}
 
-            // Determine which quadrant the plant is in
-            let forward_dot = to_plant.dot(forward);
-            let right_dot = to_plant.dot(right);
-
-            // Use dot products to determine quadrant
-            if forward_dot.abs() > right_dot.abs() {
-                // Front or back
-                if forward_dot > 0.0 {
-                    // Front
-                    sensors.smell_front = Some(match sensors.smell_front {
-                        None => distance,
-                        Some(d) => d.min(distance),
-                    });
-                } else {
-                    // Back
-                    sensors.smell_back = Some(match sensors.smell_back {
-                        None => distance,
-                        Some(d) => d.min(distance),
-                    });
-                }
-            } else {
-                // Left or right
-                if right_dot > 0.0 {
-                    // Right
-                    sensors.smell_right = Some(match sensors.smell_right {
-                        None => distance,
-                        Some(d) => d.min(distance),
-                    });
-                } else {
-                    // Left
-                    sensors.smell_left = Some(match sensors.smell_left {
-                        None => distance,
-                        Some(d) => d.min(distance),
-                    });
-                }
+impl SpawnSettings {
+    /// Random position for a seed animal under the current distribution
+    pub fn sample_position(&self, rng: &mut impl Rng) -> Vec2 {
+        match self.distribution {
+            SpawnDistribution::Uniform => Vec2::new(
+                rng.gen_range(-ANIMAL_SPAWN_RANGE..ANIMAL_SPAWN_RANGE),
+                rng.gen_range(-ANIMAL_SPAWN_RANGE..ANIMAL_SPAWN_RANGE),
+            ),
+            SpawnDistribution::Ring => {
+                let angle = rng.gen_range(0.0..std::f32::consts::TAU);
+                let half_thickness = SPAWN_RING_THICKNESS / 2.0;
+                let radius = ANIMAL_SPAWN_RANGE + rng.gen_range(-half_thickness..half_thickness);
+                Vec2::from_angle(angle) * radius
             }
+            SpawnDistribution::Cluster => {
+                let spread = Normal::new(0.0, SPAWN_CLUSTER_SPREAD).unwrap();
+                self.cluster_center + Vec2::new(spread.sample(rng), spread.sample(rng))
+            }
+            SpawnDistribution::Edge => Vec2::new(
+                -ANIMAL_SPAWN_RANGE + rng.gen_range(0.0..SPAWN_EDGE_DEPTH),
+                rng.gen_range(-ANIMAL_SPAWN_RANGE..ANIMAL_SPAWN_RANGE),
+            ),
         }
     }
 }
 
-/// Marker component for animals that need to split
-#[derive(Component)]
-pub struct PendingSplit;
+/// Genomes the population failsafe respawns animals with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RespawnSource {
+    /// The deterministic seed genome
+    #[default]
+    Seed,
+    /// Freshly generated random genomes
+    Random,
+    /// Copies of randomly picked survivors, or the seed genome if none are left
+    Survivors,
+}
 
-/// System to execute genome words (stack-based)
-pub fn execute_genomes(
-    mut commands: Commands,
-    mut animals: Query<
-        (
-            Entity,
-            &mut Animal,
-            &Genome,
-            &mut GenomeExecutor,
-            &Sensors,
-            &mut Transform,
-        ),
-        Without<PendingSplit>,
-    >,
-    mut plants: Query<(Entity, &mut Plant, &Transform), Without<Animal>>,
-) {
-    for (entity, mut animal, genome, mut executor, sensors, mut transform) in animals.iter_mut() {
-        executor.reset_for_frame(animal.energy);
-        executor.build_jump_table(genome);
-        executor.build_label_table(genome); // Build label table for jumps
-
-        let mut should_despawn = false;
-        let mut should_split = false;
-
-        // Circular execution: only stops when instruction budget runs out
-        while executor.can_execute() {
-            // Ensure IP is within bounds (wrap if necessary)
-            if executor.instruction_pointer >= genome.words.len() {
-                executor.instruction_pointer = 0;
-            }
+impl RespawnSource {
+    pub const ALL: [RespawnSource; 3] = [
+        RespawnSource::Seed,
+        RespawnSource::Random,
+        RespawnSource::Survivors,
+    ];
+}
 
-            let word = genome.words[executor.instruction_pointer];
+impl fmt::Display for RespawnSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RespawnSource::Seed => write!(f, "seed genome"),
+            RespawnSource::Random => write!(f, "random genomes"),
+            RespawnSource::Survivors => write!(f, "survivors"),
+        }
+    }
+}
 
-            // Handle Split as a special case (requires energy check before execution)
-            if matches!(word, Word::Split) {
-                if animal.energy >= SPLIT_ENERGY_COST {
-                    should_split = true;
-                    executor.advance(genome.words.len());
-                    break; // Stop execution this frame
-                } else {
-                    // Not enough energy, treat as Nop
-                    executor.advance(genome.words.len());
-                    continue;
-                }
-            }
+/// Resource controlling when and how the population failsafe respawns animals
+#[derive(Resource)]
+pub struct FailsafeSettings {
+    /// Experiments that should be allowed to go extinct turn this off
+    pub enabled: bool,
+    /// Population at or below which the failsafe triggers
+    pub threshold: usize,
+    /// Minimum ticks between two triggers
+    pub cooldown_ticks: u64,
+    pub source: RespawnSource,
+    pub respawn_count: usize,
+    last_trigger: Option<u64>,
+}
 
-            match execute_word(
-                word,
-                &mut executor,
-                &mut animal,
-                sensors,
-                &mut transform,
-                &mut plants,
-                &mut commands,
-            ) {
-                Ok(ExecutionResult::Continue) => {
-                    executor.advance(genome.words.len());
-                }
-                Ok(ExecutionResult::Jump(target)) => {
-                    // Ensure target is within bounds (wrap if necessary)
-                    executor.instruction_pointer = target % genome.words.len();
-                    executor.instructions_executed_this_frame += 1;
-                }
-                Ok(ExecutionResult::Skip) => {
-                    // Stack underflow or type mismatch - skip instruction
-                    executor.advance(genome.words.len());
-                }
-                Err(_) => {
-                    // Fatal error - kill the animal
-                    should_despawn = true;
-                    break;
-                }
-            }
+impl Default for FailsafeSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            threshold: FAILSAFE_THRESHOLD,
+            cooldown_ticks: FAILSAFE_COOLDOWN_TICKS,
+            source: RespawnSource::default(),
+            respawn_count: FAILSAFE_RESPAWN_COUNT,
+            last_trigger: None,
         }
+    }
+}
 
-        // Apply deferred actions after iteration completes
-        if should_despawn || animal.energy == 0 {
-            // Either fatal error or out of energy - despawn
-            commands.entity(entity).despawn();
-        } else if should_split {
-            // Only insert PendingSplit if entity is still alive
-            commands.entity(entity).insert(PendingSplit);
+impl FailsafeSettings {
+    /// Whether a population of `count` at `tick` should trigger a respawn
+    fn should_trigger(&self, count: usize, tick: u64) -> bool {
+        self.enabled
+            && count <= self.threshold
+            && self
+                .last_trigger
+                .is_none_or(|last| tick >= last + self.cooldown_ticks)
+    }
+}
+
+/// Helper function to queue animals with the deterministic seed genome
+pub fn queue_seed_animals(
+    queue: &mut SpawnQueue,
+    spawn_settings: &SpawnSettings,
+    count: usize,
+    energy: u32,
+    rng: &mut impl Rng,
+) {
+    for _ in 0..count {
+        let position = spawn_settings.sample_position(rng);
+        let rotation = rng.gen_range(0.0..std::f32::consts::TAU);
+        queue.push(Genome::seed(), energy, position, rotation);
+    }
+}
+
+/// Body traits fixed at birth from the genome's body size: bigger animals reach further
+/// to eat but move slower and burn more energy
+#[derive(Component, Reflect, Debug, Clone, Copy)]
+#[reflect(Component)]
+pub struct Phenotype {
+    pub size: f32,
+}
+
+impl Phenotype {
+    pub fn from_genome(genome: &Genome) -> Self {
+        Self {
+            size: genome.body_size.clamp(MIN_BODY_SIZE, MAX_BODY_SIZE),
         }
     }
+
+    pub fn radius(&self) -> f32 {
+        ANIMAL_RADIUS * self.size
+    }
+
+    /// Multiplier on the distance moved by Move intents (MAX_MOVEMENT_SPEED and
+    /// MAX_STRAFE_SPEED are the limits at size 1)
+    pub fn speed(&self) -> f32 {
+        1.0 / self.size
+    }
+
+    /// Multiplier on metabolism costs
+    pub fn metabolism(&self) -> f32 {
+        self.size
+    }
+
+    /// Distance within which Eat reaches a plant and touching-plant fires
+    pub fn eat_distance(&self) -> f32 {
+        EAT_DISTANCE * self.size
+    }
 }
 
-/// Execution result for word execution
-enum ExecutionResult {
-    Continue,    // Continue to next word
-    Jump(usize), // Jump to specific position (for control flow)
-    Skip,        // Skip this word (stack error)
+/// Body colour of a newly spawned animal, with the hue taken from its genome
+pub fn genome_color(genome: &Genome) -> Color {
+    Color::hsl(genome.hue(), ANIMAL_SATURATION, ANIMAL_LIGHTNESS)
 }
 
-/// Execute a single word
-fn execute_word(
-    word: Word,
-    executor: &mut GenomeExecutor,
-    animal: &mut Animal,
-    sensors: &Sensors,
-    transform: &mut Transform,
-    plants: &mut Query<(Entity, &mut Plant, &Transform), Without<Animal>>,
+/// Helper function to spawn a single animal running the given genome
+#[allow(clippy::too_many_arguments)]
+pub fn spawn_animal(
     commands: &mut Commands,
-) -> Result<ExecutionResult, ()> {
-    match word {
-        // Stack Manipulation
-        Word::Dup => {
-            if let Some(&val) = executor.peek() {
-                executor.stack.push(val);
-                Ok(ExecutionResult::Continue)
-            } else {
-                Ok(ExecutionResult::Skip)
-            }
-        }
-        Word::Drop => {
-            executor.pop();
-            Ok(ExecutionResult::Continue)
+    meshes: &mut ResMut<Assets<Mesh>>,
+    materials: &mut ResMut<Assets<ColorMaterial>>,
+    genome: Genome,
+    lineage: Lineage,
+    energy: u32,
+    position: Vec2,
+    rotation: f32,
+) -> Entity {
+    let color = genome_color(&genome);
+    let phenotype = Phenotype::from_genome(&genome);
+    commands
+        .spawn((
+            Animal::new(energy),
+            BodyHue(genome.hue()),
+            genome,
+            phenotype,
+            lineage,
+            GenomeExecutor::new(energy),
+            Sensors::default(),
+            Mesh2d(meshes.add(Circle::new(phenotype.radius()))),
+            MeshMaterial2d(materials.add(ColorMaterial::from_color(color))),
+            Transform::from_xyz(position.x, position.y, 0.0)
+                .with_rotation(Quat::from_rotation_z(rotation)),
+        ))
+        .id()
+}
+
+/// Which side of an animal counts as its right. Smell sensors, strafing and turning all
+/// go through this so a genome's "left" always agrees with its left sensor
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Handedness {
+    /// Right is clockwise from forward (+X when facing +Y) and left turns are
+    /// counter-clockwise
+    #[default]
+    Standard,
+    /// Left and right swapped for sensors, strafing and turning together
+    Mirrored,
+}
+
+impl Handedness {
+    pub const ALL: [Handedness; 2] = [Handedness::Standard, Handedness::Mirrored];
+
+    /// Direction an animal with this rotation faces
+    pub fn forward(rotation: Quat) -> Vec2 {
+        (rotation * Vec3::Y).truncate()
+    }
+
+    /// Direction of an animal's right side
+    pub fn right(self, rotation: Quat) -> Vec2 {
+        let right = (rotation * Vec3::X).truncate();
+        match self {
+            Handedness::Standard => right,
+            Handedness::Mirrored => -right,
         }
-        Word::Swap => {
-            if let (Some(b), Some(a)) = (executor.pop(), executor.pop()) {
-                executor.stack.push(b);
-                executor.stack.push(a);
-                Ok(ExecutionResult::Continue)
-            } else {
-                Ok(ExecutionResult::Skip)
-            }
+    }
+
+    /// Rotation applied by a Turn intent; positive degrees turn left
+    pub fn turn(self, degrees: f32) -> Quat {
+        let degrees = match self {
+            Handedness::Standard => degrees,
+            Handedness::Mirrored => -degrees,
+        };
+        Quat::from_rotation_z(degrees.to_radians())
+    }
+
+    /// Compass heading of an animal with this rotation, as a fraction of a full turn in
+    /// 0..1: 0 faces +Y and the heading grows as the animal turns left
+    pub fn heading(self, rotation: Quat) -> f32 {
+        let forward = Self::forward(rotation);
+        let counter_clockwise = (-forward.x)
+            .atan2(forward.y)
+            .rem_euclid(std::f32::consts::TAU)
+            / std::f32::consts::TAU;
+        let heading = match self {
+            Handedness::Standard => counter_clockwise,
+            Handedness::Mirrored => 1.0 - counter_clockwise,
+        };
+        // Rounding can land exactly on a full turn
+        if heading >= 1.0 { 0.0 } else { heading }
+    }
+}
+
+impl fmt::Display for Handedness {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Handedness::Standard => write!(f, "standard"),
+            Handedness::Mirrored => write!(f, "mirrored"),
         }
-        Word::Over => {
-            if executor.stack.len() >= 2 {
-                let val = executor.stack[executor.stack.len() - 2];
-                executor.stack.push(val);
-                Ok(ExecutionResult::Continue)
-            } else {
-                Ok(ExecutionResult::Skip)
-            }
+    }
+}
+
+/// What the smell sensors report about the sources of each scent channel in each
+/// quadrant
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ScentModel {
+    /// Distance to the nearest source; nothing smelled reads as a huge distance
+    #[default]
+    Nearest,
+    /// Sum of every source's energy / distance², so dense patches smell stronger than a
+    /// lone plant; nothing smelled reads as 0
+    Intensity,
+}
+
+impl ScentModel {
+    pub const ALL: [ScentModel; 2] = [ScentModel::Nearest, ScentModel::Intensity];
+
+    /// Value a smell sensor starts from before any source is counted
+    fn empty(self) -> Option<f32> {
+        match self {
+            ScentModel::Nearest => None,
+            ScentModel::Intensity => Some(0.0),
         }
-        Word::Rot => {
-            if executor.stack.len() >= 3 {
-                let c = executor.pop().unwrap();
-                let b = executor.pop().unwrap();
-                let a = executor.pop().unwrap();
-                executor.stack.push(b);
-                executor.stack.push(c);
-                executor.stack.push(a);
-                Ok(ExecutionResult::Continue)
-            } else {
-                Ok(ExecutionResult::Skip)
+    }
+
+    /// Count a source with `energy` at `distance` towards a smell sensor
+    fn add(self, sensor: &mut Option<f32>, distance: f32, energy: u32) {
+        match self {
+            ScentModel::Nearest => keep_nearest(sensor, distance),
+            ScentModel::Intensity => {
+                let falloff = distance.max(SCENT_MIN_DISTANCE).powi(2);
+                *sensor = Some(sensor.unwrap_or(0.0) + energy as f32 / falloff);
             }
         }
+    }
+}
 
-        // Literals
-        Word::PushFloat(val) => {
-            executor.push_float(val);
-            Ok(ExecutionResult::Continue)
+impl fmt::Display for ScentModel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScentModel::Nearest => write!(f, "nearest distance"),
+            ScentModel::Intensity => write!(f, "intensity"),
         }
-        Word::PushBool(val) => {
-            executor.push_bool(val);
-            Ok(ExecutionResult::Continue)
+    }
+}
+
+/// Resource limiting how far the smell sensors reach. Sources farther away are not
+/// smelled and do not count as the nearest animal or plant
+#[derive(Resource)]
+pub struct SmellRange {
+    pub radius: f32,
+}
+
+impl Default for SmellRange {
+    fn default() -> Self {
+        Self {
+            radius: SMELL_RANGE,
         }
+    }
+}
+
+/// Resource adding gaussian noise to what animals perceive. Each reading is scaled by
+/// `1 + N(0, sigma)`, so the noise is relative and readings of every unit are equally
+/// unreliable. Counts, true/false readings and the animal's own position and heading stay
+/// exact; the last two are also what speed and turn rate are measured from
+#[derive(Resource)]
+pub struct SensorNoise {
+    pub sigma: f32,
+}
 
-        // Sensor Operations
-        Word::SmellFront => {
-            let distance = sensors.smell_front.unwrap_or(999999.0);
-            executor.push_float(distance);
-            Ok(ExecutionResult::Continue)
+impl Default for SensorNoise {
+    fn default() -> Self {
+        Self {
+            sigma: SENSOR_NOISE,
         }
-        Word::SmellBack => {
-            let distance = sensors.smell_back.unwrap_or(999999.0);
-            executor.push_float(distance);
-            Ok(ExecutionResult::Continue)
+    }
+}
+
+impl SensorNoise {
+    /// Perturb the continuous readings of `sensors`
+    pub fn apply(&self, sensors: &mut Sensors, rng: &mut impl Rng) {
+        let Ok(normal) = Normal::new(1.0, self.sigma) else {
+            return;
+        };
+        let mut noisy = |value: &mut f32| *value *= normal.sample(rng);
+
+        for quadrants in [
+            &mut sensors.plant_scent,
+            &mut sensors.animal_scent,
+            &mut sensors.corpse_scent,
+            &mut sensors.nearest_animal,
+            &mut sensors.hearing,
+        ] {
+            for reading in [
+                &mut quadrants.front,
+                &mut quadrants.back,
+                &mut quadrants.left,
+                &mut quadrants.right,
+            ]
+            .into_iter()
+            .flatten()
+            {
+                noisy(reading);
+            }
         }
-        Word::SmellLeft => {
-            let distance = sensors.smell_left.unwrap_or(999999.0);
-            executor.push_float(distance);
-            Ok(ExecutionResult::Continue)
+        if let Some((distance, _)) = &mut sensors.sight {
+            noisy(distance);
         }
-        Word::SmellRight => {
-            let distance = sensors.smell_right.unwrap_or(999999.0);
-            executor.push_float(distance);
-            Ok(ExecutionResult::Continue)
+        noisy(&mut sensors.pheromone);
+        noisy(&mut sensors.speed);
+        noisy(&mut sensors.turn_rate);
+    }
+}
+
+/// Scent lingering where an animal died, weakening until its timer runs out
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct CorpseScent {
+    pub timer: Timer,
+}
+
+impl CorpseScent {
+    /// Energy the corpse counts as towards intensity smell
+    fn strength(&self) -> u32 {
+        (CORPSE_SCENT_STRENGTH as f32 * self.timer.fraction_remaining()).round() as u32
+    }
+}
+
+/// System to leave corpse scent where each animal died
+pub fn leave_corpse_scents(mut commands: Commands, mut deaths: EventReader<AnimalDied>) {
+    for death in deaths.read() {
+        commands.spawn((
+            CorpseScent {
+                timer: Timer::from_seconds(CORPSE_SCENT_DURATION, TimerMode::Once),
+            },
+            Transform::from_xyz(death.position.x, death.position.y, 0.0),
+        ));
+    }
+}
+
+/// System to remove corpse scent once it has faded
+pub fn fade_corpse_scents(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut corpses: Query<(Entity, &mut CorpseScent)>,
+) {
+    for (entity, mut corpse) in corpses.iter_mut() {
+        if corpse.timer.tick(time.delta()).finished() {
+            commands.entity(entity).despawn();
         }
-        Word::Energy => {
-            executor.push_float(animal.energy as f32);
-            Ok(ExecutionResult::Continue)
+    }
+}
+
+/// System to update sensors for all animals (directional smell per scent channel,
+/// directional animal distances, vision, contact and starvation)
+#[allow(clippy::too_many_arguments)]
+pub fn update_sensors(
+    starvation: Res<StarvationWarning>,
+    handedness: Res<Handedness>,
+    scent: Res<ScentModel>,
+    smell_range: Res<SmellRange>,
+    noise: Res<SensorNoise>,
+    pheromones: Res<PheromoneGrid>,
+    mut calls: ResMut<Calls>,
+    mut rng: ResMut<SimRng>,
+    mut animals: Query<(Entity, &Animal, &Phenotype, &Transform, &mut Sensors)>,
+    plants: Query<(&Transform, &Plant), With<PlantScent>>,
+    corpses: Query<(&Transform, &CorpseScent)>,
+) {
+    // Snapshot animal positions so each animal can check contact with the others
+    let animal_positions: Vec<(Entity, Vec2, u32)> = animals
+        .iter()
+        .map(|(entity, animal, _, transform, _)| {
+            (entity, transform.translation.truncate(), animal.energy)
+        })
+        .collect();
+    let calls = std::mem::take(&mut calls.0);
+
+    // Anything farther than both smell and sight is skipped before any other work
+    let range = smell_range.radius.max(0.0);
+    let reach = range.max(VISION_RANGE);
+
+    for (entity, animal, phenotype, animal_transform, mut sensors) in animals.iter_mut() {
+        let animal_pos = animal_transform.translation.truncate();
+        sensors.starving = animal.energy < starvation.threshold;
+
+        sensors.touching_animal = animal_positions.iter().any(|(other, other_pos, _)| {
+            *other != entity && animal_pos.distance(*other_pos) <= CONTACT_DISTANCE
+        });
+        sensors.touching_plant = false;
+
+        // Get animal's forward and right vectors
+        let forward = Handedness::forward(animal_transform.rotation);
+        let right = handedness.right(animal_transform.rotation);
+
+        // Initialize sensors
+        for channel in ScentChannel::ALL {
+            *sensors.scent_mut(channel) = Quadrants::splat(scent.empty());
         }
+        sensors.nearest_animal = Quadrants::default();
+        sensors.hearing = Quadrants::default();
+        sensors.crowding = 0;
+        sensors.track_motion(animal_pos, handedness.heading(animal_transform.rotation));
+        sensors.sight = None;
+        sensors.nearest_plant_energy = None;
+        let mut nearest_plant = f32::INFINITY;
+        sensors.pheromone = if pheromones.enabled {
+            pheromones.sample(animal_pos)
+        } else {
+            0.0
+        };
 
-        // Arithmetic Operations
-        Word::Add => {
-            if let (Some(b), Some(a)) = (executor.pop_float(), executor.pop_float()) {
-                executor.push_float(a + b);
-                Ok(ExecutionResult::Continue)
-            } else {
-                Ok(ExecutionResult::Skip)
+        // Check each other animal and categorize by quadrant
+        for (other, other_pos, other_energy) in animal_positions.iter() {
+            if *other == entity {
+                continue;
             }
-        }
-        Word::Sub => {
-            if let (Some(b), Some(a)) = (executor.pop_float(), executor.pop_float()) {
-                executor.push_float(a - b);
-                Ok(ExecutionResult::Continue)
-            } else {
-                Ok(ExecutionResult::Skip)
+            let to_animal = *other_pos - animal_pos;
+            let distance = to_animal.length();
+            if distance <= CROWDING_RADIUS {
+                sensors.crowding += 1;
             }
-        }
-        Word::Mul => {
-            if let (Some(b), Some(a)) = (executor.pop_float(), executor.pop_float()) {
-                executor.push_float(a * b);
-                Ok(ExecutionResult::Continue)
-            } else {
-                Ok(ExecutionResult::Skip)
+            if distance > reach {
+                continue;
             }
-        }
-        Word::Div => {
-            if let (Some(b), Some(a)) = (executor.pop_float(), executor.pop_float()) {
-                if b != 0.0 {
-                    executor.push_float(a / b);
-                } else {
-                    executor.push_float(0.0); // Division by zero returns 0
-                }
-                Ok(ExecutionResult::Continue)
-            } else {
-                Ok(ExecutionResult::Skip)
+
+            if let Some(seen) = in_vision_cone(to_animal, forward)
+                && sensors.sight.is_none_or(|(nearest, _)| seen < nearest)
+            {
+                sensors.sight = Some((seen, SightKind::Animal));
             }
-        }
 
-        // Comparison Operations
-        Word::Lt => {
-            if let (Some(b), Some(a)) = (executor.pop_float(), executor.pop_float()) {
-                executor.push_bool(a < b);
-                Ok(ExecutionResult::Continue)
-            } else {
-                Ok(ExecutionResult::Skip)
+            if distance > range {
+                continue;
             }
+            let quadrant = Quadrant::of(to_animal, forward, right);
+            keep_nearest(quadrant.of_sensor(&mut sensors.nearest_animal), distance);
+            scent.add(
+                quadrant.of_sensor(&mut sensors.animal_scent),
+                distance,
+                *other_energy,
+            );
         }
-        Word::Gt => {
-            if let (Some(b), Some(a)) = (executor.pop_float(), executor.pop_float()) {
-                executor.push_bool(a > b);
-                Ok(ExecutionResult::Continue)
-            } else {
-                Ok(ExecutionResult::Skip)
+
+        // Check each plant and categorize by quadrant
+        for (plant_transform, plant) in plants.iter() {
+            let plant_pos = plant_transform.translation.truncate();
+            let to_plant = plant_pos - animal_pos;
+            let distance = to_plant.length();
+
+            if distance <= phenotype.eat_distance() {
+                sensors.touching_plant = true;
             }
-        }
-        Word::Eq => {
-            if let (Some(b), Some(a)) = (executor.pop_float(), executor.pop_float()) {
-                executor.push_bool((a - b).abs() < 0.001); // Float equality with tolerance
-                Ok(ExecutionResult::Continue)
-            } else {
-                Ok(ExecutionResult::Skip)
+            if distance > reach {
+                continue;
+            }
+            if distance < nearest_plant && distance <= range {
+                nearest_plant = distance;
+                sensors.nearest_plant_energy = Some(plant.energy);
             }
-        }
 
-        // Logic Operations
-        Word::And => {
-            if let (Some(b), Some(a)) = (executor.pop_bool(), executor.pop_bool()) {
-                executor.push_bool(a && b);
-                Ok(ExecutionResult::Continue)
-            } else {
-                Ok(ExecutionResult::Skip)
+            if let Some(seen) = in_vision_cone(to_plant, forward)
+                && sensors.sight.is_none_or(|(nearest, _)| seen < nearest)
+            {
+                sensors.sight = Some((seen, SightKind::Plant));
             }
+
+            if distance > range {
+                continue;
+            }
+            let slot = Quadrant::of(to_plant, forward, right).of_sensor(&mut sensors.plant_scent);
+            scent.add(slot, distance, plant.energy);
         }
-        Word::Or => {
-            if let (Some(b), Some(a)) = (executor.pop_bool(), executor.pop_bool()) {
-                executor.push_bool(a || b);
-                Ok(ExecutionResult::Continue)
-            } else {
-                Ok(ExecutionResult::Skip)
+
+        for (corpse_transform, corpse) in corpses.iter() {
+            let to_corpse = corpse_transform.translation.truncate() - animal_pos;
+            let distance = to_corpse.length();
+            if distance > range {
+                continue;
             }
+            let slot = Quadrant::of(to_corpse, forward, right).of_sensor(&mut sensors.corpse_scent);
+            scent.add(slot, distance, corpse.strength());
         }
-        Word::Not => {
-            if let Some(a) = executor.pop_bool() {
-                executor.push_bool(!a);
-                Ok(ExecutionResult::Continue)
-            } else {
-                Ok(ExecutionResult::Skip)
+
+        for (caller, caller_pos) in calls.iter() {
+            if *caller == entity {
+                continue;
+            }
+            let to_caller = *caller_pos - animal_pos;
+            let distance = to_caller.length();
+            if distance <= HEARING_RANGE {
+                let quadrant = Quadrant::of(to_caller, forward, right);
+                keep_nearest(quadrant.of_sensor(&mut sensors.hearing), distance);
             }
         }
 
-        // Control Flow
-        Word::If => {
-            let condition = executor.pop_bool().unwrap_or(false);
-            let current_pos = executor.instruction_pointer;
+        if noise.sigma > 0.0 {
+            noise.apply(&mut sensors, &mut *rng);
+        }
+    }
+}
+
+/// System to count the plants and other animals whose bodies overlap each animal's body
+pub fn detect_contacts(
+    mut animals: Query<(Entity, &Phenotype, &Transform, &mut Sensors)>,
+    plants: Query<&Transform, (With<Plant>, Without<Carried>)>,
+) {
+    let bodies: Vec<(Entity, Vec2, f32)> = animals
+        .iter()
+        .map(|(entity, phenotype, transform, _)| {
+            (entity, transform.translation.truncate(), phenotype.radius())
+        })
+        .collect();
 
-            // Find matching Then/Else in jump table
-            if let Some((_, else_pos, then_pos)) = executor
-                .jump_table
-                .iter()
-                .find(|(if_pos, _, _)| *if_pos == current_pos)
-            {
-                if !condition {
-                    // Jump to else or then
-                    if let Some(else_target) = else_pos {
-                        Ok(ExecutionResult::Jump(*else_target + 1))
-                    } else {
-                        Ok(ExecutionResult::Jump(*then_pos + 1))
-                    }
-                } else {
-                    // Continue to next word (execute if branch)
-                    Ok(ExecutionResult::Continue)
-                }
+    for (entity, phenotype, transform, mut sensors) in animals.iter_mut() {
+        let position = transform.translation.truncate();
+        let radius = phenotype.radius();
+        let animals = bodies
+            .iter()
+            .filter(|(other, other_pos, other_radius)| {
+                *other != entity && position.distance(*other_pos) < radius + other_radius
+            })
+            .count();
+        let plants = plants
+            .iter()
+            .filter(|plant| {
+                let plant_radius = PLANT_RADIUS * plant.scale.x;
+                position.distance(plant.translation.truncate()) < radius + plant_radius
+            })
+            .count();
+        sensors.contacts = (animals + plants) as u32;
+    }
+}
+
+/// Quadrant around an animal that a directional sensor covers
+#[derive(Clone, Copy)]
+enum Quadrant {
+    Front,
+    Back,
+    Left,
+    Right,
+}
+
+impl Quadrant {
+    /// Quadrant a target at `offset` lies in, from the dot products with the animal's
+    /// forward and right vectors
+    fn of(offset: Vec2, forward: Vec2, right: Vec2) -> Self {
+        let forward_dot = offset.dot(forward);
+        let right_dot = offset.dot(right);
+        if forward_dot.abs() > right_dot.abs() {
+            if forward_dot > 0.0 {
+                Quadrant::Front
             } else {
-                // No matching then found, skip
-                Ok(ExecutionResult::Continue)
+                Quadrant::Back
             }
+        } else if right_dot > 0.0 {
+            Quadrant::Right
+        } else {
+            Quadrant::Left
         }
-        Word::Else => {
-            // When we hit else, we came from the IF branch, so skip to THEN
-            let current_pos = executor.instruction_pointer;
-
-            // Find the IF that this ELSE belongs to
-            for (_if_pos, else_pos, then_pos) in &executor.jump_table {
-                if *else_pos == Some(current_pos) {
-                    return Ok(ExecutionResult::Jump(*then_pos + 1));
-                }
-            }
+    }
 
-            Ok(ExecutionResult::Continue)
+    /// This quadrant's reading of a directional sensor
+    fn of_sensor(self, sensor: &mut Quadrants) -> &mut Option<f32> {
+        match self {
+            Quadrant::Front => &mut sensor.front,
+            Quadrant::Back => &mut sensor.back,
+            Quadrant::Left => &mut sensor.left,
+            Quadrant::Right => &mut sensor.right,
         }
-        Word::Then => {
-            // Then is just a marker, continue execution
-            Ok(ExecutionResult::Continue)
+    }
+}
+
+/// Record `distance` in a directional sensor if nothing nearer was found yet
+fn keep_nearest(sensor: &mut Option<f32>, distance: f32) {
+    *sensor = Some(sensor.map_or(distance, |nearest| nearest.min(distance)));
+}
+
+/// Distance to a target at `offset` from an animal facing `forward`, if it lies inside
+/// the animal's vision cone
+fn in_vision_cone(offset: Vec2, forward: Vec2) -> Option<f32> {
+    let distance = offset.length();
+    let visible = distance > 0.0
+        && distance <= VISION_RANGE
+        && offset.dot(forward) >= distance * VISION_HALF_ANGLE.to_radians().cos();
+    visible.then_some(distance)
+}
+
+/// Source of lineage ids; every founder starts a new lineage
+static NEXT_LINEAGE_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Ancestry of an animal, recorded when it is spawned or born from a split
+#[derive(Component, Reflect, Debug, Clone, Copy)]
+#[reflect(Component)]
+pub struct Lineage {
+    /// Shared by a founder and all of its descendants
+    pub lineage_id: u64,
+    /// Animal that split to produce this one; None for founders. The entity may since
+    /// have died (and its id been reused)
+    pub parent: Option<Entity>,
+    /// Number of splits since the founder (founders are generation 0)
+    pub generation: u32,
+    pub birth_tick: u64,
+}
+
+impl Lineage {
+    /// Start a new lineage (seed, imported, failsafe and hand-written animals)
+    pub fn founder(birth_tick: u64) -> Self {
+        Self {
+            lineage_id: NEXT_LINEAGE_ID.fetch_add(1, Ordering::Relaxed),
+            parent: None,
+            generation: 0,
+            birth_tick,
         }
+    }
 
-        // Movement Actions
-        Word::MoveForward => {
-            if let Some(distance) = executor.pop_float() {
-                let clamped_distance =
-                    (distance * 0.01).clamp(-MAX_MOVEMENT_SPEED, MAX_MOVEMENT_SPEED);
-                let forward = transform.rotation * Vec3::Y;
-                transform.translation += forward * clamped_distance;
-                Ok(ExecutionResult::Continue)
-            } else {
-                Ok(ExecutionResult::Skip)
-            }
+    /// Lineage of an offspring of `parent`, whose lineage this is
+    pub fn child(&self, parent: Entity, birth_tick: u64) -> Self {
+        Self {
+            lineage_id: self.lineage_id,
+            parent: Some(parent),
+            generation: self.generation + 1,
+            birth_tick,
         }
-        Word::MoveBackward => {
-            if let Some(distance) = executor.pop_float() {
-                let clamped_distance =
-                    (distance * 0.01).clamp(-MAX_MOVEMENT_SPEED, MAX_MOVEMENT_SPEED);
-                let backward = transform.rotation * Vec3::NEG_Y;
-                transform.translation += backward * clamped_distance;
-                Ok(ExecutionResult::Continue)
+    }
+}
+
+/// Marker component for animals that need to split
+#[derive(Component)]
+pub struct PendingSplit;
+
+/// Current body hue: the genome's hue at birth, then whatever SetColor last asked for.
+/// Changes recolor the animal's own material in place
+#[derive(Component, Reflect, Debug, Clone, Copy, PartialEq)]
+#[reflect(Component)]
+pub struct BodyHue(pub f32);
+
+/// Event carrying one intent produced by an animal's genome, applied by `apply_intents`
+#[derive(Event, Debug, Clone, Copy)]
+pub struct AnimalIntent {
+    pub entity: Entity,
+    pub intent: Intent,
+}
+
+/// Deaths and splits decided while genomes run, applied in one place by
+/// `apply_lifecycle_queue` instead of through per-entity commands
+#[derive(Resource, Default)]
+pub struct LifecycleQueue {
+    pub deaths: Vec<AnimalDied>,
+    pub splits: Vec<Entity>,
+}
+
+/// Calls made this tick, with where each caller stood; `update_sensors` hears them on
+/// the next tick and then clears them
+#[derive(Resource, Default)]
+pub struct Calls(pub Vec<(Entity, Vec2)>);
+
+/// System to execute genome words (stack-based); genomes run in parallel since each
+/// only touches its own animal
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub fn execute_genomes(
+    mut queue: ResMut<LifecycleQueue>,
+    mut intents: EventWriter<AnimalIntent>,
+    mut flows: ResMut<EnergyFlows>,
+    vm_settings: Res<VmSettings>,
+    starvation: Res<StarvationWarning>,
+    length_cost: Res<GenomeLengthCost>,
+    mut animals: Query<
+        (
+            Entity,
+            &mut Animal,
+            &Genome,
+            &mut GenomeExecutor,
+            &Sensors,
+            &Transform,
+        ),
+        Without<PendingSplit>,
+    >,
+    mut frame_deaths: Local<Parallel<Vec<AnimalDied>>>,
+    mut frame_intents: Local<Parallel<Vec<AnimalIntent>>>,
+    mut frame_spent: Local<Parallel<u32>>,
+) {
+    animals.par_iter_mut().for_each(
+        |(entity, mut animal, genome, mut executor, sensors, transform)| {
+            executor.apply_settings(&vm_settings);
+            executor.budget_cap = if starvation.reduce_budget && sensors.starving {
+                STARVATION_INSTRUCTION_BUDGET
             } else {
-                Ok(ExecutionResult::Skip)
-            }
-        }
-        Word::TurnLeft => {
-            if let Some(degrees) = executor.pop_float() {
-                let clamped_degrees =
-                    (degrees * 0.01).clamp(-MAX_ANGULAR_VELOCITY, MAX_ANGULAR_VELOCITY);
-                let rotation = Quat::from_rotation_z(clamped_degrees.to_radians());
-                transform.rotation = rotation * transform.rotation;
-                Ok(ExecutionResult::Continue)
+                MAX_INSTRUCTIONS_PER_FRAME
+            };
+
+            let mut body = Body {
+                energy: animal.energy,
+                split_ratio: animal.split_ratio,
+                age: animal.age,
+                lifespan: MAX_LIFESPAN,
+                split_cost: length_cost.split_cost(genome),
+            };
+            let frame = GenomeVm::new(genome, &mut executor).run_frame(&mut body, sensors);
+            // Attacks are the only words that spend energy while the genome runs
+            *frame_spent.borrow_local_mut() += animal.energy - body.energy;
+            animal.energy = body.energy;
+            animal.split_ratio = body.split_ratio;
+
+            if frame.fatal || animal.energy == 0 {
+                // Either fatal error or out of energy - despawn
+                let cause = if frame.fatal {
+                    DeathCause::VmError
+                } else {
+                    animal.depletion_cause()
+                };
+                frame_deaths.borrow_local_mut().push(AnimalDied {
+                    entity,
+                    cause,
+                    position: transform.translation.truncate(),
+                });
             } else {
-                Ok(ExecutionResult::Skip)
+                // Each animal's intents stay together and in execution order
+                frame_intents.borrow_local_mut().extend(
+                    frame
+                        .intents
+                        .into_iter()
+                        .map(|intent| AnimalIntent { entity, intent }),
+                );
             }
+        },
+    );
+
+    // Genomes finish in whatever order the threads ran them; sorting by entity keeps
+    // seeded runs repeatable. The sort is stable, so each animal's intents keep their order
+    let spent = frame_spent.iter_mut().map(std::mem::take).sum();
+    flows.record(Flow::Attacks, spent);
+    let mut deaths: Vec<AnimalDied> = frame_deaths.drain().collect();
+    deaths.sort_by_key(|death| death.entity);
+    queue.deaths.extend(deaths);
+    let mut frame_intents: Vec<AnimalIntent> = frame_intents.drain().collect();
+    frame_intents.sort_by_key(|intent| intent.entity);
+    intents.send_batch(frame_intents);
+}
+
+/// Exclusive system to despawn animals that died running their genome and mark the
+/// survivors that asked to split, at one point in the tick
+pub fn apply_lifecycle_queue(world: &mut World) {
+    let LifecycleQueue { deaths, splits } =
+        std::mem::take(&mut *world.resource_mut::<LifecycleQueue>());
+
+    for death in deaths {
+        // A meteor or attack may already have removed the animal
+        if let Ok(entity) = world.get_entity_mut(death.entity) {
+            let energy = entity.get::<Animal>().map_or(0, |animal| animal.energy);
+            entity.despawn();
+            world
+                .resource_mut::<EnergyFlows>()
+                .record(Flow::Death, energy);
+            world.send_event(death);
         }
-        Word::TurnRight => {
-            if let Some(degrees) = executor.pop_float() {
-                let clamped_degrees =
-                    (degrees * 0.01).clamp(-MAX_ANGULAR_VELOCITY, MAX_ANGULAR_VELOCITY);
-                let rotation = Quat::from_rotation_z(-clamped_degrees.to_radians());
-                transform.rotation = rotation * transform.rotation;
-                Ok(ExecutionResult::Continue)
-            } else {
-                Ok(ExecutionResult::Skip)
-            }
+    }
+
+    for entity in splits {
+        if let Ok(mut entity) = world.get_entity_mut(entity) {
+            entity.insert(PendingSplit);
         }
+    }
+}
+
+/// System to apply genome intents to the world, in the order the words executed
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub fn apply_intents(
+    mut commands: Commands,
+    mut intents: EventReader<AnimalIntent>,
+    mut attacks: EventWriter<AttackIntent>,
+    mut plants_eaten: EventWriter<PlantEaten>,
+    mut plants_depleted: EventWriter<PlantDepleted>,
+    mut queue: ResMut<LifecycleQueue>,
+    mut flows: ResMut<EnergyFlows>,
+    handedness: Res<Handedness>,
+    mut pheromones: ResMut<PheromoneGrid>,
+    mut calls: ResMut<Calls>,
+    mut animals: Query<
+        (
+            &mut Animal,
+            &mut Transform,
+            &mut BodyHue,
+            &Genome,
+            &Phenotype,
+            Option<&Carrying>,
+        ),
+        Without<Plant>,
+    >,
+    mut plants: Query<(Entity, &mut Plant, &Transform), Without<Animal>>,
+) {
+    for &AnimalIntent { entity, intent } in intents.read() {
+        // The animal may have died since its genome ran
+        let Ok((mut animal, mut transform, mut body_hue, genome, phenotype, carrying)) =
+            animals.get_mut(entity)
+        else {
+            continue;
+        };
 
-        // Resource Actions
-        Word::Eat => {
-            let animal_pos = transform.translation.truncate();
+        match intent {
+            Intent::Move { forward, right } => {
+                let offset = Handedness::forward(transform.rotation) * forward
+                    + handedness.right(transform.rotation) * right;
+                transform.translation += (offset * phenotype.speed()).extend(0.0);
+            }
+            Intent::Turn(degrees) => {
+                transform.rotation = handedness.turn(degrees) * transform.rotation;
+            }
+            Intent::Eat => {
+                let animal_pos = transform.translation.truncate();
 
-            // Find plant within eating distance
-            for (plant_entity, mut plant, plant_transform) in plants.iter_mut() {
-                let plant_pos = plant_transform.translation.truncate();
-                if animal_pos.distance(plant_pos) <= EAT_DISTANCE {
-                    // Transfer energy from plant to animal
-                    let energy_to_transfer = plant.energy.min(EAT_AMOUNT);
-                    plant.consume_energy(energy_to_transfer);
-                    animal.add_energy(energy_to_transfer);
+                // Find plant within eating distance
+                for (plant_entity, mut plant, plant_transform) in plants.iter_mut() {
+                    let plant_pos = plant_transform.translation.truncate();
+                    if animal_pos.distance(plant_pos) <= phenotype.eat_distance() {
+                        // Transfer energy from plant to animal, scaled by its diet
+                        let energy_to_transfer = plant.energy.min(EAT_AMOUNT);
+                        plant.consume_energy(energy_to_transfer);
+                        let gained =
+                            Diet::digest(energy_to_transfer, genome.diet.plant_efficiency());
+                        animal.eat(gained);
+                        flows.record(Flow::Grazing, gained);
+                        flows.record_digestion(energy_to_transfer, gained);
+                        plants_eaten.send(PlantEaten { animal: entity });
 
-                    // If plant is depleted, remove it
-                    if plant.energy == 0 {
-                        commands.entity(plant_entity).despawn();
+                        // If plant is depleted, remove it
+                        if plant.energy == 0 {
+                            commands.entity(plant_entity).despawn();
+                            plants_depleted.send(PlantDepleted {
+                                position: plant_pos,
+                            });
+                        }
+                        break;
                     }
-                    break;
                 }
             }
-            Ok(ExecutionResult::Continue)
-        }
-        Word::Split => {
-            // Should never reach here (handled in execute_genomes)
-            Ok(ExecutionResult::Continue)
-        }
-
-        // Labels (just markers, act like Nop)
-        Word::Label0 | Word::Label1 | Word::Label2 | Word::Label3 => Ok(ExecutionResult::Continue),
-
-        // Jumps (jump to label position)
-        Word::Jump0 => {
-            if let Some(target) = executor.label_table[0] {
-                Ok(ExecutionResult::Jump(target))
-            } else {
-                // Label not found, treat as Nop
-                Ok(ExecutionResult::Continue)
+            Intent::Split => queue.splits.push(entity),
+            Intent::Attack => {
+                attacks.send(AttackIntent { attacker: entity });
             }
-        }
-        Word::Jump1 => {
-            if let Some(target) = executor.label_table[1] {
-                Ok(ExecutionResult::Jump(target))
-            } else {
-                Ok(ExecutionResult::Continue)
+            Intent::Grab => {
+                if carrying.is_some() {
+                    continue;
+                }
+                let animal_pos = transform.translation.truncate();
+                let plant = plants
+                    .iter()
+                    .find(|(_, _, plant_transform)| {
+                        animal_pos.distance(plant_transform.translation.truncate()) <= GRAB_DISTANCE
+                    })
+                    .map(|(plant_entity, _, _)| plant_entity);
+                if let Some(plant) = plant {
+                    // Grabbing a plant someone else holds steals it from them. The plant
+                    // may be eaten by another animal this frame, hence try_insert.
+                    commands.entity(entity).try_insert(Carrying(plant));
+                    commands.entity(plant).try_insert(Carried { by: entity });
+                }
             }
-        }
-        Word::Jump2 => {
-            if let Some(target) = executor.label_table[2] {
-                Ok(ExecutionResult::Jump(target))
-            } else {
-                Ok(ExecutionResult::Continue)
+            Intent::DropItem => {
+                if carrying.is_some() {
+                    commands.entity(entity).remove::<Carrying>();
+                }
             }
-        }
-        Word::Jump3 => {
-            if let Some(target) = executor.label_table[3] {
-                Ok(ExecutionResult::Jump(target))
-            } else {
-                Ok(ExecutionResult::Continue)
+            Intent::SetColor(hue) => {
+                // Repeating the current hue must not flag the material for re-upload
+                body_hue.set_if_neq(BodyHue(hue));
+            }
+            Intent::DepositPheromone(amount) => {
+                if pheromones.enabled {
+                    pheromones.deposit(transform.translation.truncate(), amount);
+                }
             }
+            Intent::Call => calls.0.push((entity, transform.translation.truncate())),
         }
+    }
+}
 
-        // Special
-        Word::Nop => Ok(ExecutionResult::Continue),
+/// System to recolor each animal's own material when its body hue changes, without
+/// allocating a new material
+pub fn apply_color_changes(
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    animals: Query<(Ref<BodyHue>, &MeshMaterial2d<ColorMaterial>), Changed<BodyHue>>,
+) {
+    for (hue, material_handle) in animals.iter() {
+        // Spawned animals already have a material in their starting color
+        if hue.is_added() {
+            continue;
+        }
+        if let Some(material) = materials.get_mut(&material_handle.0) {
+            // Keep saturation/lightness so only the hue is under genetic control
+            let mut hsla = Hsla::from(material.color);
+            hsla.hue = hue.0;
+            material.color = hsla.into();
+        }
     }
 }
 
@@ -575,87 +1307,618 @@ fn execute_word(
 pub fn animal_metabolism(
     time: Res<Time>,
     mut timer: ResMut<MetabolismTimer>,
-    mut animals: Query<&mut Animal>,
+    length_cost: Res<GenomeLengthCost>,
+    mut flows: ResMut<EnergyFlows>,
+    mut rng: ResMut<SimRng>,
+    mut animals: Query<(&mut Animal, &Genome, &Phenotype)>,
 ) {
     // Increment age continuously for all animals
     let delta = time.delta_secs();
-    for mut animal in animals.iter_mut() {
+    for (mut animal, _, _) in animals.iter_mut() {
         animal.age += delta;
     }
 
     // Drain energy at regular intervals
     if timer.0.tick(time.delta()).just_finished() {
-        for mut animal in animals.iter_mut() {
-            animal.consume_energy(METABOLISM_COST);
+        for (mut animal, genome, phenotype) in animals.iter_mut() {
+            // Round the scaled cost up or down at random so fractions still add up
+            let scaled = length_cost.metabolism_cost(genome) as f32 * phenotype.metabolism();
+            let cost = scaled.floor() as u32 + rng.gen_bool(scaled.fract() as f64) as u32;
+            let cost = cost.min(animal.energy);
+            animal.consume_energy(cost);
+            flows.record(Flow::Metabolism, cost);
         }
     }
 }
 
 /// System to remove dead animals (zero energy or exceeded max lifespan)
-pub fn remove_dead_animals(mut commands: Commands, animals: Query<(Entity, &Animal)>) {
-    for (entity, animal) in animals.iter() {
-        if animal.energy == 0 || animal.age >= MAX_LIFESPAN {
-            commands.entity(entity).despawn();
-        }
+pub fn remove_dead_animals(
+    mut commands: Commands,
+    mut deaths: EventWriter<AnimalDied>,
+    mut flows: ResMut<EnergyFlows>,
+    animals: Query<(Entity, &Animal, &Transform)>,
+) {
+    for (entity, animal, transform) in animals.iter() {
+        let cause = if animal.energy == 0 {
+            animal.depletion_cause()
+        } else if animal.age >= MAX_LIFESPAN {
+            DeathCause::OldAge
+        } else {
+            continue;
+        };
+
+        flows.record(Flow::Death, animal.energy);
+        deaths.send(AnimalDied {
+            entity,
+            cause,
+            position: transform.translation.truncate(),
+        });
+        commands.entity(entity).despawn();
     }
 }
 
+/// What a splitting parent passes on besides its genome, grouped to stay under Bevy's
+/// system parameter limit
+#[derive(SystemParam)]
+pub struct Inheritance<'w, 's> {
+    lineages: Query<'w, 's, &'static Lineage>,
+    species: Query<'w, 's, &'static Species>,
+    transferred: Query<'w, 's, &'static TransferredGenes>,
+}
+
 /// System to handle animal splitting/reproduction
+#[allow(clippy::too_many_arguments)]
 pub fn split_animals(
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<ColorMaterial>>,
-    mut splitting_animals: Query<(Entity, &mut Animal, &Genome, &Transform), With<PendingSplit>>,
+    mut splits: EventWriter<AnimalSplit>,
+    settings: Res<ReproductionSettings>,
+    mutation: Res<MutationConfig>,
+    length_cost: Res<GenomeLengthCost>,
+    tick: Res<SimulationTick>,
+    mut budget: ResMut<SpawnBudget>,
+    mut rng: ResMut<SimRng>,
+    mut flows: ResMut<EnergyFlows>,
+    splitting_animals: Query<Entity, With<PendingSplit>>,
+    mut animals: Query<(Entity, &mut Animal, &Genome, &Transform)>,
+    inheritance: Inheritance,
+    scorched_zones: Query<(&ScorchedZone, &Transform)>,
 ) {
-    for (entity, mut animal, genome, transform) in splitting_animals.iter_mut() {
-        // Consume energy for split
-        let remaining_energy = animal.energy.saturating_sub(SPLIT_ENERGY_COST);
-        let offspring_energy = remaining_energy / 2;
+    let wants_partner = settings.mate_required || settings.mode == ReproductionMode::Sexual;
 
-        // Parent keeps half the remaining energy
-        animal.energy = offspring_energy;
+    for entity in splitting_animals.iter() {
+        // The rest keep PendingSplit and split on a later tick
+        if budget.is_exhausted() {
+            break;
+        }
 
-        // Create a single offspring with mutated genome
-        let mutated_genome = genome.mutate();
-        let position = transform.translation.truncate();
+        // Parent keeps living but loses the PendingSplit component, whether or not the
+        // split goes ahead
+        commands.entity(entity).remove::<PendingSplit>();
 
-        // Child faces 180 degrees from parent rotation
+        let Ok((_, animal, genome, transform)) = animals.get(entity) else {
+            continue;
+        };
+        // Longer genomes may cost more to copy; the split fails if the parent can't pay
+        let split_cost = length_cost.split_cost(genome);
+        if animal.energy < split_cost {
+            continue;
+        }
+        let position = transform.translation.truncate();
         let parent_rotation = transform.rotation;
-        let child_rotation = parent_rotation * Quat::from_rotation_z(std::f32::consts::PI);
 
-        commands.spawn((
-            Animal::new(offspring_energy),
-            mutated_genome,
-            GenomeExecutor::new(offspring_energy),
-            Sensors::default(),
-            Mesh2d(meshes.add(Circle::new(10.0))),
-            MeshMaterial2d(
-                materials.add(ColorMaterial::from_color(Color::srgb(0.9, 0.3, 0.2))),
-            ),
-            Transform::from_xyz(position.x, position.y, 0.0).with_rotation(child_rotation),
-        ));
+        // Pick the nearest partner in range; when one is required it must be able to
+        // pay its share of the offspring's energy
+        let partner = wants_partner
+            .then(|| {
+                animals
+                    .iter()
+                    .filter(|(mate, mate_animal, _, _)| {
+                        *mate != entity
+                            && (!settings.mate_required || mate_animal.energy > MATE_ENERGY_COST)
+                    })
+                    .map(|(mate, _, mate_genome, mate_transform)| {
+                        let distance = position.distance(mate_transform.translation.truncate());
+                        (mate, mate_genome, distance)
+                    })
+                    .filter(|(_, _, distance)| *distance <= MATE_DISTANCE)
+                    .min_by(|a, b| a.2.total_cmp(&b.2))
+            })
+            .flatten();
 
-        // Parent keeps living but loses the PendingSplit component
-        commands.entity(entity).remove::<PendingSplit>();
+        if settings.mate_required && partner.is_none() {
+            continue;
+        }
+
+        // Nothing is born on scorched ground; the split fails before anything is paid
+        let (child_position, child_rotation) =
+            settings
+                .dispersal
+                .place(position, parent_rotation, &mut *rng);
+        if is_scorched(child_position, scorched_zones.iter()) {
+            continue;
+        }
+
+        // Genes picked up from neighbours go into every future offspring
+        let received = inheritance
+            .transferred
+            .get(entity)
+            .ok()
+            .map(|genes| genome.with_transferred(&genes.segment, &mut *rng));
+        let genome = received.as_ref().map_or(genome, |(received, _)| received);
+
+        // Create a single offspring with mutated (and possibly recombined) genome
+        let recombined =
+            partner.filter(|(_, mate_genome, _)| settings.can_recombine(genome, mate_genome));
+        let (mutated_genome, mut mutations) = match recombined {
+            Some((_, mate_genome, _)) => genome
+                .crossover(mate_genome, &mut *rng)
+                .mutate(&mutation, &mut *rng),
+            None => genome.mutate(&mutation, &mut *rng),
+        };
+        if let Some((_, transfer)) = received {
+            mutations.insert(0, transfer);
+        }
+        let partner = partner.map(|(mate, _, _)| mate);
+        let recombined = recombined.map(|(mate, _, _)| mate);
+
+        // The partner's contribution goes straight into the offspring
+        let mut mate_energy = 0;
+        if settings.mate_required
+            && let Some(mate) = partner
+            && let Ok((_, mut mate_animal, _, _)) = animals.get_mut(mate)
+        {
+            mate_animal.energy -= MATE_ENERGY_COST;
+            mate_energy = MATE_ENERGY_COST;
+        }
+
+        let Ok((_, mut animal, _, _)) = animals.get_mut(entity) else {
+            continue;
+        };
+
+        // Consume energy for split
+        let remaining_energy = animal.energy.saturating_sub(split_cost);
+        flows.record(Flow::Reproduction, animal.energy - remaining_energy);
+        let offspring_energy = (remaining_energy as f32 * animal.split_ratio) as u32;
+
+        // Parent keeps the rest; the ratio only applies to this split
+        animal.energy = remaining_energy - offspring_energy;
+        animal.split_ratio = DEFAULT_SPLIT_RATIO;
+        animal.offspring += 1;
+        let offspring_energy = offspring_energy + mate_energy;
+
+        budget.try_take();
+        let lineage = match inheritance.lineages.get(entity) {
+            Ok(lineage) => lineage.child(entity, tick.0),
+            Err(_) => Lineage::founder(tick.0),
+        };
+
+        let color = genome_color(&mutated_genome);
+        let phenotype = Phenotype::from_genome(&mutated_genome);
+        let child = commands
+            .spawn((
+                Animal::new(offspring_energy),
+                BodyHue(mutated_genome.hue()),
+                mutated_genome,
+                phenotype,
+                lineage,
+                GenomeExecutor::new(offspring_energy),
+                Sensors::default(),
+                Mesh2d(meshes.add(Circle::new(phenotype.radius()))),
+                MeshMaterial2d(materials.add(ColorMaterial::from_color(color))),
+                Transform::from_xyz(child_position.x, child_position.y, 0.0)
+                    .with_rotation(child_rotation),
+            ))
+            .id();
+        // Offspring start in the parent's species until the next clustering pass
+        if let Ok(species) = inheritance.species.get(entity) {
+            commands.entity(child).insert(*species);
+        }
+
+        splits.send(AnimalSplit {
+            parent: entity,
+            child,
+            partner: recombined,
+            mutations,
+        });
     }
 }
 
-/// System to respawn animals when population reaches zero
+/// System to respawn animals when the population falls to the failsafe threshold
 pub fn population_failsafe(
-    mut commands: Commands,
-    mut meshes: ResMut<Assets<Mesh>>,
-    mut materials: ResMut<Assets<ColorMaterial>>,
-    animals: Query<&Animal>,
+    tick: Res<SimulationTick>,
+    mut failsafe: ResMut<FailsafeSettings>,
+    mut queue: ResMut<SpawnQueue>,
+    spawn_settings: Res<SpawnSettings>,
+    mut rng: ResMut<SimRng>,
+    mut milestones: EventWriter<Milestone>,
+    animals: Query<&Genome, With<Animal>>,
 ) {
     let count = animals.iter().count();
 
-    if count == 0 {
-        spawn_seed_animals(
-            &mut commands,
-            &mut meshes,
-            &mut materials,
-            FAILSAFE_RESPAWN_COUNT,
-            STARTING_ANIMAL_ENERGY,
+    // Animals already waiting in the queue will repopulate the world
+    if !queue.is_empty() || !failsafe.should_trigger(count, tick.0) {
+        return;
+    }
+    failsafe.last_trigger = Some(tick.0);
+
+    let survivors: Vec<&Genome> = animals.iter().collect();
+    let source = match failsafe.source {
+        RespawnSource::Survivors if survivors.is_empty() => RespawnSource::Seed,
+        source => source,
+    };
+    for _ in 0..failsafe.respawn_count {
+        let genome = match source {
+            RespawnSource::Seed => Genome::seed(),
+            RespawnSource::Random => Genome::random(FAILSAFE_RANDOM_GENOME_LENGTH, &mut *rng),
+            RespawnSource::Survivors => survivors[rng.gen_range(0..survivors.len())].clone(),
+        };
+        let position = spawn_settings.sample_position(&mut *rng);
+        let rotation = rng.gen_range(0.0..std::f32::consts::TAU);
+        queue.push(genome, STARTING_ANIMAL_ENERGY, position, rotation);
+    }
+
+    milestones.send(Milestone::Failsafe {
+        population: count,
+        source,
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::ecs::system::RunSystemOnce;
+    use rand::SeedableRng;
+
+    #[test]
+    fn crowding_and_plant_energy_stay_within_range() {
+        let mut world = World::new();
+        world.init_resource::<StarvationWarning>();
+        world.init_resource::<Handedness>();
+        world.init_resource::<ScentModel>();
+        world.init_resource::<SmellRange>();
+        world.init_resource::<SensorNoise>();
+        world.init_resource::<PheromoneGrid>();
+        world.init_resource::<Calls>();
+        world.insert_resource(SimRng::seeded(1));
+
+        let phenotype = Phenotype::from_genome(&Genome::seed());
+        let animal = world
+            .spawn((
+                Animal::new(100),
+                phenotype,
+                Transform::default(),
+                Sensors::default(),
+            ))
+            .id();
+        for x in [CROWDING_RADIUS - 1.0, -CROWDING_RADIUS - 1.0] {
+            world.spawn((
+                Animal::new(100),
+                phenotype,
+                Transform::from_xyz(x, 0.0, 0.0),
+                Sensors::default(),
+            ));
+        }
+        world.spawn((
+            Plant { energy: 80 },
+            PlantScent,
+            Transform::from_xyz(0.0, SMELL_RANGE + 10.0, 0.0),
+        ));
+
+        world.run_system_once(update_sensors).unwrap();
+        let sensors = world.get::<Sensors>(animal).unwrap();
+        assert_eq!(sensors.crowding, 1);
+        assert_eq!(sensors.nearest_plant_energy, None);
+
+        // Widening smell brings the plant into range
+        world.resource_mut::<SmellRange>().radius = SMELL_RANGE + 20.0;
+        world.run_system_once(update_sensors).unwrap();
+        let sensors = world.get::<Sensors>(animal).unwrap();
+        assert_eq!(sensors.nearest_plant_energy, Some(80));
+    }
+
+    #[test]
+    fn corpses_are_smelled_apart_from_plants() {
+        let mut world = World::new();
+        world.init_resource::<Events<AnimalDied>>();
+        world.init_resource::<StarvationWarning>();
+        world.init_resource::<Handedness>();
+        world.init_resource::<ScentModel>();
+        world.init_resource::<SmellRange>();
+        world.init_resource::<SensorNoise>();
+        world.init_resource::<PheromoneGrid>();
+        world.init_resource::<Calls>();
+        world.insert_resource(SimRng::seeded(1));
+
+        let animal = world
+            .spawn((
+                Animal::new(100),
+                Phenotype::from_genome(&Genome::seed()),
+                Transform::default(),
+                Sensors::default(),
+            ))
+            .id();
+        world.spawn((
+            Plant { energy: 80 },
+            PlantScent,
+            Transform::from_xyz(0.0, -30.0, 0.0),
+        ));
+
+        // An animal dying ahead leaves a corpse the living one can smell
+        world.send_event(AnimalDied {
+            entity: Entity::PLACEHOLDER,
+            cause: DeathCause::Starvation,
+            position: Vec2::new(0.0, 40.0),
+        });
+        world.run_system_once(leave_corpse_scents).unwrap();
+        world.run_system_once(update_sensors).unwrap();
+
+        let sensors = world.get::<Sensors>(animal).unwrap();
+        assert_eq!(sensors.corpse_scent.front, Some(40.0));
+        assert_eq!(sensors.corpse_scent.back, None);
+        assert_eq!(sensors.plant_scent.front, None);
+        assert_eq!(sensors.plant_scent.back, Some(30.0));
+    }
+
+    #[test]
+    fn calls_are_heard_once_from_their_direction() {
+        let mut world = World::new();
+        world.init_resource::<StarvationWarning>();
+        world.init_resource::<Handedness>();
+        world.init_resource::<ScentModel>();
+        world.init_resource::<SmellRange>();
+        world.init_resource::<SensorNoise>();
+        world.init_resource::<PheromoneGrid>();
+        world.init_resource::<Calls>();
+        world.insert_resource(SimRng::seeded(1));
+
+        let phenotype = Phenotype::from_genome(&Genome::seed());
+        let listener = world
+            .spawn((
+                Animal::new(100),
+                phenotype,
+                Transform::default(),
+                Sensors::default(),
+            ))
+            .id();
+        let caller = world
+            .spawn((
+                Animal::new(100),
+                phenotype,
+                Transform::from_xyz(0.0, -50.0, 0.0),
+                Sensors::default(),
+            ))
+            .id();
+        let far = Vec2::new(0.0, HEARING_RANGE + 10.0);
+        world.resource_mut::<Calls>().0 = vec![(caller, Vec2::new(0.0, -50.0)), (caller, far)];
+
+        world.run_system_once(update_sensors).unwrap();
+        let heard = &world.get::<Sensors>(listener).unwrap().hearing;
+        assert_eq!(heard.back, Some(50.0));
+        assert_eq!(heard.front, None);
+        // Animals do not hear their own calls
+        assert_eq!(world.get::<Sensors>(caller).unwrap().hearing.front, None);
+
+        // Each call is heard on one sensor update only
+        world.run_system_once(update_sensors).unwrap();
+        assert_eq!(world.get::<Sensors>(listener).unwrap().hearing.back, None);
+    }
+
+    #[test]
+    fn sensor_noise_only_scales_continuous_readings() {
+        let sensed = || Sensors {
+            plant_scent: Quadrants {
+                front: Some(100.0),
+                ..Quadrants::default()
+            },
+            sight: Some((50.0, SightKind::Plant)),
+            speed: 4.0,
+            crowding: 3,
+            position: Vec2::new(10.0, 20.0),
+            nearest_plant_energy: Some(35),
+            ..Sensors::default()
+        };
+        let mut rng = rand::rngs::StdRng::seed_from_u64(7);
+
+        // Without spread every reading stays exact
+        let mut sensors = sensed();
+        SensorNoise { sigma: 0.0 }.apply(&mut sensors, &mut rng);
+        assert_eq!(sensors.plant_scent.front, Some(100.0));
+        assert_eq!(sensors.sight, Some((50.0, SightKind::Plant)));
+        assert_eq!(sensors.speed, 4.0);
+
+        let noise = SensorNoise { sigma: 0.1 };
+        for _ in 0..100 {
+            let mut sensors = sensed();
+            noise.apply(&mut sensors, &mut rng);
+            let front = sensors.plant_scent.front.unwrap();
+            assert!((50.0..150.0).contains(&front), "{front}");
+            assert_ne!(front, 100.0);
+            assert!((2.0..6.0).contains(&sensors.speed), "{}", sensors.speed);
+            assert_eq!(sensors.plant_scent.back, None);
+            assert_eq!(sensors.sight.unwrap().1, SightKind::Plant);
+            assert_eq!(sensors.crowding, 3);
+            assert_eq!(sensors.position, Vec2::new(10.0, 20.0));
+            assert_eq!(sensors.nearest_plant_energy, Some(35));
+        }
+    }
+
+    /// World with what `split_animals` needs, reproducing under `settings`
+    fn split_world(settings: ReproductionSettings) -> World {
+        let mut world = World::new();
+        world.init_resource::<Assets<Mesh>>();
+        world.init_resource::<Assets<ColorMaterial>>();
+        world.init_resource::<Events<AnimalSplit>>();
+        world.init_resource::<MutationConfig>();
+        world.init_resource::<GenomeLengthCost>();
+        world.init_resource::<SimulationTick>();
+        world.init_resource::<SpawnBudget>();
+        world.init_resource::<EnergyFlows>();
+        world.insert_resource(SimRng::seeded(1));
+        world.insert_resource(settings);
+        world
+    }
+
+    #[test]
+    fn mating_debits_the_partner_into_the_offspring() {
+        let settings = || ReproductionSettings {
+            mate_required: true,
+            ..default()
+        };
+        let genome = Genome::seed();
+        let split_cost = GenomeLengthCost::default().split_cost(&genome);
+
+        let mut world = split_world(settings());
+        let parent = world
+            .spawn((
+                Animal::new(100),
+                genome.clone(),
+                Transform::default(),
+                PendingSplit,
+            ))
+            .id();
+        let partner = world
+            .spawn((
+                Animal::new(100),
+                genome.clone(),
+                Transform::from_xyz(MATE_DISTANCE - 1.0, 0.0, 0.0),
+            ))
+            .id();
+        world.run_system_once(split_animals).unwrap();
+
+        let children: Vec<u32> = world
+            .query::<(Entity, &Animal)>()
+            .iter(&world)
+            .filter(|(entity, _)| ![parent, partner].contains(entity))
+            .map(|(_, animal)| animal.energy)
+            .collect();
+        let share = ((100 - split_cost) as f32 * DEFAULT_SPLIT_RATIO) as u32;
+        assert_eq!(children, vec![share + MATE_ENERGY_COST]);
+        let partner_energy = world.get::<Animal>(partner).unwrap().energy;
+        assert_eq!(partner_energy, 100 - MATE_ENERGY_COST);
+
+        // Without a partner in range the split fails and nobody pays for it
+        let mut world = split_world(settings());
+        let parent = world
+            .spawn((
+                Animal::new(100),
+                genome.clone(),
+                Transform::default(),
+                PendingSplit,
+            ))
+            .id();
+        let partner = world
+            .spawn((
+                Animal::new(100),
+                genome,
+                Transform::from_xyz(MATE_DISTANCE + 1.0, 0.0, 0.0),
+            ))
+            .id();
+        world.run_system_once(split_animals).unwrap();
+
+        assert_eq!(world.query::<&Animal>().iter(&world).count(), 2);
+        assert_eq!(world.get::<Animal>(parent).unwrap().energy, 100);
+        assert_eq!(world.get::<Animal>(partner).unwrap().energy, 100);
+    }
+
+    #[test]
+    fn failsafe_respects_threshold_cooldown_and_source() {
+        let mut world = World::new();
+        world.init_resource::<SimulationTick>();
+        world.init_resource::<SpawnQueue>();
+        world.init_resource::<SpawnSettings>();
+        world.init_resource::<Events<Milestone>>();
+        world.insert_resource(SimRng::seeded(1));
+        world.insert_resource(FailsafeSettings {
+            threshold: 1,
+            cooldown_ticks: 10,
+            source: RespawnSource::Survivors,
+            respawn_count: 3,
+            ..default()
+        });
+
+        let mut survivor = Genome::seed();
+        survivor.words.push(crate::genome::Word::Attack);
+        world.spawn((Animal::new(100), survivor.clone()));
+        let other = world.spawn((Animal::new(100), Genome::seed())).id();
+
+        // Two animals are above the threshold
+        world.run_system_once(population_failsafe).unwrap();
+        assert!(world.resource::<SpawnQueue>().is_empty());
+
+        world.despawn(other);
+        world.run_system_once(population_failsafe).unwrap();
+        let queue = world.resource::<SpawnQueue>();
+        assert_eq!(queue.len(), 3);
+        assert!(
+            queue
+                .0
+                .iter()
+                .all(|pending| pending.genome.to_source() == survivor.to_source())
         );
+        assert_eq!(world.resource::<Events<Milestone>>().len(), 1);
+
+        // The next trigger waits out the cooldown
+        for (tick, triggers) in [(5, false), (10, true)] {
+            world.resource_mut::<SpawnQueue>().0.clear();
+            world.resource_mut::<SimulationTick>().0 = tick;
+            world.run_system_once(population_failsafe).unwrap();
+            assert_eq!(
+                !world.resource::<SpawnQueue>().is_empty(),
+                triggers,
+                "tick {tick}"
+            );
+        }
+
+        // Experiments allowed to go extinct are left alone
+        world.resource_mut::<SpawnQueue>().0.clear();
+        world.resource_mut::<SimulationTick>().0 = 100;
+        world.resource_mut::<FailsafeSettings>().enabled = false;
+        world.run_system_once(population_failsafe).unwrap();
+        assert!(world.resource::<SpawnQueue>().is_empty());
+    }
+
+    #[test]
+    fn distant_partners_do_not_recombine() {
+        let with_extra_words = |count: usize| {
+            let mut genome = Genome::seed();
+            genome
+                .words
+                .extend(std::iter::repeat_n(crate::genome::Word::Attack, count));
+            genome
+        };
+        for (incompatible, extra_words, recombines) in
+            [(true, 1, true), (true, 5, false), (false, 5, true)]
+        {
+            let mut world = split_world(ReproductionSettings {
+                mode: ReproductionMode::Sexual,
+                hybrid_incompatibility: incompatible,
+                max_crossover_distance: 2,
+                ..default()
+            });
+            world.spawn((
+                Animal::new(100),
+                Genome::seed(),
+                Transform::default(),
+                PendingSplit,
+            ));
+            let partner = world
+                .spawn((
+                    Animal::new(100),
+                    with_extra_words(extra_words),
+                    Transform::from_xyz(10.0, 0.0, 0.0),
+                ))
+                .id();
+            world.run_system_once(split_animals).unwrap();
+
+            let splits: Vec<AnimalSplit> = world
+                .resource_mut::<Events<AnimalSplit>>()
+                .drain()
+                .collect();
+            assert_eq!(splits.len(), 1);
+            let expected = recombines.then_some(partner);
+            assert_eq!(splits[0].partner, expected, "{extra_words} extra words");
+        }
     }
 }
@@ -0,0 +1,210 @@
+use crate::RunName;
+use crate::animal::{Animal, Lineage};
+use crate::cli::CliArgs;
+use crate::config::*;
+use crate::fast_forward::FastForward;
+use crate::plant::Plant;
+use crate::sim_rng::SimRng;
+use bevy::app::AppExit;
+use bevy::prelude::*;
+use bevy::utils::Instant;
+use std::fmt;
+
+/// Resource present while a `--benchmark` run is fast-forwarding
+#[derive(Resource)]
+pub struct BenchmarkRun {
+    pub ticks: u32,
+    started: Instant,
+}
+
+/// End-of-run summary of a benchmark, comparable across code changes
+#[derive(Debug, Clone, PartialEq)]
+pub struct BenchmarkScore {
+    pub ticks: u32,
+    /// Wall-clock time the run took
+    pub seconds: f32,
+    pub animals: usize,
+    pub plants: usize,
+    /// Energy held by every living animal and plant
+    pub biomass: u64,
+    pub mean_generation: f32,
+    pub max_generation: u32,
+}
+
+impl BenchmarkScore {
+    /// Score the final population from each animal's energy and generation and each
+    /// plant's energy
+    pub fn measure(
+        ticks: u32,
+        seconds: f32,
+        animals: impl IntoIterator<Item = (u32, u32)>,
+        plants: impl IntoIterator<Item = u32>,
+    ) -> Self {
+        let mut score = Self {
+            ticks,
+            seconds,
+            animals: 0,
+            plants: 0,
+            biomass: 0,
+            mean_generation: 0.0,
+            max_generation: 0,
+        };
+        let mut generations = 0u64;
+        for (energy, generation) in animals {
+            score.animals += 1;
+            score.biomass += energy as u64;
+            generations += generation as u64;
+            score.max_generation = score.max_generation.max(generation);
+        }
+        for energy in plants {
+            score.plants += 1;
+            score.biomass += energy as u64;
+        }
+        if score.animals > 0 {
+            score.mean_generation = generations as f32 / score.animals as f32;
+        }
+        score
+    }
+}
+
+impl fmt::Display for BenchmarkScore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "ticks: {}", self.ticks)?;
+        writeln!(f, "seconds: {:.2}", self.seconds)?;
+        writeln!(f, "animals: {}", self.animals)?;
+        writeln!(f, "plants: {}", self.plants)?;
+        writeln!(f, "biomass: {}", self.biomass)?;
+        writeln!(f, "mean_generation: {:.2}", self.mean_generation)?;
+        write!(f, "max_generation: {}", self.max_generation)
+    }
+}
+
+/// Startup system to fast-forward through the benchmark scenario's tick budget when
+/// run with `--benchmark`, from the scenario's seed or a fixed one
+pub fn start_benchmark(
+    mut commands: Commands,
+    cli: Res<CliArgs>,
+    mut fast_forward: ResMut<FastForward>,
+    mut rng: ResMut<SimRng>,
+) {
+    if !cli.benchmark {
+        return;
+    }
+    // A scenario's own seed is applied with the rest of its config
+    if cli
+        .scenario
+        .as_ref()
+        .is_none_or(|scenario| scenario.config.seed.is_none())
+    {
+        *rng = SimRng::seeded(BENCHMARK_SEED);
+    }
+    let ticks = cli
+        .scenario
+        .as_ref()
+        .and_then(|scenario| scenario.ticks)
+        .unwrap_or(FAST_FORWARD_DEFAULT_TICKS);
+    fast_forward.start(ticks);
+    commands.insert_resource(BenchmarkRun {
+        ticks,
+        started: Instant::now(),
+    });
+    info!("Running benchmark for {} ticks", ticks);
+}
+
+/// System to report the score once the benchmark's ticks have run, then quit
+pub fn finish_benchmark(
+    mut commands: Commands,
+    run: Res<BenchmarkRun>,
+    run_name: Res<RunName>,
+    fast_forward: Res<FastForward>,
+    animals: Query<(&Animal, Option<&Lineage>)>,
+    plants: Query<&Plant>,
+    mut exit: EventWriter<AppExit>,
+) {
+    if fast_forward.is_active() {
+        return;
+    }
+
+    let score = BenchmarkScore::measure(
+        run.ticks,
+        run.started.elapsed().as_secs_f32(),
+        animals.iter().map(|(animal, lineage)| {
+            (
+                animal.energy,
+                lineage.map_or(0, |lineage| lineage.generation),
+            )
+        }),
+        plants.iter().map(|plant| plant.energy),
+    );
+    println!("{}", score);
+
+    let dir = run_name.output_dir();
+    let path = dir.join("benchmark.txt");
+    let written =
+        std::fs::create_dir_all(&dir).and_then(|_| std::fs::write(&path, format!("{}\n", score)));
+    match written {
+        Ok(()) => info!("Wrote benchmark score to {}", path.display()),
+        Err(err) => error!("Failed to write benchmark score: {err}"),
+    }
+
+    commands.remove_resource::<BenchmarkRun>();
+    exit.send(AppExit::Success);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn score_sums_biomass_and_averages_generations() {
+        let score = BenchmarkScore::measure(100, 1.0, [(10, 0), (20, 3), (30, 5)], [50, 40]);
+        assert_eq!(score.animals, 3);
+        assert_eq!(score.plants, 2);
+        assert_eq!(score.biomass, 150);
+        assert_eq!(score.mean_generation, 8.0 / 3.0);
+        assert_eq!(score.max_generation, 5);
+
+        let extinct = BenchmarkScore::measure(100, 1.0, [], [50]);
+        assert_eq!(extinct.mean_generation, 0.0);
+        assert_eq!(extinct.biomass, 50);
+    }
+
+    /// Run the benchmark scenario headless for a few hundred ticks and score it
+    fn run_benchmark(ticks: u32) -> BenchmarkScore {
+        let mut cli = CliArgs::parse(["--benchmark".to_string()]);
+        if let Some(scenario) = cli.scenario.as_mut() {
+            scenario.ticks = Some(ticks);
+        }
+
+        let mut app = crate::headless_app(cli);
+        app.update();
+        while app.world().resource::<FastForward>().is_active() {
+            app.update();
+        }
+
+        let world = app.world_mut();
+        let animals: Vec<(u32, u32)> = world
+            .query::<(&Animal, Option<&Lineage>)>()
+            .iter(world)
+            .map(|(animal, lineage)| {
+                (
+                    animal.energy,
+                    lineage.map_or(0, |lineage| lineage.generation),
+                )
+            })
+            .collect();
+        let plants: Vec<u32> = world
+            .query::<&Plant>()
+            .iter(world)
+            .map(|plant| plant.energy)
+            .collect();
+        BenchmarkScore::measure(ticks, 0.0, animals, plants)
+    }
+
+    #[test]
+    fn seeded_benchmark_runs_score_the_same() {
+        let first = run_benchmark(300);
+        assert!(first.animals > 0);
+        assert_eq!(first, run_benchmark(300));
+    }
+}
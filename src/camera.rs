@@ -4,6 +4,8 @@ use bevy::prelude::*;
 use bevy::window::PrimaryWindow;
 use bevy_egui::EguiContexts;
 
+use crate::config::*;
+
 #[derive(Component)]
 pub struct MainCamera;
 
@@ -55,7 +57,8 @@ pub fn camera_zoom(
     for event in scroll_events.read() {
         // Zoom in/out with mouse wheel
         let zoom_delta = -event.y * 0.1;
-        camera_state.zoom = (camera_state.zoom + zoom_delta).clamp(0.1, 10.0);
+        camera_state.zoom =
+            (camera_state.zoom + zoom_delta).clamp(CAMERA_MIN_ZOOM, CAMERA_MAX_ZOOM);
 
         if let Ok(mut projection) = query.get_single_mut() {
             projection.scale = camera_state.zoom;
@@ -96,6 +99,63 @@ pub fn camera_pan(
     }
 }
 
+/// Keys handled by `camera_keyboard`
+const CAMERA_KEYS: [KeyCode; 8] = [
+    KeyCode::Equal,
+    KeyCode::NumpadAdd,
+    KeyCode::Minus,
+    KeyCode::NumpadSubtract,
+    KeyCode::Digit0,
+    KeyCode::Numpad0,
+    KeyCode::PageUp,
+    KeyCode::PageDown,
+];
+
+/// Keyboard camera controls for machines without a middle button or wheel: `+`/`-` step
+/// the zoom, `0` resets it and PageUp/PageDown pan by most of a screen
+pub fn camera_keyboard(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut camera_state: ResMut<CameraState>,
+    mut camera_query: Query<(&mut Transform, &mut OrthographicProjection), With<MainCamera>>,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    mut contexts: EguiContexts,
+) {
+    if contexts.ctx_mut().wants_keyboard_input() {
+        return;
+    }
+
+    if !keys.any_just_pressed(CAMERA_KEYS) {
+        return;
+    }
+
+    let pressed = |codes: &[KeyCode]| codes.iter().any(|code| keys.just_pressed(*code));
+    if pressed(&[KeyCode::Equal, KeyCode::NumpadAdd]) {
+        camera_state.zoom = (camera_state.zoom / CAMERA_ZOOM_STEP).max(CAMERA_MIN_ZOOM);
+    }
+    if pressed(&[KeyCode::Minus, KeyCode::NumpadSubtract]) {
+        camera_state.zoom = (camera_state.zoom * CAMERA_ZOOM_STEP).min(CAMERA_MAX_ZOOM);
+    }
+    if pressed(&[KeyCode::Digit0, KeyCode::Numpad0]) {
+        camera_state.zoom = 1.0;
+    }
+
+    let page = window_query.get_single().map_or(0.0, |window| {
+        window.height() * camera_state.zoom * CAMERA_PAGE_PAN
+    });
+    if keys.just_pressed(KeyCode::PageUp) {
+        camera_state.position.y += page;
+    }
+    if keys.just_pressed(KeyCode::PageDown) {
+        camera_state.position.y -= page;
+    }
+
+    if let Ok((mut transform, mut projection)) = camera_query.get_single_mut() {
+        projection.scale = camera_state.zoom;
+        transform.translation.x = camera_state.position.x;
+        transform.translation.y = camera_state.position.y;
+    }
+}
+
 pub fn camera_touch_controls(
     touches: Res<Touches>,
     mut camera_state: ResMut<CameraState>,
@@ -144,8 +204,8 @@ pub fn camera_touch_controls(
                 if let Some(previous_distance) = camera_state.last_pinch_distance {
                     let distance_delta = current_distance - previous_distance;
                     if distance_delta.abs() > f32::EPSILON {
-                        camera_state.zoom =
-                            (camera_state.zoom - distance_delta * 0.003).clamp(0.1, 10.0);
+                        camera_state.zoom = (camera_state.zoom - distance_delta * 0.003)
+                            .clamp(CAMERA_MIN_ZOOM, CAMERA_MAX_ZOOM);
                         projection.scale = camera_state.zoom;
                     }
                 }
@@ -157,17 +217,16 @@ pub fn camera_touch_controls(
         }
 
         // Handle swipe panning when a single touch is active.
-        if camera_state.secondary_touch_id.is_none() {
-            if let Some(primary_id) = camera_state.primary_touch_id {
-                if let Some(primary_touch) = touches.get_pressed(primary_id) {
-                    let delta = primary_touch.delta();
-                    if delta.length_squared() > 0.0 {
-                        let pan_delta = Vec2::new(-delta.x, delta.y) * camera_state.zoom;
-                        camera_state.position += pan_delta;
-                        transform.translation.x = camera_state.position.x;
-                        transform.translation.y = camera_state.position.y;
-                    }
-                }
+        if camera_state.secondary_touch_id.is_none()
+            && let Some(primary_id) = camera_state.primary_touch_id
+            && let Some(primary_touch) = touches.get_pressed(primary_id)
+        {
+            let delta = primary_touch.delta();
+            if delta.length_squared() > 0.0 {
+                let pan_delta = Vec2::new(-delta.x, delta.y) * camera_state.zoom;
+                camera_state.position += pan_delta;
+                transform.translation.x = camera_state.position.x;
+                transform.translation.y = camera_state.position.y;
             }
         }
     }
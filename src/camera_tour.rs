@@ -0,0 +1,375 @@
+use crate::camera::{CameraState, MainCamera};
+use crate::config::*;
+use crate::dock::{DockLayout, DockTab};
+use bevy::prelude::*;
+use bevy_egui::{EguiContexts, egui};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// One view of a camera tour
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TourStop {
+    pub position: (f32, f32),
+    pub zoom: f32,
+    /// Seconds spent gliding here from the previous view
+    #[serde(default = "default_transition")]
+    pub transition: f32,
+    /// Seconds spent looking at this view before moving on
+    #[serde(default = "default_dwell")]
+    pub dwell: f32,
+}
+
+fn default_transition() -> f32 {
+    CAMERA_TOUR_TRANSITION
+}
+
+fn default_dwell() -> f32 {
+    CAMERA_TOUR_DWELL
+}
+
+impl TourStop {
+    /// A stop at the given view with the default timings
+    pub fn new(position: Vec2, zoom: f32) -> Self {
+        Self {
+            position: (position.x, position.y),
+            zoom,
+            transition: CAMERA_TOUR_TRANSITION,
+            dwell: CAMERA_TOUR_DWELL,
+        }
+    }
+
+    fn center(&self) -> Vec2 {
+        Vec2::new(self.position.0, self.position.1)
+    }
+
+    /// Seconds from leaving the previous view to leaving this one
+    fn duration(&self) -> f32 {
+        self.transition.max(0.0) + self.dwell.max(0.0)
+    }
+}
+
+/// Where a playing tour is
+#[derive(Debug, Clone, Copy)]
+struct TourPlayback {
+    stop: usize,
+    /// Seconds since leaving the previous view
+    elapsed: f32,
+    /// Camera position and zoom the transition to `stop` starts from
+    from: (Vec2, f32),
+}
+
+/// Error while loading or saving a camera tour
+#[derive(Debug)]
+pub enum TourError {
+    Io(std::io::Error),
+    Parse(ron::error::SpannedError),
+    Write(ron::Error),
+}
+
+impl fmt::Display for TourError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TourError::Io(err) => write!(f, "{}", err),
+            TourError::Parse(err) => write!(f, "{}", err),
+            TourError::Write(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl From<std::io::Error> for TourError {
+    fn from(err: std::io::Error) -> Self {
+        TourError::Io(err)
+    }
+}
+
+impl From<ron::error::SpannedError> for TourError {
+    fn from(err: ron::error::SpannedError) -> Self {
+        TourError::Parse(err)
+    }
+}
+
+impl From<ron::Error> for TourError {
+    fn from(err: ron::Error) -> Self {
+        TourError::Write(err)
+    }
+}
+
+/// Resource holding a sequence of views the camera visits on its own, for demos and
+/// unattended recordings. While it plays the tour holds the camera
+#[derive(Resource, Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CameraTour {
+    pub stops: Vec<TourStop>,
+    /// Start over after the last stop instead of ending the tour
+    #[serde(default)]
+    pub looping: bool,
+    #[serde(skip)]
+    playback: Option<TourPlayback>,
+}
+
+impl CameraTour {
+    /// Where a tour saved from the UI is written; load it again with `--tour`
+    pub fn default_path() -> PathBuf {
+        PathBuf::from(OUTPUT_DIR).join(CAMERA_TOUR_FILE)
+    }
+
+    /// Read a tour from a RON file
+    pub fn load(path: &Path) -> Result<Self, TourError> {
+        Ok(ron::from_str(&std::fs::read_to_string(path)?)?)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), TourError> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let text = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())?;
+        std::fs::write(path, text)?;
+        Ok(())
+    }
+
+    /// Seconds one pass through every stop takes
+    pub fn duration(&self) -> f32 {
+        self.stops.iter().map(TourStop::duration).sum()
+    }
+
+    /// Start from the first stop, gliding there from the current camera view. Tours
+    /// that take no time do not start
+    pub fn play(&mut self, position: Vec2, zoom: f32) {
+        self.playback = (self.duration() > 0.0).then_some(TourPlayback {
+            stop: 0,
+            elapsed: 0.0,
+            from: (position, zoom),
+        });
+    }
+
+    pub fn stop(&mut self) {
+        self.playback = None;
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.playback.is_some()
+    }
+
+    /// Index of the stop being moved to or looked at
+    pub fn current_stop(&self) -> Option<usize> {
+        self.playback.map(|playback| playback.stop)
+    }
+
+    /// Move `seconds` further through the tour and return the camera position and
+    /// zoom to show, or None when no tour is playing. The last view is returned once
+    /// more when a tour that does not loop ends
+    pub fn advance(&mut self, seconds: f32) -> Option<(Vec2, f32)> {
+        let Self {
+            stops,
+            looping,
+            playback,
+        } = self;
+        let current = playback.as_mut()?;
+        // Stops may have been removed from the UI while playing
+        if current.stop >= stops.len() {
+            *playback = None;
+            return None;
+        }
+
+        current.elapsed += seconds;
+        while current.elapsed >= stops[current.stop].duration() {
+            let stop = stops[current.stop];
+            current.elapsed -= stop.duration();
+            current.from = (stop.center(), stop.zoom);
+            current.stop += 1;
+            if current.stop == stops.len() {
+                if !*looping {
+                    *playback = None;
+                    return Some((stop.center(), stop.zoom));
+                }
+                current.stop = 0;
+            }
+        }
+
+        let stop = stops[current.stop];
+        let progress = if stop.transition > 0.0 {
+            (current.elapsed / stop.transition).min(1.0)
+        } else {
+            1.0
+        };
+        // Ease in and out, and zoom at a steady rate rather than a steady step
+        let eased = progress * progress * (3.0 - 2.0 * progress);
+        let (from_position, from_zoom) = current.from;
+        let position = from_position.lerp(stop.center(), eased);
+        let zoom = from_zoom * (stop.zoom / from_zoom).powf(eased);
+        Some((position, zoom))
+    }
+}
+
+/// System to move the camera along a playing tour
+pub fn play_camera_tour(
+    time: Res<Time>,
+    mut tour: ResMut<CameraTour>,
+    mut camera_state: ResMut<CameraState>,
+    mut camera_query: Query<(&mut Transform, &mut OrthographicProjection), With<MainCamera>>,
+) {
+    let Some((position, zoom)) = tour.advance(time.delta_secs()) else {
+        return;
+    };
+
+    camera_state.position = position;
+    camera_state.zoom = zoom.clamp(CAMERA_MIN_ZOOM, CAMERA_MAX_ZOOM);
+    if let Ok((mut transform, mut projection)) = camera_query.get_single_mut() {
+        projection.scale = camera_state.zoom;
+        transform.translation.x = position.x;
+        transform.translation.y = position.y;
+    }
+}
+
+/// System to show the camera tour editor: record the current view as a stop, tune
+/// timings, play and save
+pub fn camera_tour_ui(
+    mut contexts: EguiContexts,
+    dock: Res<DockLayout>,
+    mut tour: ResMut<CameraTour>,
+    camera_state: Res<CameraState>,
+) {
+    dock.show(
+        contexts.ctx_mut(),
+        DockTab::Tour,
+        egui::Window::new("Camera Tour")
+            .default_pos(egui::pos2(1020.0, 600.0))
+            .default_width(280.0),
+        |ui| {
+            ui.label(format!(
+                "{} stop(s), {:.0}s per pass",
+                tour.stops.len(),
+                tour.duration()
+            ));
+
+            let current = tour.current_stop();
+            let mut to_remove = None;
+            for (index, stop) in tour.stops.iter_mut().enumerate() {
+                ui.horizontal(|ui| {
+                    let text = format!(
+                        "#{} ({:.0}, {:.0}) {:.2}x",
+                        index + 1,
+                        stop.position.0,
+                        stop.position.1,
+                        stop.zoom
+                    );
+                    if current == Some(index) {
+                        ui.strong(text);
+                    } else {
+                        ui.label(text);
+                    }
+                    ui.add(
+                        egui::DragValue::new(&mut stop.transition)
+                            .range(0.0..=600.0)
+                            .speed(0.1)
+                            .suffix("s move"),
+                    );
+                    ui.add(
+                        egui::DragValue::new(&mut stop.dwell)
+                            .range(0.0..=3600.0)
+                            .speed(0.1)
+                            .suffix("s stay"),
+                    );
+                    if ui.small_button("🗑").clicked() {
+                        to_remove = Some(index);
+                    }
+                });
+            }
+            if let Some(index) = to_remove {
+                tour.stops.remove(index);
+            }
+
+            ui.horizontal(|ui| {
+                if ui.button("➕ Add current view").clicked() {
+                    tour.stops
+                        .push(TourStop::new(camera_state.position, camera_state.zoom));
+                }
+                ui.checkbox(&mut tour.looping, "Loop");
+            });
+
+            ui.horizontal(|ui| {
+                if tour.is_playing() {
+                    if ui.button("⏹ Stop").clicked() {
+                        tour.stop();
+                    }
+                } else if ui
+                    .add_enabled(tour.duration() > 0.0, egui::Button::new("🎥 Play"))
+                    .on_hover_text("The tour holds the camera until it ends or is stopped")
+                    .clicked()
+                {
+                    tour.play(camera_state.position, camera_state.zoom);
+                }
+
+                if ui.button("💾 Save").clicked() {
+                    let path = CameraTour::default_path();
+                    match tour.save(&path) {
+                        Ok(()) => info!("Saved camera tour to {}", path.display()),
+                        Err(err) => error!("Failed to save camera tour: {err}"),
+                    }
+                }
+            });
+        },
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stop(x: f32, zoom: f32) -> TourStop {
+        TourStop {
+            position: (x, 0.0),
+            zoom,
+            transition: 2.0,
+            dwell: 1.0,
+        }
+    }
+
+    #[test]
+    fn tour_glides_dwells_and_loops() {
+        let mut tour = CameraTour {
+            stops: vec![stop(100.0, 1.0), stop(200.0, 4.0)],
+            looping: true,
+            ..Default::default()
+        };
+        assert_eq!(tour.advance(1.0), None);
+
+        tour.play(Vec2::ZERO, 1.0);
+        let (halfway, _) = tour.advance(1.0).unwrap();
+        assert_eq!(halfway, Vec2::new(50.0, 0.0));
+        // Arrived and dwelling
+        assert_eq!(tour.advance(1.5), Some((Vec2::new(100.0, 0.0), 1.0)));
+        assert_eq!(tour.current_stop(), Some(0));
+
+        // Halfway to the second stop the zoom is halfway on a log scale
+        let (position, zoom) = tour.advance(1.5).unwrap();
+        assert_eq!(tour.current_stop(), Some(1));
+        assert_eq!(position, Vec2::new(150.0, 0.0));
+        assert!((zoom - 2.0).abs() < 1e-4);
+
+        // Looping starts over from the last stop's view
+        tour.advance(2.0);
+        assert_eq!(tour.current_stop(), Some(0));
+        let (position, _) = tour.advance(1.0).unwrap();
+        assert_eq!(position, Vec2::new(150.0, 0.0));
+
+        // Without looping the tour ends on the last view
+        tour.looping = false;
+        assert_eq!(tour.advance(5.0), Some((Vec2::new(200.0, 0.0), 4.0)));
+        assert!(!tour.is_playing());
+        assert_eq!(tour.advance(1.0), None);
+    }
+
+    #[test]
+    fn tours_round_trip_through_ron() {
+        let source = "(stops: [(position: (10.0, -20.0), zoom: 2.0, dwell: 5.0)], looping: true)";
+        let tour: CameraTour = ron::from_str(source).unwrap();
+        assert_eq!(tour.stops[0].transition, CAMERA_TOUR_TRANSITION);
+        assert_eq!(tour.stops[0].dwell, 5.0);
+
+        let text = ron::to_string(&tour).unwrap();
+        let loaded: CameraTour = ron::from_str(&text).unwrap();
+        assert_eq!(loaded.stops, tour.stops);
+        assert!(loaded.looping);
+    }
+}
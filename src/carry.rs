@@ -0,0 +1,53 @@
+use crate::animal::Animal;
+use crate::config::*;
+use crate::plant::Plant;
+use bevy::prelude::*;
+
+/// Component on an animal that is carrying a plant
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct Carrying(pub Entity);
+
+/// Component on a plant that is being carried by an animal
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct Carried {
+    pub by: Entity,
+}
+
+/// System to keep carried plants attached in front of their carrier.
+///
+/// Plants follow their carrier by copying its world position every frame instead of
+/// being parented in the transform hierarchy, so every other system can keep treating
+/// a plant's `Transform` as its world position.
+#[allow(clippy::type_complexity)]
+pub fn move_carried_plants(
+    mut commands: Commands,
+    carriers: Query<(Entity, &Carrying, &Transform), (With<Animal>, Without<Plant>)>,
+    mut carried: Query<(Entity, &Carried, &mut Transform), With<Plant>>,
+) {
+    // Release plants whose carrier has died or dropped them
+    for (plant_entity, carried_by, _) in carried.iter() {
+        let still_held = carriers
+            .get(carried_by.by)
+            .is_ok_and(|(_, carrying, _)| carrying.0 == plant_entity);
+        if !still_held {
+            commands.entity(plant_entity).remove::<Carried>();
+        }
+    }
+
+    for (animal_entity, carrying, animal_transform) in carriers.iter() {
+        match carried.get_mut(carrying.0) {
+            Ok((_, carried_by, mut plant_transform)) if carried_by.by == animal_entity => {
+                let forward = animal_transform.rotation * Vec3::Y;
+                let position = animal_transform.translation + forward * CARRY_OFFSET;
+                plant_transform.translation.x = position.x;
+                plant_transform.translation.y = position.y;
+            }
+            // The plant was eaten, or another animal grabbed it
+            _ => {
+                commands.entity(animal_entity).remove::<Carrying>();
+            }
+        }
+    }
+}
@@ -0,0 +1,505 @@
+use crate::animal::Animal;
+use crate::config::*;
+use crate::dock::{DockLayout, DockTab};
+use crate::fitness::FitnessMetrics;
+use crate::plant::Plant;
+use crate::ui_refresh::UiRefresh;
+use crate::{RunName, SimulationTick, run_output_dir};
+use bevy::prelude::*;
+use bevy_egui::{EguiContexts, egui};
+use std::collections::VecDeque;
+use std::io::Write;
+use std::path::PathBuf;
+
+/// Rolling population history used by the charts window
+#[derive(Resource, Default)]
+pub struct PopulationHistory {
+    pub plants: VecDeque<f32>,
+    pub animals: VecDeque<f32>,
+    /// Simulation tick at which each sample was taken
+    pub ticks: VecDeque<u64>,
+    /// Total number of samples ever recorded (used to place event markers)
+    pub total_samples: u64,
+    /// Sample indices at which notable events happened, with a short label
+    pub markers: VecDeque<(u64, String)>,
+}
+
+impl PopulationHistory {
+    /// Index of the oldest sample still held in the history
+    pub fn first_sample(&self) -> u64 {
+        self.total_samples - self.animals.len() as u64
+    }
+
+    /// First and last tick covered by the held samples
+    pub fn tick_range(&self) -> (u64, u64) {
+        (
+            self.ticks.front().copied().unwrap_or_default(),
+            self.ticks.back().copied().unwrap_or_default(),
+        )
+    }
+
+    /// Mark the current sample position with a label (e.g. a catastrophe)
+    pub fn add_marker(&mut self, label: impl Into<String>) {
+        self.markers.push_back((self.total_samples, label.into()));
+    }
+}
+
+/// Timer for sampling population counts
+#[derive(Resource)]
+pub struct ChartSampleTimer(pub Timer);
+
+/// System to record plant and animal counts at a fixed interval
+pub fn sample_population(
+    time: Res<Time>,
+    mut timer: ResMut<ChartSampleTimer>,
+    mut history: ResMut<PopulationHistory>,
+    tick: Res<SimulationTick>,
+    plants: Query<(), With<Plant>>,
+    animals: Query<(), With<Animal>>,
+) {
+    if !timer.0.tick(time.delta()).just_finished() {
+        return;
+    }
+
+    if history.animals.len() >= CHART_HISTORY_LENGTH {
+        history.plants.pop_front();
+        history.animals.pop_front();
+        history.ticks.pop_front();
+    }
+    history.plants.push_back(plants.iter().count() as f32);
+    history.animals.push_back(animals.iter().count() as f32);
+    history.ticks.push_back(tick.0);
+    history.total_samples += 1;
+
+    // Forget markers that have scrolled out of view
+    let first = history.first_sample();
+    while history.markers.front().is_some_and(|(i, _)| *i < first) {
+        history.markers.pop_front();
+    }
+}
+
+/// Draw several series on a shared vertical scale with optional event markers. The
+/// shapes are rebuilt only when `refresh` is set or the chart was resized; otherwise
+/// the last ones are drawn again
+pub fn draw_line_chart(
+    ui: &mut egui::Ui,
+    series: &[(&VecDeque<f32>, egui::Color32)],
+    markers: &[(usize, &str)],
+    capacity: usize,
+    height: f32,
+    refresh: bool,
+) {
+    let id = ui.next_auto_id();
+    let (rect, _response) = ui.allocate_exact_size(
+        egui::vec2(ui.available_width(), height),
+        egui::Sense::hover(),
+    );
+
+    let cached = ui
+        .data(|data| data.get_temp::<(egui::Rect, Vec<egui::Shape>)>(id))
+        .filter(|(cached_rect, _)| !refresh && *cached_rect == rect);
+    let shapes = match cached {
+        Some((_, shapes)) => shapes,
+        None => {
+            let shapes = ui.fonts(|fonts| chart_shapes(fonts, rect, series, markers, capacity));
+            ui.data_mut(|data| data.insert_temp(id, (rect, shapes.clone())));
+            shapes
+        }
+    };
+    ui.painter().extend(shapes);
+}
+
+/// Shapes of a line chart filling `rect`
+fn chart_shapes(
+    fonts: &egui::text::Fonts,
+    rect: egui::Rect,
+    series: &[(&VecDeque<f32>, egui::Color32)],
+    markers: &[(usize, &str)],
+    capacity: usize,
+) -> Vec<egui::Shape> {
+    let mut shapes = vec![egui::Shape::rect_filled(
+        rect,
+        egui::Rounding::same(2.0),
+        egui::Color32::from_gray(30),
+    )];
+
+    let max = series
+        .iter()
+        .flat_map(|(values, _)| values.iter().copied())
+        .fold(1.0, f32::max);
+    let step = rect.width() / (capacity.max(2) - 1) as f32;
+
+    for (index, label) in markers {
+        let x = rect.left() + *index as f32 * step;
+        shapes.push(egui::Shape::line_segment(
+            [egui::pos2(x, rect.top()), egui::pos2(x, rect.bottom())],
+            egui::Stroke::new(1.0, egui::Color32::from_rgb(255, 120, 0)),
+        ));
+        shapes.push(egui::Shape::text(
+            fonts,
+            egui::pos2(x + 2.0, rect.top() + 2.0),
+            egui::Align2::LEFT_TOP,
+            *label,
+            egui::FontId::proportional(10.0),
+            egui::Color32::from_rgb(255, 120, 0),
+        ));
+    }
+
+    for (values, color) in series {
+        if values.len() < 2 {
+            continue;
+        }
+        let points: Vec<egui::Pos2> = values
+            .iter()
+            .enumerate()
+            .map(|(i, value)| {
+                egui::pos2(
+                    rect.left() + i as f32 * step,
+                    rect.bottom() - (value / max) * rect.height(),
+                )
+            })
+            .collect();
+        shapes.push(egui::Shape::line(points, egui::Stroke::new(1.5, *color)));
+    }
+
+    shapes.push(egui::Shape::text(
+        fonts,
+        rect.left_top() + egui::vec2(4.0, 14.0),
+        egui::Align2::LEFT_TOP,
+        format!("{:.0}", max),
+        egui::FontId::monospace(10.0),
+        egui::Color32::LIGHT_GRAY,
+    ));
+    shapes
+}
+
+/// Write population counts and all fitness metric histories to a CSV file.
+///
+/// Every series is sampled at the same interval, so rows are aligned on the most
+/// recent sample and older rows are left blank where a series is shorter.
+pub fn export_csv(
+    run: &RunName,
+    history: &PopulationHistory,
+    metrics: &FitnessMetrics,
+) -> std::io::Result<PathBuf> {
+    let dir = run.output_dir();
+    std::fs::create_dir_all(&dir)?;
+    let path = dir.join("metrics.csv");
+    let mut file = std::fs::File::create(&path)?;
+
+    let mut columns: Vec<(&str, &VecDeque<f32>)> =
+        vec![("plants", &history.plants), ("animals", &history.animals)];
+    for tracked in &metrics.tracked {
+        columns.push((tracked.metric.name(), &tracked.history));
+    }
+
+    let header: Vec<String> = std::iter::once("sample".to_string())
+        .chain(columns.iter().map(|(name, _)| format!("\"{}\"", name)))
+        .collect();
+    writeln!(file, "{}", header.join(","))?;
+
+    let rows = columns.iter().map(|(_, v)| v.len()).max().unwrap_or(0);
+    let first = history.total_samples.saturating_sub(rows as u64);
+    for row in 0..rows {
+        let mut fields = vec![(first + row as u64).to_string()];
+        for (_, values) in &columns {
+            let offset = rows - values.len();
+            fields.push(match row.checked_sub(offset) {
+                Some(i) => values[i].to_string(),
+                None => String::new(),
+            });
+        }
+        writeln!(file, "{}", fields.join(","))?;
+    }
+
+    Ok(path)
+}
+
+/// A chart prepared for export as a standalone SVG file
+pub struct SvgChart<'a> {
+    pub title: &'a str,
+    pub y_label: &'a str,
+    pub run_name: &'a str,
+    /// First and last simulation tick covered by the samples
+    pub ticks: (u64, u64),
+    /// Series are right-aligned: the last value of every series is the latest sample
+    pub series: Vec<(&'a str, &'a VecDeque<f32>, egui::Color32)>,
+    /// Event markers as (sample index from the start of the longest series, label)
+    pub markers: Vec<(usize, &'a str)>,
+}
+
+/// Escape text for use in SVG markup
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn svg_color(color: egui::Color32) -> String {
+    format!("#{:02x}{:02x}{:02x}", color.r(), color.g(), color.b())
+}
+
+impl SvgChart<'_> {
+    /// Render the chart as an SVG document with title, axes, legend and markers
+    pub fn to_svg(&self) -> String {
+        const WIDTH: f32 = 800.0;
+        const HEIGHT: f32 = 450.0;
+        const LEFT: f32 = 70.0;
+        const RIGHT: f32 = 20.0;
+        const TOP: f32 = 60.0;
+        const BOTTOM: f32 = 60.0;
+        const GRID_LINES: usize = 5;
+        let plot_width = WIDTH - LEFT - RIGHT;
+        let plot_height = HEIGHT - TOP - BOTTOM;
+
+        let samples = self
+            .series
+            .iter()
+            .map(|(_, v, _)| v.len())
+            .max()
+            .unwrap_or(0);
+        let max = self
+            .series
+            .iter()
+            .flat_map(|(_, values, _)| values.iter().copied())
+            .fold(1.0, f32::max);
+        let step = plot_width / (samples.max(2) - 1) as f32;
+        let x_at = |index: usize| LEFT + index as f32 * step;
+        let y_at = |value: f32| TOP + plot_height - (value / max) * plot_height;
+
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{WIDTH}\" height=\"{HEIGHT}\" \
+             viewBox=\"0 0 {WIDTH} {HEIGHT}\" font-family=\"sans-serif\">\n\
+             <rect width=\"100%\" height=\"100%\" fill=\"white\"/>\n"
+        );
+        svg.push_str(&format!(
+            "<text x=\"{}\" y=\"24\" font-size=\"18\" text-anchor=\"middle\">{}</text>\n",
+            WIDTH / 2.0,
+            xml_escape(self.title)
+        ));
+        svg.push_str(&format!(
+            "<text x=\"{}\" y=\"44\" font-size=\"12\" text-anchor=\"middle\" fill=\"#555\">\
+             {} · ticks {}–{}</text>\n",
+            WIDTH / 2.0,
+            xml_escape(self.run_name),
+            self.ticks.0,
+            self.ticks.1
+        ));
+
+        // Horizontal grid lines with value labels
+        for line in 0..=GRID_LINES {
+            let value = max * line as f32 / GRID_LINES as f32;
+            let y = y_at(value);
+            svg.push_str(&format!(
+                "<line x1=\"{LEFT}\" y1=\"{y:.1}\" x2=\"{:.1}\" y2=\"{y:.1}\" stroke=\"#ddd\"/>\n\
+                 <text x=\"{:.1}\" y=\"{:.1}\" font-size=\"11\" text-anchor=\"end\">{value:.0}</text>\n",
+                LEFT + plot_width,
+                LEFT - 6.0,
+                y + 4.0
+            ));
+        }
+
+        // Axes and their labels
+        svg.push_str(&format!(
+            "<path d=\"M{LEFT} {TOP} V{:.1} H{:.1}\" stroke=\"black\" fill=\"none\"/>\n",
+            TOP + plot_height,
+            LEFT + plot_width
+        ));
+        svg.push_str(&format!(
+            "<text x=\"{LEFT}\" y=\"{:.1}\" font-size=\"11\">{}</text>\n\
+             <text x=\"{:.1}\" y=\"{:.1}\" font-size=\"11\" text-anchor=\"end\">{}</text>\n\
+             <text x=\"{:.1}\" y=\"{:.1}\" font-size=\"12\" text-anchor=\"middle\">Tick</text>\n",
+            TOP + plot_height + 16.0,
+            self.ticks.0,
+            LEFT + plot_width,
+            TOP + plot_height + 16.0,
+            self.ticks.1,
+            LEFT + plot_width / 2.0,
+            HEIGHT - 20.0
+        ));
+        svg.push_str(&format!(
+            "<text transform=\"translate(16 {:.1}) rotate(-90)\" font-size=\"12\" \
+             text-anchor=\"middle\">{}</text>\n",
+            TOP + plot_height / 2.0,
+            xml_escape(self.y_label)
+        ));
+
+        for (index, label) in &self.markers {
+            let x = x_at(*index);
+            svg.push_str(&format!(
+                "<line x1=\"{x:.1}\" y1=\"{TOP}\" x2=\"{x:.1}\" y2=\"{:.1}\" stroke=\"#ff7800\" \
+                 stroke-dasharray=\"4 3\"/>\n\
+                 <text x=\"{:.1}\" y=\"{:.1}\" font-size=\"10\" fill=\"#ff7800\">{}</text>\n",
+                TOP + plot_height,
+                x + 3.0,
+                TOP + 12.0,
+                xml_escape(label)
+            ));
+        }
+
+        for (i, (name, values, color)) in self.series.iter().enumerate() {
+            let color = svg_color(*color);
+            let offset = samples - values.len();
+            let points: Vec<String> = values
+                .iter()
+                .enumerate()
+                .map(|(j, value)| format!("{:.1},{:.1}", x_at(offset + j), y_at(*value)))
+                .collect();
+            svg.push_str(&format!(
+                "<polyline points=\"{}\" fill=\"none\" stroke=\"{color}\" stroke-width=\"1.5\"/>\n",
+                points.join(" ")
+            ));
+
+            // Legend entry in the top-right corner of the plot
+            let y = TOP + 14.0 + i as f32 * 16.0;
+            let x = LEFT + plot_width - 150.0;
+            svg.push_str(&format!(
+                "<rect x=\"{x:.1}\" y=\"{:.1}\" width=\"10\" height=\"10\" fill=\"{color}\"/>\n\
+                 <text x=\"{:.1}\" y=\"{y:.1}\" font-size=\"11\">{}</text>\n",
+                y - 9.0,
+                x + 14.0,
+                xml_escape(name)
+            ));
+        }
+
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    /// Write the chart to the charts output directory, named after the run, title and tick
+    pub fn export(&self) -> std::io::Result<PathBuf> {
+        let dir = run_output_dir(self.run_name).join("charts");
+        std::fs::create_dir_all(&dir)?;
+        let slug: String = self
+            .title
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() {
+                    c.to_ascii_lowercase()
+                } else {
+                    '-'
+                }
+            })
+            .collect();
+        let path = dir.join(format!(
+            "{}-{}-tick-{:08}.svg",
+            self.run_name, slug, self.ticks.1
+        ));
+        std::fs::write(&path, self.to_svg())?;
+        Ok(path)
+    }
+}
+
+/// Export a chart, logging where it was written
+pub fn export_chart(chart: &SvgChart) {
+    match chart.export() {
+        Ok(path) => info!("Exported chart to {}", path.display()),
+        Err(err) => error!("Failed to export chart: {err}"),
+    }
+}
+
+/// System to show population and fitness metric charts
+pub fn charts_ui(
+    mut contexts: EguiContexts,
+    history: Res<PopulationHistory>,
+    metrics: Res<FitnessMetrics>,
+    run_name: Res<RunName>,
+    dock: Res<DockLayout>,
+    refresh: Res<UiRefresh>,
+) {
+    dock.show(
+        contexts.ctx_mut(),
+        DockTab::Charts,
+        egui::Window::new("Charts")
+            .default_pos(egui::pos2(10.0, 450.0))
+            .default_width(300.0)
+            .default_open(false),
+        |ui| {
+            ui.horizontal(|ui| {
+                ui.colored_label(egui::Color32::from_rgb(100, 220, 100), "■ Plants");
+                ui.colored_label(egui::Color32::from_rgb(230, 90, 60), "■ Animals");
+            });
+
+            let first = history.first_sample();
+            let markers: Vec<(usize, &str)> = history
+                .markers
+                .iter()
+                .map(|(i, label)| ((*i - first) as usize, label.as_str()))
+                .collect();
+
+            draw_line_chart(
+                ui,
+                &[
+                    (&history.plants, egui::Color32::from_rgb(100, 220, 100)),
+                    (&history.animals, egui::Color32::from_rgb(230, 90, 60)),
+                ],
+                &markers,
+                CHART_HISTORY_LENGTH,
+                120.0,
+                refresh.is_due(),
+            );
+            if ui.small_button("🖼 Export SVG").clicked() {
+                export_chart(&SvgChart {
+                    title: "Population",
+                    y_label: "Count",
+                    run_name: &run_name.0,
+                    ticks: history.tick_range(),
+                    series: vec![
+                        (
+                            "Plants",
+                            &history.plants,
+                            egui::Color32::from_rgb(60, 170, 60),
+                        ),
+                        (
+                            "Animals",
+                            &history.animals,
+                            egui::Color32::from_rgb(230, 90, 60),
+                        ),
+                    ],
+                    markers: markers.clone(),
+                });
+            }
+
+            // Registered fitness metrics get a chart each
+            for tracked in &metrics.tracked {
+                ui.label(format!(
+                    "{}: {:.1}",
+                    tracked.metric.name(),
+                    tracked.metric.value()
+                ));
+                draw_line_chart(
+                    ui,
+                    &[(&tracked.history, egui::Color32::from_rgb(120, 170, 255))],
+                    &[],
+                    CHART_HISTORY_LENGTH,
+                    50.0,
+                    refresh.is_due(),
+                );
+                if ui.small_button("🖼 Export SVG").clicked() {
+                    let name = tracked.metric.name();
+                    export_chart(&SvgChart {
+                        title: name,
+                        y_label: name,
+                        run_name: &run_name.0,
+                        ticks: history.tick_range(),
+                        series: vec![(
+                            name,
+                            &tracked.history,
+                            egui::Color32::from_rgb(60, 110, 220),
+                        )],
+                        markers: Vec::new(),
+                    });
+                }
+            }
+
+            ui.separator();
+            if ui.button("💾 Export CSV").clicked() {
+                match export_csv(&run_name, &history, &metrics) {
+                    Ok(path) => info!("Exported metrics to {}", path.display()),
+                    Err(err) => error!("Failed to export metrics: {err}"),
+                }
+            }
+        },
+    );
+}
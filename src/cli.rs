@@ -0,0 +1,171 @@
+use crate::animal::{SensorNoise, SpawnDistribution, SpawnSettings};
+use crate::camera::CameraState;
+use crate::camera_tour::CameraTour;
+use crate::genome::{StackMode, StackOverflowPolicy, VmSettings};
+use crate::import::ImportGenomes;
+use crate::plant::{PlantConfig, PlantPlacement};
+use crate::scenario::Scenario;
+use bevy::prelude::*;
+use std::path::PathBuf;
+
+/// Options given on the command line
+#[derive(Resource, Debug)]
+pub struct CliArgs {
+    /// Genome file, directory or zip archive to seed the population from
+    pub import: Option<PathBuf>,
+    /// Number of animals to spawn per imported genome
+    pub copies: usize,
+    /// Overrides for the VM stack limits
+    pub stack_capacity: Option<usize>,
+    pub overflow_policy: Option<StackOverflowPolicy>,
+    /// Keep arithmetic results finite
+    pub safe_math: bool,
+    /// Override for how the VM stores floats and bools
+    pub stack_mode: Option<StackMode>,
+    /// Override for where seed animals are placed
+    pub spawn_distribution: Option<SpawnDistribution>,
+    /// Override for where randomly spawned plants are placed
+    pub plant_placement: Option<PlantPlacement>,
+    /// Override for the relative standard deviation of sensor noise
+    pub sensor_noise: Option<f32>,
+    /// Exact initial setup replacing the seed animals
+    pub scenario: Option<Scenario>,
+    /// Fast-forward the scenario's tick budget, report a score and quit
+    pub benchmark: bool,
+    /// Camera tour to play from startup
+    pub tour: Option<CameraTour>,
+}
+
+impl CliArgs {
+    /// Parse the process arguments, warning about anything unrecognized
+    pub fn from_env() -> Self {
+        Self::parse(std::env::args().skip(1))
+    }
+
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Self {
+        let mut cli = Self {
+            import: None,
+            copies: 1,
+            stack_capacity: None,
+            overflow_policy: None,
+            safe_math: false,
+            stack_mode: None,
+            spawn_distribution: None,
+            plant_placement: None,
+            sensor_noise: None,
+            scenario: None,
+            benchmark: false,
+            tour: None,
+        };
+
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--import" => cli.import = args.next().map(PathBuf::from),
+                "--copies" => match args.next().and_then(|value| value.parse().ok()) {
+                    Some(copies) => cli.copies = copies,
+                    None => eprintln!("--copies expects a number"),
+                },
+                "--stack-size" => match args.next().and_then(|value| value.parse().ok()) {
+                    Some(capacity) if capacity > 0 => cli.stack_capacity = Some(capacity),
+                    _ => eprintln!("--stack-size expects a positive number"),
+                },
+                "--overflow-policy" => match args.next().map(|value| value.parse()) {
+                    Some(Ok(policy)) => cli.overflow_policy = Some(policy),
+                    Some(Err(err)) => eprintln!("{err}"),
+                    None => eprintln!("--overflow-policy expects a policy name"),
+                },
+                "--safe-math" => cli.safe_math = true,
+                "--stack-mode" => match args.next().map(|value| value.parse()) {
+                    Some(Ok(mode)) => cli.stack_mode = Some(mode),
+                    Some(Err(err)) => eprintln!("{err}"),
+                    None => eprintln!("--stack-mode expects mixed or typed"),
+                },
+                "--spawn-distribution" => match args.next().map(|value| value.parse()) {
+                    Some(Ok(distribution)) => cli.spawn_distribution = Some(distribution),
+                    Some(Err(err)) => eprintln!("{err}"),
+                    None => eprintln!("--spawn-distribution expects a distribution name"),
+                },
+                "--plant-placement" => match args.next().map(|value| value.parse()) {
+                    Some(Ok(placement)) => cli.plant_placement = Some(placement),
+                    Some(Err(err)) => eprintln!("{err}"),
+                    None => eprintln!("--plant-placement expects uniform or even"),
+                },
+                "--sensor-noise" => match args.next().and_then(|value| value.parse().ok()) {
+                    Some(sigma) if sigma >= 0.0 => cli.sensor_noise = Some(sigma),
+                    _ => eprintln!("--sensor-noise expects a non-negative number"),
+                },
+                "--scenario" => match args.next().map(|value| Scenario::load(&value)) {
+                    Some(Ok(scenario)) => cli.scenario = Some(scenario),
+                    Some(Err(err)) => eprintln!("Failed to load scenario: {err}"),
+                    None => eprintln!(
+                        "--scenario expects a file or one of: {}",
+                        Scenario::bundled_names().collect::<Vec<_>>().join(", ")
+                    ),
+                },
+                "--benchmark" => cli.benchmark = true,
+                "--tour" => match args.next().map(|value| CameraTour::load(value.as_ref())) {
+                    Some(Ok(tour)) => cli.tour = Some(tour),
+                    Some(Err(err)) => eprintln!("Failed to load camera tour: {err}"),
+                    None => eprintln!("--tour expects a file"),
+                },
+                _ => eprintln!("Ignoring unknown argument: {arg}"),
+            }
+        }
+
+        // Benchmark the standard scenario unless another one was given
+        if cli.benchmark && cli.scenario.is_none() {
+            match Scenario::load("benchmark") {
+                Ok(scenario) => cli.scenario = Some(scenario),
+                Err(err) => eprintln!("Failed to load benchmark scenario: {err}"),
+            }
+        }
+        cli
+    }
+}
+
+/// Startup system to act on command-line options
+#[allow(clippy::too_many_arguments)]
+pub fn apply_cli_args(
+    cli: Res<CliArgs>,
+    mut vm_settings: ResMut<VmSettings>,
+    mut spawn_settings: ResMut<SpawnSettings>,
+    mut plant_config: ResMut<PlantConfig>,
+    mut sensor_noise: ResMut<SensorNoise>,
+    mut imports: EventWriter<ImportGenomes>,
+    mut tour: ResMut<CameraTour>,
+    camera_state: Res<CameraState>,
+) {
+    if let Some(capacity) = cli.stack_capacity {
+        vm_settings.stack_capacity = capacity;
+    }
+    if let Some(policy) = cli.overflow_policy {
+        vm_settings.overflow_policy = policy;
+    }
+    if cli.safe_math {
+        vm_settings.safe_math = true;
+    }
+    if let Some(mode) = cli.stack_mode {
+        vm_settings.stack_mode = mode;
+    }
+    if let Some(distribution) = cli.spawn_distribution {
+        spawn_settings.distribution = distribution;
+    }
+    if let Some(placement) = cli.plant_placement {
+        plant_config.placement = placement;
+    }
+    if let Some(sigma) = cli.sensor_noise {
+        sensor_noise.sigma = sigma;
+    }
+    if let Some(cli_tour) = &cli.tour {
+        *tour = cli_tour.clone();
+        tour.play(camera_state.position, camera_state.zoom);
+    }
+    if let Some(path) = &cli.import {
+        imports.send(ImportGenomes {
+            path: path.clone(),
+            copies: cli.copies,
+            center: Vec2::ZERO,
+        });
+    }
+}
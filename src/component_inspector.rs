@@ -0,0 +1,130 @@
+use crate::dock::{DockLayout, DockTab};
+use crate::selection::SelectedEntity;
+use bevy::ecs::reflect::ReflectComponent;
+use bevy::prelude::*;
+use bevy::reflect::{PartialReflect, ReflectMut};
+use bevy::window::PrimaryWindow;
+use bevy_egui::{EguiContext, egui};
+
+/// System to show every reflected component of the selected entity. The handcrafted
+/// inspector only knows the components it was written for; this generic view keeps new
+/// ones inspectable too. Numbers and flags can be edited, anything else is shown with
+/// its debug formatting
+pub fn component_inspector_ui(world: &mut World) {
+    let Some(entity) = world.resource::<SelectedEntity>().entity else {
+        return;
+    };
+    let Ok(mut egui_context) = world
+        .query_filtered::<&mut EguiContext, With<PrimaryWindow>>()
+        .get_single_mut(world)
+    else {
+        return;
+    };
+    let ctx = egui_context.get_mut().clone();
+    let dock = *world.resource::<DockLayout>();
+    let registry = world.resource::<AppTypeRegistry>().clone();
+    let registry = registry.read();
+
+    let Ok(entity_ref) = world.get_entity(entity) else {
+        return;
+    };
+    let mut components: Vec<(&str, &ReflectComponent)> = entity_ref
+        .archetype()
+        .components()
+        .filter_map(|id| {
+            let registration = registry.get(world.components().get_info(id)?.type_id()?)?;
+            Some((
+                registration.type_info().type_path_table().short_path(),
+                registration.data::<ReflectComponent>()?,
+            ))
+        })
+        .collect();
+    components.sort_by_key(|(name, _)| *name);
+
+    dock.show(
+        &ctx,
+        DockTab::Components,
+        egui::Window::new("Components")
+            .default_pos(egui::pos2(700.0, 300.0))
+            .default_width(300.0)
+            .default_open(false),
+        |ui| {
+            ui.label(format!("{} ({} reflected)", entity, components.len()));
+            for (name, reflect) in components {
+                egui::CollapsingHeader::new(name)
+                    .id_salt(name)
+                    .show(ui, |ui| {
+                        let Some(mut component) = reflect.reflect_mut(world.entity_mut(entity))
+                        else {
+                            return;
+                        };
+                        // Only flag the component as changed when a field was edited
+                        let edited = edit_fields(
+                            ui,
+                            name,
+                            component.bypass_change_detection().as_partial_reflect_mut(),
+                        );
+                        if edited {
+                            component.set_changed();
+                        }
+                    });
+            }
+        },
+    );
+}
+
+/// Show the fields of a reflected struct, or the value itself for anything else.
+/// Returns whether anything was edited
+fn edit_fields(ui: &mut egui::Ui, label: &str, value: &mut dyn PartialReflect) -> bool {
+    match value.reflect_mut() {
+        ReflectMut::Struct(fields) if fields.field_len() > 0 => {
+            let mut edited = false;
+            for index in 0..fields.field_len() {
+                let name = fields.name_at(index).unwrap_or_default().to_string();
+                if let Some(field) = fields.field_at_mut(index) {
+                    edited |= edit_value(ui, &name, field);
+                }
+            }
+            edited
+        }
+        // Newtypes such as `BodyHue(f32)` edit their single field in place
+        ReflectMut::TupleStruct(fields) if fields.field_len() == 1 => fields
+            .field_mut(0)
+            .is_some_and(|field| edit_value(ui, label, field)),
+        _ => edit_value(ui, label, value),
+    }
+}
+
+/// Show a reflected value labeled `label`: an editor for numbers and flags, nested
+/// fields for structs and the debug formatting for anything else. Returns whether it
+/// was edited
+fn edit_value(ui: &mut egui::Ui, label: &str, value: &mut dyn PartialReflect) -> bool {
+    if let Some(number) = value.try_downcast_mut::<f32>() {
+        ui.horizontal(|ui| {
+            ui.label(label);
+            ui.add(egui::DragValue::new(number).speed(0.1)).changed()
+        })
+        .inner
+    } else if let Some(number) = value.try_downcast_mut::<u32>() {
+        ui.horizontal(|ui| {
+            ui.label(label);
+            ui.add(egui::DragValue::new(number)).changed()
+        })
+        .inner
+    } else if let Some(number) = value.try_downcast_mut::<u64>() {
+        ui.horizontal(|ui| {
+            ui.label(label);
+            ui.add(egui::DragValue::new(number)).changed()
+        })
+        .inner
+    } else if let Some(flag) = value.try_downcast_mut::<bool>() {
+        ui.checkbox(flag, label).changed()
+    } else if let ReflectMut::Struct(_) = value.reflect_mut() {
+        ui.collapsing(label, |ui| edit_fields(ui, label, value))
+            .body_returned
+            .unwrap_or(false)
+    } else {
+        ui.label(format!("{}: {:?}", label, value));
+        false
+    }
+}
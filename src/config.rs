@@ -1,4 +1,4 @@
-/// Configuration constants for the evolution simulator
+//! Configuration constants for the evolution simulator
 
 // ============================================================================
 // GENOME SETTINGS
@@ -7,18 +7,127 @@
 /// Number of instructions in a newly generated genome
 pub const BASE_GENOME_LENGTH: usize = 100;
 
-/// Mutation rate: 1% chance per instruction to be replaced with random instruction
-pub const MUTATION_RATE: u32 = 1;
+/// Initial mutation rate: 1% chance per instruction to be replaced with random instruction
+/// (each genome carries and evolves its own rates)
+pub const MUTATION_RATE: f32 = 1.0;
 
-/// Duplication rate: 1% chance per instruction to be duplicated (inserted after)
-pub const DUPLICATION_RATE: u32 = 1;
+/// Initial duplication rate: 1% chance per instruction to be duplicated (inserted after)
+pub const DUPLICATION_RATE: f32 = 1.0;
 
-/// Deletion rate: 1% chance per instruction to be deleted
-pub const DELETION_RATE: u32 = 1;
+/// Initial deletion rate: 1% chance per instruction to be deleted
+pub const DELETION_RATE: f32 = 1.0;
+
+/// Standard deviation of the log-normal jitter applied to a genome's own rates per split
+pub const RATE_MUTATION_SIGMA: f32 = 0.1;
+
+/// Lowest per-instruction rate (in percent) a genome can evolve
+pub const MIN_EVOLVED_RATE: f32 = 0.01;
+
+/// Highest per-instruction rate (in percent) a genome can evolve
+pub const MAX_EVOLVED_RATE: f32 = 20.0;
+
+/// Plant and meat digestion efficiencies of every genome sum to this
+pub const DIET_TOTAL_EFFICIENCY: f32 = 1.5;
+
+/// Founders' share of DIET_TOTAL_EFFICIENCY spent on plants: plants are digested fully
+/// and meat at half
+pub const DEFAULT_HERBIVORY: f32 = 2.0 / 3.0;
+
+/// Standard deviation of the gaussian jitter applied to herbivory on every split
+pub const DIET_MUTATION_SIGMA: f32 = 0.05;
+
+/// Body size of founders; 1 gives the base radius, speed, metabolism and eat reach
+pub const DEFAULT_BODY_SIZE: f32 = 1.0;
+
+/// Smallest and largest body size a genome can evolve
+pub const MIN_BODY_SIZE: f32 = 0.5;
+pub const MAX_BODY_SIZE: f32 = 2.0;
+
+/// Standard deviation of the log-normal noise applied to body size on every split
+pub const BODY_SIZE_MUTATION_SIGMA: f32 = 0.05;
+
+/// Block duplication rate: 2% chance per offspring to copy a run of words in place
+pub const BLOCK_DUPLICATION_RATE: u32 = 2;
+
+/// Block deletion rate: 2% chance per offspring to remove a run of words
+pub const BLOCK_DELETION_RATE: u32 = 2;
+
+/// Insertion rate: 2% chance per offspring to splice in a run of brand new random words
+pub const INSERTION_RATE: u32 = 2;
+
+/// Longest run of new random words a single insertion adds
+pub const INSERTION_MAX_LENGTH: usize = 3;
+
+/// Shortest run of words a block duplication or deletion acts on
+pub const BLOCK_MIN_LENGTH: usize = 2;
+
+/// Longest run of words a block duplication or deletion acts on
+pub const BLOCK_MAX_LENGTH: usize = 20;
+
+/// Inversion rate: 5% chance per offspring to reverse a random run of words
+pub const INVERSION_RATE: u32 = 5;
+
+/// Longest run of words a single inversion reverses
+pub const INVERSION_MAX_LENGTH: usize = 20;
+
+/// Translocation rate: 5% chance per offspring to move a random run of words elsewhere
+pub const TRANSLOCATION_RATE: u32 = 5;
+
+/// Longest run of words a single translocation moves
+pub const TRANSLOCATION_MAX_LENGTH: usize = 20;
+
+/// Chance per check that a touching animal copies a segment of its neighbour's genome
+pub const GENE_TRANSFER_PROBABILITY: f32 = 0.05;
+
+/// Interval in seconds between horizontal gene transfer checks
+pub const GENE_TRANSFER_INTERVAL: f32 = 1.0;
+
+/// Longest genome segment copied by a single horizontal gene transfer
+pub const GENE_TRANSFER_MAX_LENGTH: usize = 8;
+
+/// Most word edits from a species' founding genome that still count as that species
+pub const SPECIES_DISTANCE_THRESHOLD: usize = 10;
+
+/// Interval in seconds between re-clustering living genomes into species
+pub const SPECIES_CLUSTER_INTERVAL: f32 = 5.0;
+
+/// Most words an offspring genome may have; longer ones are cut back when mutated
+pub const MAX_GENOME_LENGTH: usize = 500;
+
+/// Share of random words drawn from the words added after the original vocabulary, so
+/// new words never dilute the original mix
+pub const EXTENDED_WORD_SHARE: f64 = 0.1;
+
+/// Number of entries in each genome's constant pool (read by PushConst)
+pub const CONSTANT_POOL_SIZE: usize = 8;
+
+/// Constant mutation rate: 10% chance per constant to receive gaussian jitter
+pub const CONSTANT_MUTATION_RATE: u32 = 10;
+
+/// Standard deviation of constant jitter, relative to the constant's magnitude
+pub const CONSTANT_MUTATION_SIGMA: f32 = 0.1;
+
+/// Default maximum number of values on a genome's stack
+pub const STACK_CAPACITY: usize = 256;
+
+/// Largest magnitude an arithmetic result keeps when safe math is on
+pub const SAFE_MATH_LIMIT: f32 = 1.0e6;
 
 /// Energy cost to execute the Split instruction
 pub const SPLIT_ENERGY_COST: u32 = 10;
 
+/// Extra split cost per genome word, so bloated genomes pay more to copy (0 disables)
+pub const SPLIT_COST_PER_WORD: f32 = 0.0;
+
+/// Share of the parent's remaining energy given to the offspring when no SplitRatio was set
+pub const DEFAULT_SPLIT_RATIO: f32 = 0.5;
+
+/// Smallest offspring share a SplitRatio word can request
+pub const MIN_SPLIT_RATIO: f32 = 0.1;
+
+/// Largest offspring share a SplitRatio word can request
+pub const MAX_SPLIT_RATIO: f32 = 0.9;
+
 // ============================================================================
 // SPAWN SETTINGS
 // ============================================================================
@@ -32,9 +141,31 @@ pub const STARTING_ANIMAL_ENERGY: u32 = 10;
 /// Number of animals respawned by failsafe when population reaches zero
 pub const FAILSAFE_RESPAWN_COUNT: usize = 500;
 
+/// Population at or below which the failsafe respawns animals
+pub const FAILSAFE_THRESHOLD: usize = 0;
+
+/// Minimum simulation ticks between two failsafe respawns
+pub const FAILSAFE_COOLDOWN_TICKS: u64 = 600;
+
+/// Length of the genomes the failsafe generates when respawning random animals
+pub const FAILSAFE_RANDOM_GENOME_LENGTH: usize = 64;
+
 /// Number of animals spawned by manual spawn button
 pub const MANUAL_SPAWN_COUNT: usize = 500;
 
+/// Most entities (offspring, plants, seedlings and queued animals) spawned per tick
+pub const MAX_SPAWNS_PER_TICK: u32 = 50;
+
+/// Width of the band seed animals are scattered in under the ring distribution (the ring's
+/// radius is ANIMAL_SPAWN_RANGE)
+pub const SPAWN_RING_THICKNESS: f32 = 20.0;
+
+/// Standard deviation of seed animal positions around the point of the cluster distribution
+pub const SPAWN_CLUSTER_SPREAD: f32 = 30.0;
+
+/// Depth of the strip along the west edge of the spawn area used by the edge distribution
+pub const SPAWN_EDGE_DEPTH: f32 = 20.0;
+
 // ============================================================================
 // METABOLISM & TIMING
 // ============================================================================
@@ -42,9 +173,18 @@ pub const MANUAL_SPAWN_COUNT: usize = 500;
 /// Interval in seconds between metabolism ticks (energy drain)
 pub const METABOLISM_INTERVAL: f32 = 1.0;
 
-/// Energy drained from each animal per metabolism tick
+/// Energy drained from each animal per metabolism tick (scaled by body size)
 pub const METABOLISM_COST: u32 = 1;
 
+/// Extra metabolism cost per genome word, so bloated genomes burn more (0 disables)
+pub const METABOLISM_COST_PER_WORD: f32 = 0.0;
+
+/// Animals with less energy than this are starving (outlined, and sensed by Starving)
+pub const STARVATION_THRESHOLD: u32 = 5;
+
+/// Instructions per frame for starving animals when budget reduction is enabled
+pub const STARVATION_INSTRUCTION_BUDGET: u32 = 3;
+
 /// Maximum lifespan of an animal in seconds (animals die when age >= this value)
 pub const MAX_LIFESPAN: f32 = 60.0;
 
@@ -60,6 +200,64 @@ pub const PLANT_GROWTH_AMOUNT: u32 = 1;
 /// Maximum energy a plant can store
 pub const PLANT_MAX_ENERGY: u32 = 100;
 
+/// Radius of a plant drawn at scale 1 (plants scale from 0.5 to 1.5 with their energy)
+pub const PLANT_RADIUS: f32 = 8.0;
+
+/// Lower edge of the plant:animal ratio band the plant governor aims for
+pub const GOVERNOR_TARGET_RATIO_MIN: f32 = 2.0;
+
+/// Upper edge of the plant:animal ratio band the plant governor aims for
+pub const GOVERNOR_TARGET_RATIO_MAX: f32 = 5.0;
+
+/// Interval in seconds between plant governor adjustments
+pub const GOVERNOR_INTERVAL: f32 = 5.0;
+
+/// Fraction by which the governor changes the plant spawn interval per adjustment
+pub const GOVERNOR_STEP: f32 = 0.1;
+
+/// Shortest plant spawn interval the governor may set
+pub const GOVERNOR_MIN_SPAWN_INTERVAL: f32 = 0.1;
+
+/// Longest plant spawn interval the governor may set
+pub const GOVERNOR_MAX_SPAWN_INTERVAL: f32 = 10.0;
+
+/// Chance that eating a plant leaves a seed in the animal's gut
+pub const SEED_DISPERSAL_PROBABILITY: f32 = 0.1;
+
+/// Ticks a swallowed seed spends in the gut before it is dropped as a seedling
+pub const SEED_DISPERSAL_DELAY_TICKS: u32 = 300;
+
+/// Share of plants eaten to nothing that regrow where they stood
+pub const PLANT_REGROWTH_SHARE: f32 = 0.5;
+
+/// Ticks before a depleted plant's site sprouts again
+pub const PLANT_REGROWTH_DELAY_TICKS: u32 = 600;
+
+/// Random candidates weighed per plant under even placement; more spread plants more
+/// evenly at the cost of more distance checks
+pub const PLANT_PLACEMENT_CANDIDATES: usize = 10;
+
+/// Distance within which mature plants shade a plant's growth
+pub const PLANT_SHADING_RADIUS: f32 = 40.0;
+
+/// Growth lost per shading neighbour: a plant grows with chance 1 / (1 + strength × neighbours)
+pub const PLANT_SHADING_STRENGTH: f32 = 0.5;
+
+/// Energy from which a plant counts as mature and shades its neighbours
+pub const PLANT_MATURE_ENERGY: u32 = 50;
+
+/// Simulated seconds per tick while fast-forwarding
+pub const FAST_FORWARD_TICK_SECONDS: f32 = 1.0 / 60.0;
+
+/// Real seconds spent fast-forwarding per rendered frame (keeps the UI responsive)
+pub const FAST_FORWARD_FRAME_BUDGET: f32 = 0.1;
+
+/// Number of ticks offered by default in the fast-forward control
+pub const FAST_FORWARD_DEFAULT_TICKS: u32 = 10_000;
+
+/// Random seed of `--benchmark` runs, so the same build always scores the same
+pub const BENCHMARK_SEED: u64 = 1;
+
 // ============================================================================
 // WORLD & INTERACTION SETTINGS
 // ============================================================================
@@ -73,9 +271,142 @@ pub const ANIMAL_SPAWN_RANGE: f32 = 200.0;
 /// Maximum distance at which an animal can eat a plant
 pub const EAT_DISTANCE: f32 = 10.0;
 
+/// Radius of an animal's body at size 1
+pub const ANIMAL_RADIUS: f32 = 10.0;
+
+/// Distance at which contact sensors report touching another animal (plants are touched
+/// within the animal's own eat distance, so "touching a plant" means an Eat will succeed)
+pub const CONTACT_DISTANCE: f32 = EAT_DISTANCE;
+
+/// Distance below which a plant's scent intensity stops growing, so a plant right on top
+/// of an animal does not smell infinitely strong
+pub const SCENT_MIN_DISTANCE: f32 = 1.0;
+
+/// Farthest an animal can smell plants, animals and corpses, or sense the nearest
+/// animal and plant
+pub const SMELL_RANGE: f32 = 400.0;
+
+/// Farthest a call made with the call word carries
+pub const HEARING_RANGE: f32 = 500.0;
+
+/// Default relative standard deviation of sensor noise; 0 leaves perception exact
+pub const SENSOR_NOISE: f32 = 0.0;
+
+/// Largest relative standard deviation of sensor noise offered in the UI
+pub const MAX_SENSOR_NOISE: f32 = 1.0;
+
+/// Side length of a cell of the pheromone grid
+pub const PHEROMONE_CELL_SIZE: f32 = 20.0;
+
+/// Fraction of a pheromone cell's contents shared with its neighbours per tick
+pub const PHEROMONE_DIFFUSION: f32 = 0.1;
+
+/// Fraction of the pheromone grid's contents that evaporates per tick
+pub const PHEROMONE_DECAY: f32 = 0.005;
+
+/// Ticks between passes of pheromone diffusion; readings are blended in between
+pub const PHEROMONE_UPDATE_INTERVAL: u32 = 1;
+
+/// Most pheromone a single grid cell can hold
+pub const PHEROMONE_MAX: f32 = 100.0;
+
+/// Most pheromone a single deposit-pheromone word can add
+pub const PHEROMONE_MAX_DEPOSIT: f32 = 10.0;
+
+/// Side length of a cell of the animal trail map
+pub const TRAIL_CELL_SIZE: f32 = 10.0;
+
+/// Fraction of the trail map's visit counts that fades per tick
+pub const TRAIL_DECAY: f32 = 0.001;
+
+/// Seconds between redraws of the trail overlay
+pub const TRAIL_REDRAW_INTERVAL: f32 = 0.5;
+
+/// Opacity (out of 255) of the most worn trail cell
+pub const TRAIL_MAX_ALPHA: f32 = 200.0;
+
+/// Seconds the scent of a dead animal lingers where it died
+pub const CORPSE_SCENT_DURATION: f32 = 20.0;
+
+/// Energy a fresh corpse counts as towards intensity smell; it weakens as the scent fades
+pub const CORPSE_SCENT_STRENGTH: u32 = 100;
+
+/// Width of the interaction range rings drawn around the selected animal
+pub const INTERACTION_RING_WIDTH: f32 = 1.5;
+
+/// Radius within which the crowding sensor counts other animals
+pub const CROWDING_RADIUS: f32 = 100.0;
+
+/// Farthest an animal can see along its vision cone
+pub const VISION_RANGE: f32 = 300.0;
+
+/// Half-width in degrees of the cone in front of an animal that it sees into
+pub const VISION_HALF_ANGLE: f32 = 15.0;
+
 /// Maximum energy transferred from plant to animal per eat action
 pub const EAT_AMOUNT: u32 = 20;
 
+/// Radius of the area wiped out by a meteor strike
+pub const METEOR_RADIUS: f32 = 150.0;
+
+/// Seconds a meteor crater stays scorched (nothing spawns inside it)
+pub const SCORCH_DURATION: f32 = 30.0;
+
+/// Distance past a scorched zone's edge that founders queued inside it are moved to
+pub const SCORCH_ESCAPE_MARGIN: f32 = 10.0;
+
+/// Seconds a death marker takes to fade away
+pub const DEATH_MARKER_DURATION: f32 = 3.0;
+
+/// Length of each stroke of a death marker's X
+pub const DEATH_MARKER_SIZE: f32 = 10.0;
+
+/// Mean interval in seconds between random meteor strikes (when enabled)
+pub const METEOR_RANDOM_INTERVAL: f32 = 120.0;
+
+/// Maximum distance at which the Attack word can reach another animal
+pub const ATTACK_DISTANCE: f32 = 20.0;
+
+/// Maximum energy drained from the target per attack
+pub const ATTACK_DAMAGE: u32 = 10;
+
+/// Energy cost for the attacker each time the Attack word executes
+pub const ATTACK_ENERGY_COST: u32 = 1;
+
+/// Most energy one animal can lose to other animals' interactions per tick
+pub const INTERACTION_MAX_LOSS_PER_TICK: u32 = 20;
+
+/// Most energy one animal can gain from interactions with other animals per tick
+pub const INTERACTION_MAX_GAIN_PER_TICK: u32 = 10;
+
+/// Maximum distance to a partner for sexual or mate-required reproduction
+pub const MATE_DISTANCE: f32 = 40.0;
+
+/// Whether splitting needs a partner within MATE_DISTANCE by default
+pub const MATE_REQUIRED: bool = false;
+
+/// Energy a partner gives to the offspring when reproduction requires a mate
+pub const MATE_ENERGY_COST: u32 = 5;
+
+/// Whether sexual partners must have similar genomes to recombine by default
+pub const HYBRID_INCOMPATIBILITY: bool = false;
+
+/// Most word edits apart two genomes can be and still recombine when hybrid
+/// incompatibility is on; more distant pairs reproduce asexually
+pub const CROSSOVER_MAX_DISTANCE: usize = SPECIES_DISTANCE_THRESHOLD;
+
+/// Farthest a newborn lands from its parent under scatter dispersal
+pub const OFFSPRING_SCATTER_RADIUS: f32 = 20.0;
+
+/// Distance a newborn is pushed away from its parent under push dispersal
+pub const OFFSPRING_PUSH_DISTANCE: f32 = 15.0;
+
+/// Maximum distance at which the Grab word can pick up a plant
+pub const GRAB_DISTANCE: f32 = EAT_DISTANCE;
+
+/// Distance in front of the carrier at which a carried plant is held
+pub const CARRY_OFFSET: f32 = 12.0;
+
 /// Maximum distance for selecting entities with mouse
 pub const SELECTION_RADIUS: f32 = 20.0;
 
@@ -86,8 +417,134 @@ pub const SELECTION_RADIUS: f32 = 20.0;
 /// Maximum movement speed per instruction (distance units)
 pub const MAX_MOVEMENT_SPEED: f32 = 0.5;
 
+/// Maximum sideways (strafe) movement speed per instruction (distance units)
+pub const MAX_STRAFE_SPEED: f32 = 0.25;
+
 /// Maximum rotation speed per instruction (degrees)
 pub const MAX_ANGULAR_VELOCITY: f32 = 5.0;
 
 /// Maximum number of instructions an animal can execute per frame (prevents high-energy animals from moving too fast)
 pub const MAX_INSTRUCTIONS_PER_FRAME: u32 = 10;
+
+// ============================================================================
+// UI SETTINGS
+// ============================================================================
+
+/// Closest and furthest camera zoom (projection scale)
+pub const CAMERA_MIN_ZOOM: f32 = 0.1;
+pub const CAMERA_MAX_ZOOM: f32 = 10.0;
+
+/// Factor the zoom changes by per press of + or -
+pub const CAMERA_ZOOM_STEP: f32 = 1.25;
+
+/// Share of the visible height moved per press of PageUp or PageDown
+pub const CAMERA_PAGE_PAN: f32 = 0.8;
+
+/// Default seconds a camera tour spends gliding to each stop
+pub const CAMERA_TOUR_TRANSITION: f32 = 3.0;
+
+/// Default seconds a camera tour stays at each stop
+pub const CAMERA_TOUR_DWELL: f32 = 10.0;
+
+/// File in OUTPUT_DIR the camera tour is saved to from the UI
+pub const CAMERA_TOUR_FILE: &str = "camera_tour.ron";
+
+/// Saturation of animal bodies; the hue comes from the genome
+pub const ANIMAL_SATURATION: f32 = 0.75;
+
+/// Lightness of animal bodies
+pub const ANIMAL_LIGHTNESS: f32 = 0.55;
+
+/// Number of executed instructions kept in an animal's execution trace
+pub const EXECUTION_TRACE_LENGTH: usize = 200;
+
+/// Seconds the Genome Viewer shows a selected animal's split before following the offspring
+pub const SPLIT_PLAYBACK_DURATION: f32 = 3.0;
+
+/// Maximum number of animals that can be pinned to the watchlist
+pub const WATCHLIST_MAX_PINNED: usize = 4;
+
+/// Interval in seconds between watchlist energy samples
+pub const WATCHLIST_SAMPLE_INTERVAL: f32 = 0.25;
+
+/// Number of energy samples kept per pinned animal
+pub const WATCHLIST_HISTORY_LENGTH: usize = 240;
+
+/// Interval in seconds between population chart samples
+pub const CHART_SAMPLE_INTERVAL: f32 = 1.0;
+
+/// Size of the grid cells used by the exploration-area fitness metric
+pub const EXPLORATION_CELL_SIZE: f32 = 50.0;
+
+/// Seconds over which energy flows are totalled before the dashboard updates
+pub const ENERGY_FLOW_INTERVAL: f32 = 1.0;
+
+/// Times per real second the census, charts and genome viewer rebuild their contents
+pub const UI_REFRESH_RATE: f32 = 10.0;
+
+/// Range offered for the UI refresh rate
+pub const UI_REFRESH_MIN_RATE: f32 = 1.0;
+pub const UI_REFRESH_MAX_RATE: f32 = 60.0;
+
+/// Number of samples kept in the population charts
+pub const CHART_HISTORY_LENGTH: usize = 600;
+
+/// Directory where run artifacts (screenshots, exports) are written
+pub const OUTPUT_DIR: &str = "output";
+
+/// Directory in OUTPUT_DIR holding one output directory per run, named after the run
+pub const RUN_OUTPUT_DIR: &str = "runs";
+
+/// File in OUTPUT_DIR that remembers the panel layout between runs
+pub const DOCK_LAYOUT_FILE: &str = "ui_layout.txt";
+
+/// Default distance between coordinate grid lines
+pub const GRID_SPACING: f32 = 100.0;
+
+/// Most grid lines drawn across the view; spacing doubles until it fits
+pub const GRID_MAX_LINES: f32 = 40.0;
+
+/// Initial width of the docked panel
+pub const DOCK_PANEL_WIDTH: f32 = 420.0;
+
+/// Default number of mutated copies generated by the genome viewer's mutation preview
+pub const MUTATION_PREVIEW_COUNT: usize = 5;
+
+/// File extension of genome source files read by the importer
+pub const GENOME_FILE_EXTENSION: &str = "gen";
+
+/// File extension of banked genomes, saved as RON with all their heritable traits
+pub const GENOME_BANK_EXTENSION: &str = "ron";
+
+/// Directory in OUTPUT_DIR holding each run's banked genomes
+pub const GENOME_BANK_DIR: &str = "genome_bank";
+
+/// Directory in a run's genome bank holding the periodic population samples
+pub const POPULATION_SAMPLE_DIR: &str = "samples";
+
+/// Ticks between population samples (when enabled)
+pub const POPULATION_SAMPLE_INTERVAL: u64 = 1000;
+
+/// Number of random living genomes written per population sample
+pub const POPULATION_SAMPLE_SIZE: usize = 10;
+
+/// Distance between animals spawned in a grid by the genome importer
+pub const IMPORT_GRID_SPACING: f32 = 30.0;
+
+/// Seconds by which the oldest animal must beat the lifespan record to count as a milestone
+pub const LIFESPAN_RECORD_STEP: f32 = 10.0;
+
+/// Number of genomes kept in the hall of fame
+pub const HALL_OF_FAME_SIZE: usize = 20;
+
+/// Age in seconds at which an animal qualifies for the hall of fame
+pub const HALL_OF_FAME_MIN_AGE: f32 = 30.0;
+
+/// Number of offspring at which an animal qualifies for the hall of fame
+pub const HALL_OF_FAME_MIN_OFFSPRING: u32 = 5;
+
+/// Lifetime energy eaten at which an animal qualifies for the hall of fame
+pub const HALL_OF_FAME_MIN_ENERGY_EATEN: u64 = 500;
+
+/// Interval in seconds between hall of fame snapshots
+pub const HALL_OF_FAME_INTERVAL: f32 = 1.0;
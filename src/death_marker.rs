@@ -0,0 +1,87 @@
+use crate::animal::AnimalDied;
+use crate::config::*;
+use bevy::prelude::*;
+
+/// Settings for the fading X left where each animal dies
+#[derive(Resource)]
+pub struct DeathMarkers {
+    pub enabled: bool,
+}
+
+impl Default for DeathMarkers {
+    fn default() -> Self {
+        Self { enabled: true }
+    }
+}
+
+/// Decal marking where an animal died, faded out and removed when its timer runs out
+#[derive(Component)]
+pub struct DeathMarker {
+    /// Shared by both strokes of the X so they fade together
+    pub material: Handle<ColorMaterial>,
+    pub timer: Timer,
+}
+
+/// System to leave a marker at each death location
+pub fn spawn_death_markers(
+    mut commands: Commands,
+    settings: Res<DeathMarkers>,
+    mut deaths: EventReader<AnimalDied>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut stroke: Local<Option<Handle<Mesh>>>,
+) {
+    if !settings.enabled {
+        deaths.clear();
+        return;
+    }
+
+    for death in deaths.read() {
+        let stroke = stroke
+            .get_or_insert_with(|| {
+                meshes.add(Rectangle::new(DEATH_MARKER_SIZE, DEATH_MARKER_SIZE * 0.2))
+            })
+            .clone();
+        let material = materials.add(ColorMaterial::from_color(Color::srgba(0.9, 0.1, 0.1, 0.8)));
+
+        commands
+            .spawn((
+                DeathMarker {
+                    material: material.clone(),
+                    timer: Timer::from_seconds(DEATH_MARKER_DURATION, TimerMode::Once),
+                },
+                // Above scorched ground, below living animals and plants
+                Transform::from_xyz(death.position.x, death.position.y, -0.4),
+                Visibility::default(),
+            ))
+            .with_children(|marker| {
+                for angle in [45.0f32, -45.0] {
+                    marker.spawn((
+                        Mesh2d(stroke.clone()),
+                        MeshMaterial2d(material.clone()),
+                        Transform::from_rotation(Quat::from_rotation_z(angle.to_radians())),
+                    ));
+                }
+            });
+    }
+}
+
+/// System to fade death markers and remove them once they have disappeared, or all at
+/// once when markers are turned off
+pub fn fade_death_markers(
+    mut commands: Commands,
+    time: Res<Time>,
+    settings: Res<DeathMarkers>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut markers: Query<(Entity, &mut DeathMarker)>,
+) {
+    for (entity, mut marker) in markers.iter_mut() {
+        if !settings.enabled || marker.timer.tick(time.delta()).finished() {
+            commands.entity(entity).despawn_recursive();
+        } else if let Some(material) = materials.get_mut(&marker.material) {
+            material
+                .color
+                .set_alpha(0.8 * marker.timer.fraction_remaining());
+        }
+    }
+}
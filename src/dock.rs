@@ -0,0 +1,151 @@
+use crate::config::*;
+use bevy::prelude::*;
+use bevy_egui::{EguiContexts, egui};
+use std::fmt;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+/// Panels that can be shown as tabs of the docked layout
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DockTab {
+    #[default]
+    Simulation,
+    Inspector,
+    Genome,
+    Charts,
+    Energy,
+    Watchlist,
+    Tour,
+    Components,
+}
+
+impl DockTab {
+    pub const ALL: [DockTab; 8] = [
+        DockTab::Simulation,
+        DockTab::Inspector,
+        DockTab::Genome,
+        DockTab::Charts,
+        DockTab::Energy,
+        DockTab::Watchlist,
+        DockTab::Tour,
+        DockTab::Components,
+    ];
+}
+
+impl fmt::Display for DockTab {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DockTab::Simulation => write!(f, "simulation"),
+            DockTab::Inspector => write!(f, "inspector"),
+            DockTab::Genome => write!(f, "genome"),
+            DockTab::Charts => write!(f, "charts"),
+            DockTab::Energy => write!(f, "energy"),
+            DockTab::Watchlist => write!(f, "watchlist"),
+            DockTab::Tour => write!(f, "tour"),
+            DockTab::Components => write!(f, "components"),
+        }
+    }
+}
+
+impl FromStr for DockTab {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        DockTab::ALL
+            .into_iter()
+            .find(|tab| tab.to_string() == s)
+            .ok_or_else(|| format!("unknown tab '{}'", s))
+    }
+}
+
+/// Resource choosing between free-floating windows and a tabbed side panel, saved to
+/// DOCK_LAYOUT_FILE whenever it changes
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DockLayout {
+    pub docked: bool,
+    pub active: DockTab,
+}
+
+impl Default for DockLayout {
+    fn default() -> Self {
+        Self {
+            docked: true,
+            active: DockTab::default(),
+        }
+    }
+}
+
+impl DockLayout {
+    fn path() -> PathBuf {
+        PathBuf::from(OUTPUT_DIR).join(DOCK_LAYOUT_FILE)
+    }
+
+    /// Layout saved by a previous run, or the default when there is none
+    pub fn load() -> Self {
+        let mut layout = Self::default();
+        let Ok(saved) = std::fs::read_to_string(Self::path()) else {
+            return layout;
+        };
+        for line in saved.lines() {
+            match line.split_once('=') {
+                Some(("docked", value)) => layout.docked = value == "true",
+                Some(("tab", value)) => layout.active = value.parse().unwrap_or_default(),
+                _ => {}
+            }
+        }
+        layout
+    }
+
+    pub fn save(&self) -> std::io::Result<()> {
+        std::fs::create_dir_all(OUTPUT_DIR)?;
+        std::fs::write(
+            Self::path(),
+            format!("docked={}\ntab={}\n", self.docked, self.active),
+        )
+    }
+
+    /// Show a panel's contents as its floating window, or in the dock when it is docked
+    /// and its tab is active
+    pub fn show(
+        &self,
+        ctx: &egui::Context,
+        tab: DockTab,
+        window: egui::Window,
+        add_contents: impl FnOnce(&mut egui::Ui),
+    ) {
+        if !self.docked {
+            window.show(ctx, add_contents);
+        } else if self.active == tab {
+            egui::SidePanel::left("dock_panel")
+                .resizable(true)
+                .default_width(DOCK_PANEL_WIDTH)
+                .show(ctx, |ui| {
+                    egui::ScrollArea::vertical().show(ui, add_contents);
+                });
+        }
+    }
+}
+
+/// System to show the dock's tab bar; runs before the panels so the side panel sits
+/// below it
+pub fn dock_tabs_ui(mut contexts: EguiContexts, mut layout: ResMut<DockLayout>) {
+    let mut edited = *layout;
+    egui::TopBottomPanel::top("dock_tabs").show(contexts.ctx_mut(), |ui| {
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut edited.docked, "Dock panels");
+            if edited.docked {
+                ui.separator();
+                for tab in DockTab::ALL {
+                    ui.selectable_value(&mut edited.active, tab, tab.to_string());
+                }
+            }
+        });
+    });
+
+    if edited != *layout {
+        *layout = edited;
+        if let Err(err) = layout.save() {
+            error!("Failed to save panel layout: {err}");
+        }
+    }
+}
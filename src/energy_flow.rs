@@ -0,0 +1,281 @@
+use crate::config::*;
+use crate::dock::{DockLayout, DockTab};
+use bevy::prelude::*;
+use bevy_egui::{EguiContexts, egui};
+use std::fmt;
+
+/// Paths along which energy enters, moves through or leaves the ecosystem
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Flow {
+    /// Sunlight growing plants
+    Solar,
+    /// Founders and scenario plants placed in the world
+    Introduction,
+    /// Energy absorbed beyond what the food held, by eaters digesting above 100%
+    Assimilation,
+    /// Plants eaten by animals (energy gained by the eater)
+    Grazing,
+    /// Animals drained by attackers (energy gained by the attacker)
+    Predation,
+    /// Animals' upkeep drained every metabolism tick
+    Metabolism,
+    /// Food energy the eater failed to absorb, including gains over the interaction cap
+    Digestion,
+    /// Energy animals spend to strike
+    Attacks,
+    /// Energy spent copying the genome on every split
+    Reproduction,
+    /// Energy still held by animals and plants when they die or are destroyed
+    Death,
+}
+
+impl Flow {
+    pub const ALL: [Flow; 10] = [
+        Flow::Solar,
+        Flow::Introduction,
+        Flow::Assimilation,
+        Flow::Grazing,
+        Flow::Predation,
+        Flow::Metabolism,
+        Flow::Digestion,
+        Flow::Attacks,
+        Flow::Reproduction,
+        Flow::Death,
+    ];
+
+    /// Whether the flow adds energy to the ecosystem
+    pub fn is_input(&self) -> bool {
+        matches!(self, Flow::Solar | Flow::Introduction | Flow::Assimilation)
+    }
+
+    /// Whether the flow removes energy from the ecosystem
+    pub fn is_loss(&self) -> bool {
+        matches!(
+            self,
+            Flow::Metabolism | Flow::Digestion | Flow::Attacks | Flow::Reproduction | Flow::Death
+        )
+    }
+}
+
+impl fmt::Display for Flow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Flow::Solar => write!(f, "solar → plants"),
+            Flow::Introduction => write!(f, "placed founders and plants"),
+            Flow::Assimilation => write!(f, "digestion surplus"),
+            Flow::Grazing => write!(f, "plants → animals"),
+            Flow::Predation => write!(f, "animals → animals"),
+            Flow::Metabolism => write!(f, "metabolism loss"),
+            Flow::Digestion => write!(f, "digestion loss"),
+            Flow::Attacks => write!(f, "attack cost"),
+            Flow::Reproduction => write!(f, "split cost"),
+            Flow::Death => write!(f, "death loss"),
+        }
+    }
+}
+
+/// Resource totalling energy moved along each flow, reported per simulated second
+#[derive(Resource)]
+pub struct EnergyFlows {
+    /// Totals for the second in progress
+    current: [u64; Flow::ALL.len()],
+    /// Totals for the last complete second
+    last_second: [u64; Flow::ALL.len()],
+    timer: Timer,
+}
+
+impl Default for EnergyFlows {
+    fn default() -> Self {
+        Self {
+            current: [0; Flow::ALL.len()],
+            last_second: [0; Flow::ALL.len()],
+            timer: Timer::from_seconds(ENERGY_FLOW_INTERVAL, TimerMode::Repeating),
+        }
+    }
+}
+
+impl EnergyFlows {
+    pub fn record(&mut self, flow: Flow, amount: u32) {
+        self.current[flow as usize] += amount as u64;
+    }
+
+    /// Record `eaten` energy taken from food of which the eater absorbed `gained`: any
+    /// shortfall is lost to digestion and any excess is assimilated
+    pub fn record_digestion(&mut self, eaten: u32, gained: u32) {
+        if gained < eaten {
+            self.record(Flow::Digestion, eaten - gained);
+        } else {
+            self.record(Flow::Assimilation, gained - eaten);
+        }
+    }
+
+    /// Energy per second along a flow, over the last complete interval
+    pub fn rate(&self, flow: Flow) -> f32 {
+        self.last_second[flow as usize] as f32 / ENERGY_FLOW_INTERVAL
+    }
+
+    /// Close the interval in progress and start a new one
+    fn roll(&mut self) {
+        self.last_second = std::mem::take(&mut self.current);
+    }
+}
+
+/// System to publish each interval's energy totals
+pub fn roll_energy_flows(time: Res<Time>, mut flows: ResMut<EnergyFlows>) {
+    if flows.timer.tick(time.delta()).just_finished() {
+        flows.roll();
+    }
+}
+
+/// System to show energy flows as bars, with inputs against losses
+pub fn energy_flow_ui(mut contexts: EguiContexts, flows: Res<EnergyFlows>, dock: Res<DockLayout>) {
+    dock.show(
+        contexts.ctx_mut(),
+        DockTab::Energy,
+        egui::Window::new("Energy Flow")
+            .default_pos(egui::pos2(320.0, 450.0))
+            .default_width(320.0)
+            .default_open(false),
+        |ui| {
+            let largest = Flow::ALL
+                .iter()
+                .map(|&flow| flows.rate(flow))
+                .fold(1.0, f32::max);
+
+            egui::Grid::new("energy_flows")
+                .num_columns(3)
+                .show(ui, |ui| {
+                    for flow in Flow::ALL {
+                        let rate = flows.rate(flow);
+                        let color = if flow.is_loss() {
+                            egui::Color32::from_rgb(230, 90, 60)
+                        } else {
+                            egui::Color32::from_rgb(100, 220, 100)
+                        };
+                        ui.label(flow.to_string());
+                        ui.monospace(format!("{:8.1}/s", rate));
+                        ui.add(
+                            egui::ProgressBar::new(rate / largest)
+                                .desired_width(120.0)
+                                .fill(color),
+                        );
+                        ui.end_row();
+                    }
+                });
+
+            ui.separator();
+            let inputs: f32 = Flow::ALL
+                .iter()
+                .filter(|flow| flow.is_input())
+                .map(|&flow| flows.rate(flow))
+                .sum();
+            let losses: f32 = Flow::ALL
+                .iter()
+                .filter(|flow| flow.is_loss())
+                .map(|&flow| flows.rate(flow))
+                .sum();
+            let balance = inputs - losses;
+            let color = if balance >= 0.0 {
+                egui::Color32::from_rgb(100, 220, 100)
+            } else {
+                egui::Color32::from_rgb(230, 90, 60)
+            };
+            ui.colored_label(color, format!("Inputs minus losses: {:+.1}/s", balance))
+                .on_hover_text(
+                    "Negative for long means animals are burning energy faster than it comes in",
+                );
+        },
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::animal::Animal;
+    use crate::cli::CliArgs;
+    use crate::fast_forward::FastForward;
+    use crate::plant::Plant;
+    use crate::scenario::Scenario;
+
+    #[test]
+    fn flows_are_reported_after_the_interval_closes() {
+        let mut flows = EnergyFlows::default();
+        flows.record(Flow::Solar, 30);
+        flows.record(Flow::Solar, 12);
+        flows.record(Flow::Death, 5);
+        assert_eq!(flows.rate(Flow::Solar), 0.0);
+
+        flows.roll();
+        assert_eq!(flows.rate(Flow::Solar), 42.0 / ENERGY_FLOW_INTERVAL);
+        assert_eq!(flows.rate(Flow::Death), 5.0 / ENERGY_FLOW_INTERVAL);
+        assert_eq!(flows.rate(Flow::Grazing), 0.0);
+
+        flows.roll();
+        assert_eq!(flows.rate(Flow::Solar), 0.0);
+    }
+
+    /// Grazers, hunters and a plant-rich patch close enough for every flow to move
+    const CROWDED_PATCH: &str = r#"(
+        config: (meteors: Some(false), seed: Some(3)),
+        animals: [
+            (position: (0.0, 0.0), energy: 80, genome: Some("eat split"), herbivory: Some(1.0)),
+            (position: (6.0, 0.0), energy: 80, genome: Some("attack eat split"), herbivory: Some(0.0)),
+            (position: (0.0, 6.0), energy: 80, genome: Some("attack attack eat"), herbivory: Some(0.5)),
+            (position: (-6.0, 0.0), energy: 80, genome: Some("eat attack split")),
+        ],
+        plants: [
+            (position: (0.0, 0.0), energy: 100),
+            (position: (4.0, 4.0), energy: 100),
+            (position: (-4.0, 4.0), energy: 60),
+        ],
+    )"#;
+
+    #[test]
+    fn flows_account_for_every_change_in_total_energy() {
+        let mut cli = CliArgs::parse([]);
+        cli.scenario = Some(Scenario::parse(CROWDED_PATCH).unwrap());
+        let mut app = crate::headless_app(cli);
+        // Fewer ticks than one flow interval, so nothing has been rolled away
+        app.world_mut().resource_mut::<FastForward>().start(40);
+        while app.world().resource::<FastForward>().is_active() {
+            app.update();
+        }
+
+        let world = app.world_mut();
+        let animals: u64 = world
+            .query::<&Animal>()
+            .iter(world)
+            .map(|animal| animal.energy as u64)
+            .sum();
+        let plants: u64 = world
+            .query::<&Plant>()
+            .iter(world)
+            .map(|plant| plant.energy as u64)
+            .sum();
+
+        // The world started empty, so what came in minus what left is what it holds
+        let flows = world.resource::<EnergyFlows>();
+        let total = |keep: fn(&Flow) -> bool| -> u64 {
+            Flow::ALL
+                .iter()
+                .filter(|flow| keep(flow))
+                .map(|&flow| flows.current[flow as usize])
+                .sum()
+        };
+        assert_eq!(
+            total(Flow::is_input) - total(Flow::is_loss),
+            animals + plants
+        );
+
+        for flow in [
+            Flow::Grazing,
+            Flow::Predation,
+            Flow::Digestion,
+            Flow::Assimilation,
+            Flow::Attacks,
+            Flow::Reproduction,
+        ] {
+            assert!(flows.current[flow as usize] > 0, "{flow} never moved");
+        }
+    }
+}
@@ -0,0 +1,91 @@
+use crate::SimulationState;
+use crate::camera::MainCamera;
+use crate::config::*;
+use crate::spawning::SpawnBudget;
+use bevy::ecs::schedule::ScheduleLabel;
+use bevy::prelude::*;
+use bevy::utils::Instant;
+use std::time::Duration;
+
+/// Schedule holding every system that advances the simulation by one tick
+#[derive(ScheduleLabel, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SimulationStep;
+
+/// Resource tracking a running fast-forward
+#[derive(Resource, Default)]
+pub struct FastForward {
+    pub total: u32,
+    pub remaining: u32,
+}
+
+impl FastForward {
+    pub fn start(&mut self, ticks: u32) {
+        self.total = ticks;
+        self.remaining = ticks;
+    }
+
+    pub fn cancel(&mut self) {
+        self.remaining = 0;
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.remaining > 0
+    }
+
+    /// Fraction of the requested ticks already simulated
+    pub fn progress(&self) -> f32 {
+        if self.total == 0 {
+            1.0
+        } else {
+            (self.total - self.remaining) as f32 / self.total as f32
+        }
+    }
+}
+
+/// Exclusive system that runs the simulation schedule: once per frame while running, or
+/// as many fixed-length ticks as fit in the frame budget while fast-forwarding
+pub fn run_simulation(world: &mut World) {
+    if !world.resource::<FastForward>().is_active() {
+        set_world_rendering(world, true);
+        if *world.resource::<SimulationState>() == SimulationState::Running {
+            run_tick(world);
+        }
+        return;
+    }
+
+    // Drawing thousands of entities is wasted work while fast-forwarding; the UI keeps
+    // rendering so the progress bar stays visible
+    set_world_rendering(world, false);
+
+    let started = Instant::now();
+    let budget = Duration::from_secs_f32(FAST_FORWARD_FRAME_BUDGET);
+    let tick = Duration::from_secs_f32(FAST_FORWARD_TICK_SECONDS);
+    while world.resource::<FastForward>().is_active() && started.elapsed() < budget {
+        // Every tick sees the same fixed delta, whatever the real frame time; the frame's
+        // real time is restored when Time updates at the start of the next frame
+        world.resource_mut::<Time>().advance_by(tick);
+        run_tick(world);
+        world.resource_mut::<FastForward>().remaining -= 1;
+    }
+
+    if !world.resource::<FastForward>().is_active() {
+        let total = world.resource::<FastForward>().total;
+        info!("Fast-forwarded {} ticks", total);
+    }
+}
+
+/// Run one simulation tick with a fresh spawn budget
+fn run_tick(world: &mut World) {
+    world.resource_mut::<SpawnBudget>().refresh();
+    world.run_schedule(SimulationStep);
+}
+
+/// Turn the main camera on or off
+fn set_world_rendering(world: &mut World, enabled: bool) {
+    let mut cameras = world.query_filtered::<&mut Camera, With<MainCamera>>();
+    for mut camera in cameras.iter_mut(world) {
+        if camera.is_active != enabled {
+            camera.is_active = enabled;
+        }
+    }
+}
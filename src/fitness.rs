@@ -0,0 +1,196 @@
+use crate::animal::{Animal, AnimalDied, DeathCause};
+use crate::config::*;
+use bevy::prelude::*;
+use std::collections::{HashSet, VecDeque};
+
+/// Lifecycle notifications delivered to every registered metric
+#[allow(dead_code)] // Fields are for custom metrics; the built-ins don't need all of them
+#[derive(Debug, Clone, Copy)]
+pub enum LifecycleEvent {
+    Born { entity: Entity },
+    Died { entity: Entity, cause: DeathCause },
+}
+
+/// Snapshot of one living animal handed to metrics every tick
+#[allow(dead_code)] // Fields are for custom metrics; the built-ins don't need all of them
+pub struct AnimalSample {
+    pub entity: Entity,
+    pub energy: u32,
+    pub age: f32,
+    pub position: Vec2,
+}
+
+/// A pluggable fitness/research metric.
+///
+/// Metrics receive lifecycle events and a per-tick sample of the population, and report
+/// a single value that is charted and exported alongside the built-in statistics.
+pub trait FitnessMetric: Send + Sync + 'static {
+    /// Display name used in charts and as the CSV column header
+    fn name(&self) -> &str;
+
+    /// Called for every birth and death
+    fn on_event(&mut self, _event: &LifecycleEvent) {}
+
+    /// Called once per simulation tick with all living animals
+    fn sample(&mut self, _animals: &[AnimalSample]) {}
+
+    /// Current value of the metric
+    fn value(&self) -> f32;
+}
+
+/// A registered metric together with its recorded history
+pub struct TrackedMetric {
+    pub metric: Box<dyn FitnessMetric>,
+    pub history: VecDeque<f32>,
+}
+
+/// Resource holding every registered fitness metric
+#[derive(Resource, Default)]
+pub struct FitnessMetrics {
+    pub tracked: Vec<TrackedMetric>,
+}
+
+impl FitnessMetrics {
+    pub fn register(&mut self, metric: impl FitnessMetric) {
+        self.tracked.push(TrackedMetric {
+            metric: Box::new(metric),
+            history: VecDeque::with_capacity(CHART_HISTORY_LENGTH),
+        });
+    }
+}
+
+/// App extension for registering metrics from plugins or `main`
+pub trait FitnessAppExt {
+    fn add_fitness_metric(&mut self, metric: impl FitnessMetric) -> &mut Self;
+}
+
+impl FitnessAppExt for App {
+    fn add_fitness_metric(&mut self, metric: impl FitnessMetric) -> &mut Self {
+        self.world_mut()
+            .get_resource_or_insert_with(FitnessMetrics::default)
+            .register(metric);
+        self
+    }
+}
+
+/// Timer for recording metric values into their history
+#[derive(Resource)]
+pub struct FitnessSampleTimer(pub Timer);
+
+/// System to feed lifecycle events and per-tick samples to every metric
+pub fn update_fitness_metrics(
+    time: Res<Time>,
+    mut timer: ResMut<FitnessSampleTimer>,
+    mut metrics: ResMut<FitnessMetrics>,
+    mut deaths: EventReader<AnimalDied>,
+    born: Query<Entity, Added<Animal>>,
+    animals: Query<(Entity, &Animal, &Transform)>,
+) {
+    let mut events: Vec<LifecycleEvent> = born
+        .iter()
+        .map(|entity| LifecycleEvent::Born { entity })
+        .collect();
+    events.extend(deaths.read().map(|death| LifecycleEvent::Died {
+        entity: death.entity,
+        cause: death.cause,
+    }));
+
+    let samples: Vec<AnimalSample> = animals
+        .iter()
+        .map(|(entity, animal, transform)| AnimalSample {
+            entity,
+            energy: animal.energy,
+            age: animal.age,
+            position: transform.translation.truncate(),
+        })
+        .collect();
+
+    let record = timer.0.tick(time.delta()).just_finished();
+
+    for tracked in metrics.tracked.iter_mut() {
+        for event in &events {
+            tracked.metric.on_event(event);
+        }
+        tracked.metric.sample(&samples);
+
+        if record {
+            if tracked.history.len() >= CHART_HISTORY_LENGTH {
+                tracked.history.pop_front();
+            }
+            tracked.history.push_back(tracked.metric.value());
+        }
+    }
+}
+
+/// Average energy of living animals
+#[derive(Default)]
+pub struct MeanEnergy {
+    value: f32,
+}
+
+impl FitnessMetric for MeanEnergy {
+    fn name(&self) -> &str {
+        "Mean energy"
+    }
+
+    fn sample(&mut self, animals: &[AnimalSample]) {
+        self.value = if animals.is_empty() {
+            0.0
+        } else {
+            animals.iter().map(|a| a.energy as f32).sum::<f32>() / animals.len() as f32
+        };
+    }
+
+    fn value(&self) -> f32 {
+        self.value
+    }
+}
+
+/// Total number of animals killed by attacks
+#[derive(Default)]
+pub struct KillCount {
+    kills: u32,
+}
+
+impl FitnessMetric for KillCount {
+    fn name(&self) -> &str {
+        "Kill count"
+    }
+
+    fn on_event(&mut self, event: &LifecycleEvent) {
+        if let LifecycleEvent::Died {
+            cause: DeathCause::Killed,
+            ..
+        } = event
+        {
+            self.kills += 1;
+        }
+    }
+
+    fn value(&self) -> f32 {
+        self.kills as f32
+    }
+}
+
+/// Number of distinct grid cells ever visited by any animal
+#[derive(Default)]
+pub struct ExplorationArea {
+    visited: HashSet<(i32, i32)>,
+}
+
+impl FitnessMetric for ExplorationArea {
+    fn name(&self) -> &str {
+        "Exploration area (cells)"
+    }
+
+    fn sample(&mut self, animals: &[AnimalSample]) {
+        for animal in animals {
+            let cell = (animal.position / EXPLORATION_CELL_SIZE).floor();
+            self.visited.insert((cell.x as i32, cell.y as i32));
+        }
+    }
+
+    fn value(&self) -> f32 {
+        self.visited.len() as f32
+    }
+}
@@ -0,0 +1,75 @@
+use crate::animal::Animal;
+use crate::config::*;
+use crate::genome::{Genome, Word};
+use crate::sim_rng::SimRng;
+use bevy::prelude::*;
+use rand::Rng;
+
+/// Genome segment an animal copied from a touching neighbour. It leaves the animal's own
+/// running genome alone and is inserted into each of its future offspring instead
+#[derive(Component, Debug, Clone)]
+pub struct TransferredGenes {
+    pub donor: Entity,
+    pub segment: Vec<Word>,
+}
+
+/// Resource controlling horizontal gene transfer between touching animals
+#[derive(Resource)]
+pub struct GeneTransfer {
+    pub enabled: bool,
+    /// Chance per check that an animal touching another copies part of its genome
+    pub probability: f32,
+    /// Longest segment copied at once
+    pub max_length: usize,
+    pub timer: Timer,
+}
+
+impl Default for GeneTransfer {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            probability: GENE_TRANSFER_PROBABILITY,
+            max_length: GENE_TRANSFER_MAX_LENGTH,
+            timer: Timer::from_seconds(GENE_TRANSFER_INTERVAL, TimerMode::Repeating),
+        }
+    }
+}
+
+/// System to let animals occasionally copy a segment of a touching neighbour's genome,
+/// replacing any segment they received before
+pub fn transfer_genes(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut settings: ResMut<GeneTransfer>,
+    mut rng: ResMut<SimRng>,
+    animals: Query<(Entity, &Genome, &Transform), With<Animal>>,
+) {
+    if !settings.enabled || !settings.timer.tick(time.delta()).just_finished() {
+        return;
+    }
+
+    for (entity, _, transform) in animals.iter() {
+        // Roll first so only the few recipients pay for the neighbour search
+        if !rng.gen_bool(settings.probability.clamp(0.0, 1.0) as f64) {
+            continue;
+        }
+        let position = transform.translation.truncate();
+        let donor = animals
+            .iter()
+            .filter(|(other, _, _)| *other != entity)
+            .map(|(other, genome, other_transform)| {
+                let distance = position.distance(other_transform.translation.truncate());
+                (other, genome, distance)
+            })
+            .filter(|(_, _, distance)| *distance <= CONTACT_DISTANCE)
+            .min_by(|a, b| a.2.total_cmp(&b.2));
+
+        if let Some((donor, genome, _)) = donor
+            && let Some(segment) = genome.sample_segment(settings.max_length, &mut *rng)
+        {
+            commands
+                .entity(entity)
+                .try_insert(TransferredGenes { donor, segment });
+        }
+    }
+}
@@ -1,10 +1,14 @@
 use crate::config::*;
+use crate::watchpoint::WatchCondition;
 use bevy::prelude::*;
 use rand::Rng;
+use rand_distr::{Distribution, Normal};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
 use std::fmt;
 
 /// Stack value types for the stack machine
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum StackValue {
     Float(f32),
     Bool(bool),
@@ -35,8 +39,38 @@ impl fmt::Display for StackValue {
     }
 }
 
+/// Serde representation for literal floats that survives every format.
+///
+/// Finite values are written as plain numbers; NaN and the infinities, which JSON and
+/// similar formats cannot express, are written as the strings "NaN", "inf" and "-inf".
+mod stable_float {
+    use serde::{Deserialize, Deserializer, Serializer, de::Error};
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Repr {
+        Number(f32),
+        Text(String),
+    }
+
+    pub fn serialize<S: Serializer>(value: &f32, serializer: S) -> Result<S::Ok, S::Error> {
+        if value.is_finite() {
+            serializer.serialize_f32(*value)
+        } else {
+            serializer.serialize_str(&value.to_string())
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<f32, D::Error> {
+        match Repr::deserialize(deserializer)? {
+            Repr::Number(value) => Ok(value),
+            Repr::Text(text) => text.parse().map_err(D::Error::custom),
+        }
+    }
+}
+
 /// Word set for stack-based genome execution (Forth-like concatenative language)
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum Word {
     // Stack Manipulation
     Dup,  // ( a -- a a )
@@ -46,15 +80,51 @@ pub enum Word {
     Rot,  // ( a b c -- b c a )
 
     // Literals
-    PushFloat(f32), // ( -- f32 )
-    PushBool(bool), // ( -- bool )
+    PushFloat(#[serde(with = "stable_float")] f32), // ( -- f32 )
+    PushBool(bool),                                 // ( -- bool )
+    PushConst(u8), // ( -- f32 ) - Push value from the genome's constant pool
 
     // Sensor Operations (push sensor values)
-    SmellFront, // ( -- f32 ) - Push front smell sensor distance
-    SmellBack,  // ( -- f32 ) - Push back smell sensor distance
-    SmellLeft,  // ( -- f32 ) - Push left smell sensor distance
-    SmellRight, // ( -- f32 ) - Push right smell sensor distance
-    Energy,     // ( -- f32 ) - Push current energy
+    SmellFront,       // ( -- f32 ) - Push front plant scent (distance or intensity)
+    SmellBack,        // ( -- f32 ) - Push back plant scent (distance or intensity)
+    SmellLeft,        // ( -- f32 ) - Push left plant scent (distance or intensity)
+    SmellRight,       // ( -- f32 ) - Push right plant scent (distance or intensity)
+    SmellAnimalFront, // ( -- f32 ) - Push front animal scent (distance or intensity)
+    SmellAnimalBack,  // ( -- f32 ) - Push back animal scent (distance or intensity)
+    SmellAnimalLeft,  // ( -- f32 ) - Push left animal scent (distance or intensity)
+    SmellAnimalRight, // ( -- f32 ) - Push right animal scent (distance or intensity)
+    SmellCorpseFront, // ( -- f32 ) - Push front corpse scent (distance or intensity)
+    SmellCorpseBack,  // ( -- f32 ) - Push back corpse scent (distance or intensity)
+    SmellCorpseLeft,  // ( -- f32 ) - Push left corpse scent (distance or intensity)
+    SmellCorpseRight, // ( -- f32 ) - Push right corpse scent (distance or intensity)
+    AnimalFront,      // ( -- f32 ) - Push distance to the nearest animal in front
+    AnimalBack,       // ( -- f32 ) - Push distance to the nearest animal behind
+    AnimalLeft,       // ( -- f32 ) - Push distance to the nearest animal to the left
+    AnimalRight,      // ( -- f32 ) - Push distance to the nearest animal to the right
+    HearFront,        // ( -- f32 ) - Push distance to the nearest call heard in front
+    HearBack,         // ( -- f32 ) - Push distance to the nearest call heard behind
+    HearLeft,         // ( -- f32 ) - Push distance to the nearest call heard to the left
+    HearRight,        // ( -- f32 ) - Push distance to the nearest call heard to the right
+    Energy,           // ( -- f32 ) - Push current energy
+    LifeLeft,         // ( -- f32 ) - Push the fraction of the lifespan still ahead (1 to 0)
+    Touching,         // ( -- bool ) - Push true if any plant or animal is in contact
+    TouchingPlant,    // ( -- bool ) - Push true if a plant is in contact
+    TouchingAnimal,   // ( -- bool ) - Push true if another animal is in contact
+    Contacts,         // ( -- f32 ) - Push the number of plants and animals overlapping the body
+    SmellRichness,    // ( -- f32 ) - Push the energy of the nearest plant (0 if none)
+    Crowding,         // ( -- f32 ) - Push the number of other animals within the crowding radius
+    Heading,          // ( -- f32 ) - Push the facing direction as a fraction of a turn (0 to 1)
+    PosX,             // ( -- f32 ) - Push the world x coordinate
+    PosY,             // ( -- f32 ) - Push the world y coordinate
+    CenterDistance,   // ( -- f32 ) - Push the distance from the center of the world
+    EdgeDistance,     // ( -- f32 ) - Push the distance to the nearest world edge (negative outside)
+    Speed,            // ( -- f32 ) - Push the distance moved since the last tick
+    TurnRate,         // ( -- f32 ) - Push the fraction of a turn turned left since the last tick
+    Starving,         // ( -- bool ) - Push true if energy is below the starvation threshold
+    SeeDistance,      // ( -- f32 ) - Push distance to the first thing in the vision cone
+    SeePlant,         // ( -- bool ) - Push true if the first thing seen is a plant
+    SeeAnimal,        // ( -- bool ) - Push true if the first thing seen is an animal
+    Pheromone,        // ( -- f32 ) - Push the pheromone level where the animal stands
 
     // Arithmetic Operations
     Add, // ( a b -- a+b )
@@ -94,19 +164,41 @@ pub enum Word {
     MoveBackward, // ( f32 -- ) - Move backward by distance
     TurnLeft,     // ( f32 -- ) - Turn left by degrees
     TurnRight,    // ( f32 -- ) - Turn right by degrees
+    StrafeLeft,   // ( f32 -- ) - Move sideways to the left by distance
+    StrafeRight,  // ( f32 -- ) - Move sideways to the right by distance
 
     // Resource Actions
-    Eat,   // ( -- ) - Try to eat nearby plant
-    Split, // ( -- ) - Reproduce
+    Eat,              // ( -- ) - Try to eat nearby plant
+    Split,            // ( -- ) - Reproduce
+    SplitRatio,       // ( f32 -- ) - Set the offspring's share of energy for the next split
+    Attack,           // ( -- ) - Drain energy from the nearest animal within strike distance
+    Grab,             // ( -- ) - Pick up a nearby plant and carry it
+    DropItem,         // ( -- ) - Put down the carried plant
+    DepositPheromone, // ( f32 -- ) - Add pheromone where the animal stands (0 to max deposit)
+    Call,             // ( -- ) - Call out; animals within hearing range hear it next tick
+
+    // Phenotype Actions
+    SetColor, // ( f32 -- ) - Set body hue (degrees, wrapped to 0-360)
 
     // Special
     Nop, // ( -- ) - No operation
 }
 
 impl Word {
+    /// Token for this word in genome source (floats at full precision, so parsing gives
+    /// back the same value)
+    pub fn source_token(&self) -> String {
+        match self {
+            Word::PushFloat(value) => format!("{:?}", value),
+            _ => self.to_string(),
+        }
+    }
+
     /// Generate a random word with reasonable parameters
-    pub fn random() -> Self {
-        let mut rng = rand::thread_rng();
+    pub fn random(rng: &mut impl Rng) -> Self {
+        if rng.gen_bool(EXTENDED_WORD_SHARE) {
+            return Self::random_extended(rng);
+        }
         // Weighted random: bias toward useful patterns
         let r = rng.gen_range(0..100);
         match r {
@@ -153,7 +245,7 @@ impl Word {
             97 => [Word::Label0, Word::Label1, Word::Label2, Word::Label3][rng.gen_range(0..4)],
             98 => [Word::Label0, Word::Label1, Word::Label2, Word::Label3][rng.gen_range(0..4)],
 
-            // Jumps (3%)
+            // Jumps (1%)
             _ => [
                 Word::Jump0,
                 Word::Jump1,
@@ -167,17 +259,118 @@ impl Word {
         }
     }
 
+    /// Generate one of the words added after the original vocabulary, weighted among
+    /// themselves
+    fn random_extended(rng: &mut impl Rng) -> Self {
+        match rng.gen_range(0..20) {
+            // Sensors (60%)
+            0..=1 => [
+                Word::SmellAnimalFront,
+                Word::SmellAnimalBack,
+                Word::SmellAnimalLeft,
+                Word::SmellAnimalRight,
+            ][rng.gen_range(0..4)],
+            2 => [
+                Word::SmellCorpseFront,
+                Word::SmellCorpseBack,
+                Word::SmellCorpseLeft,
+                Word::SmellCorpseRight,
+            ][rng.gen_range(0..4)],
+            3..=4 => [
+                Word::AnimalFront,
+                Word::AnimalBack,
+                Word::AnimalLeft,
+                Word::AnimalRight,
+            ][rng.gen_range(0..4)],
+            5 => [
+                Word::HearFront,
+                Word::HearBack,
+                Word::HearLeft,
+                Word::HearRight,
+            ][rng.gen_range(0..4)],
+            6..=9 => [Word::SeeDistance, Word::SeePlant, Word::SeeAnimal][rng.gen_range(0..3)],
+            10 => [Word::Touching, Word::TouchingPlant, Word::TouchingAnimal][rng.gen_range(0..3)],
+            11 => [
+                Word::Starving,
+                Word::LifeLeft,
+                Word::Pheromone,
+                Word::Contacts,
+                Word::SmellRichness,
+                Word::Crowding,
+                Word::Heading,
+                Word::PosX,
+                Word::PosY,
+                Word::CenterDistance,
+                Word::EdgeDistance,
+                Word::Speed,
+                Word::TurnRate,
+            ][rng.gen_range(0..13)],
+
+            // Literals (20%)
+            12..=15 => Word::PushConst(rng.gen_range(0..CONSTANT_POOL_SIZE as u8)),
+
+            // Movement (5%)
+            16 => [Word::StrafeLeft, Word::StrafeRight][rng.gen_range(0..2)],
+
+            // Actions (15%)
+            17 => Word::Attack,
+            18 => [
+                Word::Grab,
+                Word::DropItem,
+                Word::DepositPheromone,
+                Word::Call,
+            ][rng.gen_range(0..4)],
+            _ => [Word::SetColor, Word::SplitRatio][rng.gen_range(0..2)],
+        }
+    }
+
     /// Get the category of this word for color-coding
     pub fn category(&self) -> WordCategory {
         match self {
             Word::Dup | Word::Drop | Word::Swap | Word::Over | Word::Rot => WordCategory::Stack,
             Word::PushFloat(_)
             | Word::PushBool(_)
+            | Word::PushConst(_)
             | Word::SmellFront
             | Word::SmellBack
             | Word::SmellLeft
             | Word::SmellRight
-            | Word::Energy => WordCategory::Sensor,
+            | Word::SmellAnimalFront
+            | Word::SmellAnimalBack
+            | Word::SmellAnimalLeft
+            | Word::SmellAnimalRight
+            | Word::SmellCorpseFront
+            | Word::SmellCorpseBack
+            | Word::SmellCorpseLeft
+            | Word::SmellCorpseRight
+            | Word::AnimalFront
+            | Word::AnimalBack
+            | Word::AnimalLeft
+            | Word::AnimalRight
+            | Word::HearFront
+            | Word::HearBack
+            | Word::HearLeft
+            | Word::HearRight
+            | Word::Energy
+            | Word::LifeLeft
+            | Word::Pheromone
+            | Word::Contacts
+            | Word::SmellRichness
+            | Word::Crowding
+            | Word::Heading
+            | Word::PosX
+            | Word::PosY
+            | Word::CenterDistance
+            | Word::EdgeDistance
+            | Word::Speed
+            | Word::TurnRate
+            | Word::Touching
+            | Word::TouchingPlant
+            | Word::TouchingAnimal
+            | Word::Starving
+            | Word::SeeDistance
+            | Word::SeePlant
+            | Word::SeeAnimal => WordCategory::Sensor,
             Word::Add
             | Word::Sub
             | Word::Mul
@@ -203,8 +396,17 @@ impl Word {
             | Word::MoveBackward
             | Word::TurnLeft
             | Word::TurnRight
+            | Word::StrafeLeft
+            | Word::StrafeRight
             | Word::Eat
-            | Word::Split => WordCategory::Action,
+            | Word::Split
+            | Word::SplitRatio
+            | Word::Attack
+            | Word::Grab
+            | Word::DropItem
+            | Word::DepositPheromone
+            | Word::Call
+            | Word::SetColor => WordCategory::Action,
             Word::Nop => WordCategory::Special,
         }
     }
@@ -219,11 +421,47 @@ impl Word {
             Word::Rot => "( a b c -- b c a )",
             Word::PushFloat(_) => "( -- f32 )",
             Word::PushBool(_) => "( -- bool )",
+            Word::PushConst(_) => "( -- f32 )",
             Word::SmellFront
             | Word::SmellBack
             | Word::SmellLeft
             | Word::SmellRight
-            | Word::Energy => "( -- f32 )",
+            | Word::SmellAnimalFront
+            | Word::SmellAnimalBack
+            | Word::SmellAnimalLeft
+            | Word::SmellAnimalRight
+            | Word::SmellCorpseFront
+            | Word::SmellCorpseBack
+            | Word::SmellCorpseLeft
+            | Word::SmellCorpseRight
+            | Word::AnimalFront
+            | Word::AnimalBack
+            | Word::AnimalLeft
+            | Word::AnimalRight
+            | Word::HearFront
+            | Word::HearBack
+            | Word::HearLeft
+            | Word::HearRight
+            | Word::Energy
+            | Word::LifeLeft
+            | Word::Pheromone
+            | Word::Contacts
+            | Word::SmellRichness
+            | Word::Crowding
+            | Word::Heading
+            | Word::PosX
+            | Word::PosY
+            | Word::CenterDistance
+            | Word::EdgeDistance
+            | Word::Speed
+            | Word::TurnRate
+            | Word::SeeDistance => "( -- f32 )",
+            Word::Touching
+            | Word::TouchingPlant
+            | Word::TouchingAnimal
+            | Word::Starving
+            | Word::SeePlant
+            | Word::SeeAnimal => "( -- bool )",
             Word::Add | Word::Sub | Word::Mul | Word::Div => "( a b -- result )",
             Word::Lt | Word::Gt | Word::Eq => "( a b -- bool )",
             Word::And | Word::Or => "( bool bool -- bool )",
@@ -232,10 +470,18 @@ impl Word {
             Word::Then | Word::Else => "( -- )",
             Word::Label0 | Word::Label1 | Word::Label2 | Word::Label3 => "( -- )",
             Word::Jump0 | Word::Jump1 | Word::Jump2 | Word::Jump3 => "( -- )",
-            Word::MoveForward | Word::MoveBackward | Word::TurnLeft | Word::TurnRight => {
-                "( f32 -- )"
+            Word::MoveForward
+            | Word::MoveBackward
+            | Word::TurnLeft
+            | Word::TurnRight
+            | Word::StrafeLeft
+            | Word::StrafeRight
+            | Word::SplitRatio
+            | Word::DepositPheromone
+            | Word::SetColor => "( f32 -- )",
+            Word::Eat | Word::Split | Word::Attack | Word::Grab | Word::DropItem | Word::Call => {
+                "( -- )"
             }
-            Word::Eat | Word::Split => "( -- )",
             Word::Nop => "( -- )",
         }
     }
@@ -251,11 +497,47 @@ impl fmt::Display for Word {
             Word::Rot => write!(f, "rot"),
             Word::PushFloat(val) => write!(f, "{:.1}", val),
             Word::PushBool(val) => write!(f, "{}", if *val { "true" } else { "false" }),
+            Word::PushConst(idx) => write!(f, "const{}", idx),
             Word::SmellFront => write!(f, "smell-front"),
             Word::SmellBack => write!(f, "smell-back"),
             Word::SmellLeft => write!(f, "smell-left"),
             Word::SmellRight => write!(f, "smell-right"),
+            Word::SmellAnimalFront => write!(f, "smell-animal-front"),
+            Word::SmellAnimalBack => write!(f, "smell-animal-back"),
+            Word::SmellAnimalLeft => write!(f, "smell-animal-left"),
+            Word::SmellAnimalRight => write!(f, "smell-animal-right"),
+            Word::SmellCorpseFront => write!(f, "smell-corpse-front"),
+            Word::SmellCorpseBack => write!(f, "smell-corpse-back"),
+            Word::SmellCorpseLeft => write!(f, "smell-corpse-left"),
+            Word::SmellCorpseRight => write!(f, "smell-corpse-right"),
+            Word::AnimalFront => write!(f, "animal-front"),
+            Word::AnimalBack => write!(f, "animal-back"),
+            Word::AnimalLeft => write!(f, "animal-left"),
+            Word::AnimalRight => write!(f, "animal-right"),
+            Word::HearFront => write!(f, "hear-front"),
+            Word::HearBack => write!(f, "hear-back"),
+            Word::HearLeft => write!(f, "hear-left"),
+            Word::HearRight => write!(f, "hear-right"),
             Word::Energy => write!(f, "energy"),
+            Word::LifeLeft => write!(f, "life-left"),
+            Word::Touching => write!(f, "touching"),
+            Word::TouchingPlant => write!(f, "touching-plant"),
+            Word::TouchingAnimal => write!(f, "touching-animal"),
+            Word::Contacts => write!(f, "contacts"),
+            Word::SmellRichness => write!(f, "smell-richness"),
+            Word::Crowding => write!(f, "crowding"),
+            Word::Heading => write!(f, "heading"),
+            Word::PosX => write!(f, "pos-x"),
+            Word::PosY => write!(f, "pos-y"),
+            Word::CenterDistance => write!(f, "center-distance"),
+            Word::EdgeDistance => write!(f, "edge-distance"),
+            Word::Speed => write!(f, "speed"),
+            Word::TurnRate => write!(f, "turn-rate"),
+            Word::Starving => write!(f, "starving"),
+            Word::SeeDistance => write!(f, "see-distance"),
+            Word::SeePlant => write!(f, "see-plant"),
+            Word::SeeAnimal => write!(f, "see-animal"),
+            Word::Pheromone => write!(f, "pheromone"),
             Word::Add => write!(f, "+"),
             Word::Sub => write!(f, "-"),
             Word::Mul => write!(f, "*"),
@@ -281,13 +563,194 @@ impl fmt::Display for Word {
             Word::MoveBackward => write!(f, "move-backward"),
             Word::TurnLeft => write!(f, "turn-left"),
             Word::TurnRight => write!(f, "turn-right"),
+            Word::StrafeLeft => write!(f, "strafe-left"),
+            Word::StrafeRight => write!(f, "strafe-right"),
             Word::Eat => write!(f, "eat"),
             Word::Split => write!(f, "split"),
+            Word::SplitRatio => write!(f, "split-ratio"),
+            Word::Attack => write!(f, "attack"),
+            Word::Grab => write!(f, "grab"),
+            Word::DropItem => write!(f, "drop-item"),
+            Word::DepositPheromone => write!(f, "deposit-pheromone"),
+            Word::Call => write!(f, "call"),
+            Word::SetColor => write!(f, "set-color"),
             Word::Nop => write!(f, "nop"),
         }
     }
 }
 
+/// A word-level change made by `Genome::mutate`, indexed into the offspring's words
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Mutation {
+    /// The word at `index` replaced the parent's `was`
+    Substituted { index: usize, was: Word },
+    /// The word at `index` is a duplicate of the one before it
+    Inserted { index: usize },
+    /// The parent's `was` was removed just before `index`
+    Deleted { index: usize, was: Word },
+    /// The words from `start` up to (not including) `end` were reversed
+    Inverted { start: usize, end: usize },
+    /// The `length` words at the parent's `from` were cut and reinserted at `to`
+    Translocated {
+        from: usize,
+        to: usize,
+        length: usize,
+    },
+    /// The `length` words at `index` are a copy of the run just before them
+    BlockInserted { index: usize, length: usize },
+    /// The `length` words just before `index` were removed
+    BlockDeleted { index: usize, length: usize },
+    /// The `length` words at `index` were copied from another animal's genome
+    Transferred { index: usize, length: usize },
+    /// The `length` words at `index` are new random words
+    Spliced { index: usize, length: usize },
+}
+
+impl fmt::Display for Mutation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Mutation::Substituted { index, was } => write!(f, "~{:3}: replaced {}", index, was),
+            Mutation::Inserted { index } => write!(f, "+{:3}: duplicated", index),
+            Mutation::Deleted { index, was } => write!(f, "-{:3}: deleted {}", index, was),
+            Mutation::Inverted { start, end } => {
+                write!(f, "<{:3}: inverted {} words", start, end - start)
+            }
+            Mutation::Translocated { from, to, length } => {
+                write!(f, ">{:3}: moved {} words to {}", from, length, to)
+            }
+            Mutation::BlockInserted { index, length } => {
+                write!(f, "+{:3}: duplicated {} words", index, length)
+            }
+            Mutation::BlockDeleted { index, length } => {
+                write!(f, "-{:3}: deleted {} words", index, length)
+            }
+            Mutation::Transferred { index, length } => {
+                write!(f, "+{:3}: received {} words", index, length)
+            }
+            Mutation::Spliced { index, length } => {
+                write!(f, "+{:3}: inserted {} new words", index, length)
+            }
+        }
+    }
+}
+
+/// Word-level differences between two genomes, from `Genome::diff`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct GenomeDiff {
+    pub substituted: usize,
+    pub inserted: usize,
+    pub deleted: usize,
+    pub unchanged: usize,
+}
+
+impl GenomeDiff {
+    /// Number of edits separating the two genomes
+    pub fn distance(&self) -> usize {
+        self.substituted + self.inserted + self.deleted
+    }
+}
+
+impl std::str::FromStr for Word {
+    type Err = ();
+
+    /// Parse a single word using the names written by `Display`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let word = match s {
+            "dup" => Word::Dup,
+            "drop" => Word::Drop,
+            "swap" => Word::Swap,
+            "over" => Word::Over,
+            "rot" => Word::Rot,
+            "true" => Word::PushBool(true),
+            "false" => Word::PushBool(false),
+            "smell-front" => Word::SmellFront,
+            "smell-back" => Word::SmellBack,
+            "smell-left" => Word::SmellLeft,
+            "smell-right" => Word::SmellRight,
+            "smell-animal-front" => Word::SmellAnimalFront,
+            "smell-animal-back" => Word::SmellAnimalBack,
+            "smell-animal-left" => Word::SmellAnimalLeft,
+            "smell-animal-right" => Word::SmellAnimalRight,
+            "smell-corpse-front" => Word::SmellCorpseFront,
+            "smell-corpse-back" => Word::SmellCorpseBack,
+            "smell-corpse-left" => Word::SmellCorpseLeft,
+            "smell-corpse-right" => Word::SmellCorpseRight,
+            "animal-front" => Word::AnimalFront,
+            "animal-back" => Word::AnimalBack,
+            "animal-left" => Word::AnimalLeft,
+            "animal-right" => Word::AnimalRight,
+            "hear-front" => Word::HearFront,
+            "hear-back" => Word::HearBack,
+            "hear-left" => Word::HearLeft,
+            "hear-right" => Word::HearRight,
+            "energy" => Word::Energy,
+            "life-left" => Word::LifeLeft,
+            "touching" => Word::Touching,
+            "touching-plant" => Word::TouchingPlant,
+            "touching-animal" => Word::TouchingAnimal,
+            "contacts" => Word::Contacts,
+            "smell-richness" => Word::SmellRichness,
+            "crowding" => Word::Crowding,
+            "heading" => Word::Heading,
+            "pos-x" => Word::PosX,
+            "pos-y" => Word::PosY,
+            "center-distance" => Word::CenterDistance,
+            "edge-distance" => Word::EdgeDistance,
+            "speed" => Word::Speed,
+            "turn-rate" => Word::TurnRate,
+            "starving" => Word::Starving,
+            "see-distance" => Word::SeeDistance,
+            "see-plant" => Word::SeePlant,
+            "see-animal" => Word::SeeAnimal,
+            "pheromone" => Word::Pheromone,
+            "+" => Word::Add,
+            "-" => Word::Sub,
+            "*" => Word::Mul,
+            "/" => Word::Div,
+            "<" => Word::Lt,
+            ">" => Word::Gt,
+            "=" => Word::Eq,
+            "and" => Word::And,
+            "or" => Word::Or,
+            "not" => Word::Not,
+            "if" => Word::If,
+            "then" => Word::Then,
+            "else" => Word::Else,
+            "label0" => Word::Label0,
+            "label1" => Word::Label1,
+            "label2" => Word::Label2,
+            "label3" => Word::Label3,
+            "jump0" => Word::Jump0,
+            "jump1" => Word::Jump1,
+            "jump2" => Word::Jump2,
+            "jump3" => Word::Jump3,
+            "move-forward" => Word::MoveForward,
+            "move-backward" => Word::MoveBackward,
+            "turn-left" => Word::TurnLeft,
+            "turn-right" => Word::TurnRight,
+            "strafe-left" => Word::StrafeLeft,
+            "strafe-right" => Word::StrafeRight,
+            "eat" => Word::Eat,
+            "split" => Word::Split,
+            "split-ratio" => Word::SplitRatio,
+            "attack" => Word::Attack,
+            "grab" => Word::Grab,
+            "drop-item" => Word::DropItem,
+            "deposit-pheromone" => Word::DepositPheromone,
+            "call" => Word::Call,
+            "set-color" => Word::SetColor,
+            "nop" => Word::Nop,
+            _ => {
+                if let Some(index) = s.strip_prefix("const") {
+                    return index.parse().map(Word::PushConst).map_err(|_| ());
+                }
+                return s.parse().map(Word::PushFloat).map_err(|_| ());
+            }
+        };
+        Ok(word)
+    }
+}
+
 /// Category for color-coding words
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum WordCategory {
@@ -299,23 +762,334 @@ pub enum WordCategory {
     Special,    // Gray - Special operations
 }
 
-/// A genome is a sequence of words (Forth-like program)
-#[derive(Component, Clone)]
+/// A genome is a sequence of words (Forth-like program) plus a constant pool
+/// that `PushConst` reads from. Constants mutate by small jitter rather than
+/// full replacement, so evolved thresholds can be fine-tuned.
+#[derive(Component, Clone, Serialize, Deserialize)]
+#[serde(into = "GenomeRecord", try_from = "GenomeRecord")]
 pub struct Genome {
     pub words: Vec<Word>,
+    pub constants: Vec<f32>,
+    pub rates: MutationRates,
+    pub diet: Diet,
+    /// Heritable body size, turned into a `Phenotype` at birth
+    pub body_size: f32,
+}
+
+/// Per-word mutation chances in percent, carried by each genome and jittered on every
+/// split so that mutation rates themselves evolve
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct MutationRates {
+    pub substitution: f32,
+    pub duplication: f32,
+    pub deletion: f32,
 }
 
+impl Default for MutationRates {
+    fn default() -> Self {
+        Self {
+            substitution: MUTATION_RATE,
+            duplication: DUPLICATION_RATE,
+            deletion: DELETION_RATE,
+        }
+    }
+}
+
+impl MutationRates {
+    /// Scale each rate by log-normal noise, within MIN_EVOLVED_RATE..=MAX_EVOLVED_RATE
+    fn mutate(&self, rng: &mut impl Rng) -> Self {
+        let noise = Normal::new(0.0, RATE_MUTATION_SIGMA).unwrap();
+        let mut jitter = |rate: f32| {
+            (rate * noise.sample(&mut *rng).exp()).clamp(MIN_EVOLVED_RATE, MAX_EVOLVED_RATE)
+        };
+        Self {
+            substitution: jitter(self.substitution),
+            duplication: jitter(self.duplication),
+            deletion: jitter(self.deletion),
+        }
+    }
+}
+
+/// Runtime mutation settings read by `Genome::mutate`, so experiments can be steered
+/// without recompiling
+#[derive(Resource)]
+pub struct MutationConfig {
+    /// Per-word rates used when rates do not evolve
+    pub rates: MutationRates,
+    /// Use (and jitter) each genome's own rates instead of `rates`
+    pub evolve_rates: bool,
+    /// Chances in percent per offspring of each block-level mutation
+    pub insertion: u32,
+    pub block_duplication: u32,
+    pub block_deletion: u32,
+    pub inversion: u32,
+    pub translocation: u32,
+    /// Most words an offspring genome may have
+    pub max_length: usize,
+    /// How offspring over `max_length` are cut back
+    pub bloat_control: BloatControl,
+}
+
+/// How a genome that has grown past the length cap is cut back
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BloatControl {
+    /// Remove words from the end
+    #[default]
+    DropTail,
+    /// Remove one run of words starting at a random position
+    DropSegment,
+}
+
+impl BloatControl {
+    pub const ALL: [BloatControl; 2] = [BloatControl::DropTail, BloatControl::DropSegment];
+}
+
+impl fmt::Display for BloatControl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BloatControl::DropTail => write!(f, "drop-tail"),
+            BloatControl::DropSegment => write!(f, "drop-segment"),
+        }
+    }
+}
+
+impl std::str::FromStr for BloatControl {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|strategy| strategy.to_string() == s)
+            .ok_or_else(|| format!("unknown bloat control '{}'", s))
+    }
+}
+
+impl Default for MutationConfig {
+    fn default() -> Self {
+        Self {
+            rates: MutationRates::default(),
+            evolve_rates: true,
+            insertion: INSERTION_RATE,
+            block_duplication: BLOCK_DUPLICATION_RATE,
+            block_deletion: BLOCK_DELETION_RATE,
+            inversion: INVERSION_RATE,
+            translocation: TRANSLOCATION_RATE,
+            max_length: MAX_GENOME_LENGTH,
+            bloat_control: BloatControl::default(),
+        }
+    }
+}
+
+impl MutationConfig {
+    /// Config under which offspring are exact copies of their parent's words
+    pub fn disabled() -> Self {
+        Self {
+            rates: MutationRates {
+                substitution: 0.0,
+                duplication: 0.0,
+                deletion: 0.0,
+            },
+            evolve_rates: false,
+            insertion: 0,
+            block_duplication: 0,
+            block_deletion: 0,
+            inversion: 0,
+            translocation: 0,
+            ..Default::default()
+        }
+    }
+}
+
+/// Heritable split of digestion between plants and meat. The two efficiencies always sum
+/// to DIET_TOTAL_EFFICIENCY, so specialists digest one food well and generalists both
+/// moderately.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Diet {
+    /// Share of the efficiency spent on plants, from 0 (carnivore) to 1 (herbivore)
+    pub herbivory: f32,
+}
+
+impl Default for Diet {
+    fn default() -> Self {
+        Self {
+            herbivory: DEFAULT_HERBIVORY,
+        }
+    }
+}
+
+impl Diet {
+    /// Multiplier on energy taken from plants
+    pub fn plant_efficiency(&self) -> f32 {
+        DIET_TOTAL_EFFICIENCY * self.herbivory
+    }
+
+    /// Multiplier on energy drained from attacked animals
+    pub fn meat_efficiency(&self) -> f32 {
+        DIET_TOTAL_EFFICIENCY * (1.0 - self.herbivory)
+    }
+
+    /// Energy gained from `amount` of food digested with `efficiency`
+    pub fn digest(amount: u32, efficiency: f32) -> u32 {
+        (amount as f32 * efficiency).round() as u32
+    }
+
+    /// Shift herbivory by gaussian noise, within 0..=1
+    fn mutate(&self, rng: &mut impl Rng) -> Self {
+        let noise = Normal::new(0.0, DIET_MUTATION_SIGMA).unwrap();
+        Self {
+            herbivory: (self.herbivory + noise.sample(rng)).clamp(0.0, 1.0),
+        }
+    }
+}
+
+/// Version of the serialized genome encoding. Bump it when a word is renamed, removed or
+/// changes meaning, and append the upgrade step to `MIGRATIONS`.
+pub const GENOME_FORMAT_VERSION: u32 = 1;
+
+/// Upgrades from version `i` to `i + 1`, applied in order to older records on load
+const MIGRATIONS: [fn(&mut GenomeRecord); GENOME_FORMAT_VERSION as usize] = [tokenize_words];
+
+/// Serialized form of a genome, tagged with its format version
+#[derive(Serialize, Deserialize)]
+struct GenomeRecord {
+    /// Missing in genomes saved before the version tag, which count as version 0
+    #[serde(default)]
+    version: u32,
+    words: Vec<EncodedWord>,
+    constants: Vec<f32>,
+    /// Missing in genomes saved before rates were evolvable, which get the defaults
+    #[serde(default)]
+    rates: MutationRates,
+    /// Missing in genomes saved before diets were heritable, which get the default
+    #[serde(default)]
+    diet: Diet,
+    /// Missing in genomes saved before body size was heritable
+    #[serde(default = "default_body_size")]
+    body_size: f32,
+}
+
+fn default_body_size() -> f32 {
+    DEFAULT_BODY_SIZE
+}
+
+/// A serialized word
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum EncodedWord {
+    /// Derived serde form of `Word`, used by version 0
+    Derived(Word),
+    /// Source token as written by `Genome::to_source`, used from version 1
+    Token(String),
+}
+
+/// Version 0 to 1: words are stored as source tokens, which stay readable when variants
+/// are renamed and can be migrated with plain string replacement
+fn tokenize_words(record: &mut GenomeRecord) {
+    for word in record.words.iter_mut() {
+        if let EncodedWord::Derived(derived) = word {
+            *word = EncodedWord::Token(derived.source_token());
+        }
+    }
+}
+
+impl From<Genome> for GenomeRecord {
+    fn from(genome: Genome) -> Self {
+        Self {
+            version: GENOME_FORMAT_VERSION,
+            words: genome
+                .words
+                .iter()
+                .map(|word| EncodedWord::Token(word.source_token()))
+                .collect(),
+            constants: genome.constants,
+            rates: genome.rates,
+            diet: genome.diet,
+            body_size: genome.body_size,
+        }
+    }
+}
+
+impl TryFrom<GenomeRecord> for Genome {
+    type Error = String;
+
+    fn try_from(mut record: GenomeRecord) -> Result<Self, Self::Error> {
+        if record.version > GENOME_FORMAT_VERSION {
+            return Err(format!(
+                "genome format version {} is newer than the supported version {}",
+                record.version, GENOME_FORMAT_VERSION
+            ));
+        }
+        for migrate in &MIGRATIONS[record.version as usize..] {
+            migrate(&mut record);
+        }
+
+        let words = record
+            .words
+            .into_iter()
+            .map(|word| match word {
+                EncodedWord::Derived(word) => Ok(word),
+                EncodedWord::Token(token) => token
+                    .parse()
+                    .map_err(|_| format!("unknown word '{}'", token)),
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            words,
+            constants: record.constants,
+            rates: record.rates,
+            diet: record.diet,
+            body_size: record.body_size,
+        })
+    }
+}
+
+/// Constant pool of the seed genome: a spread of magnitudes covering turn, move and
+/// comparison scales
+const SEED_CONSTANTS: [f32; 8] = [0.1, 0.5, 1.0, 10.0, 30.0, 70.0, 200.0, 500.0];
+
+/// Number of words per line written by `Genome::to_source`
+const SOURCE_WORDS_PER_LINE: usize = 12;
+
+/// Error from `Genome::parse`: an unknown token, or no words at all (line 0)
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    pub line: usize,
+    pub token: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.line == 0 {
+            write!(f, "genome has no words")
+        } else {
+            write!(f, "line {}: unknown word '{}'", self.line, self.token)
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
 impl Genome {
     /// Create a new random genome
-    pub fn random(length: usize) -> Self {
+    pub fn random(length: usize, rng: &mut impl Rng) -> Self {
+        let constants = (0..CONSTANT_POOL_SIZE)
+            .map(|_| rng.gen_range(0.0..500.0))
+            .collect();
+
         // Keep generating genomes until we get one with a Split instruction
         // This ensures all spawned animals can reproduce
         loop {
-            let words: Vec<Word> = (0..length).map(|_| Word::random()).collect();
+            let words: Vec<Word> = (0..length).map(|_| Word::random(rng)).collect();
 
             // Check if this genome contains at least one Split instruction
             if words.iter().any(|word| matches!(word, Word::Split)) {
-                return Self { words };
+                return Self {
+                    words,
+                    constants,
+                    rates: MutationRates::default(),
+                    diet: Diet::default(),
+                    body_size: DEFAULT_BODY_SIZE,
+                };
             }
             // Otherwise, try again
         }
@@ -374,44 +1148,577 @@ impl Genome {
             words.push(Word::Nop);
         }
 
-        Self { words }
+        Self {
+            words,
+            constants: SEED_CONSTANTS.to_vec(),
+            rates: MutationRates::default(),
+            diet: Diet::default(),
+            body_size: DEFAULT_BODY_SIZE,
+        }
+    }
+
+    /// Parse a genome from its Forth-like text form.
+    ///
+    /// Words are separated by whitespace and use the same names as the Genome Viewer.
+    /// Numbers become `PushFloat`, `true`/`false` become `PushBool` and `constN` reads
+    /// the constant pool. `\` starts a comment that runs to the end of the line. An
+    /// optional `.constants` line sets the constant pool; without one the seed pool is used.
+    pub fn parse(source: &str) -> Result<Self, ParseError> {
+        let mut words = Vec::new();
+        let mut constants = None;
+
+        for (line_number, line) in source.lines().enumerate() {
+            let line = line.split('\\').next().unwrap_or_default();
+            let mut tokens = line.split_whitespace().peekable();
+
+            if tokens.peek() == Some(&".constants") {
+                tokens.next();
+                let pool = tokens
+                    .map(|token| {
+                        token.parse::<f32>().map_err(|_| ParseError {
+                            line: line_number + 1,
+                            token: token.to_string(),
+                        })
+                    })
+                    .collect::<Result<Vec<f32>, _>>()?;
+                constants = Some(pool);
+                continue;
+            }
+
+            for token in tokens {
+                let word = token.parse::<Word>().map_err(|_| ParseError {
+                    line: line_number + 1,
+                    token: token.to_string(),
+                })?;
+                words.push(word);
+            }
+        }
+
+        if words.is_empty() {
+            return Err(ParseError {
+                line: 0,
+                token: String::new(),
+            });
+        }
+
+        Ok(Self {
+            words,
+            constants: constants.unwrap_or_else(|| SEED_CONSTANTS.to_vec()),
+            rates: MutationRates::default(),
+            diet: Diet::default(),
+            body_size: DEFAULT_BODY_SIZE,
+        })
+    }
+
+    /// Write the genome in the text form read by `Genome::parse`
+    pub fn to_source(&self) -> String {
+        let constants: Vec<String> = self.constants.iter().map(|c| format!("{:?}", c)).collect();
+        let mut source = format!(".constants {}\n", constants.join(" "));
+
+        for line in self.words.chunks(SOURCE_WORDS_PER_LINE) {
+            let line: Vec<String> = line.iter().map(Word::source_token).collect();
+            source.push_str(&line.join(" "));
+            source.push('\n');
+        }
+        source
+    }
+
+    /// Body hue in degrees derived from a stable (FNV-1a) hash of the words, so equal
+    /// programs always get the same colour; constants are left out so jitter alone does
+    /// not recolour offspring
+    pub fn hue(&self) -> f32 {
+        let mut hash: u32 = 0x811c_9dc5;
+        for word in &self.words {
+            for byte in word.source_token().bytes().chain([b' ']) {
+                hash ^= byte as u32;
+                hash = hash.wrapping_mul(0x0100_0193);
+            }
+        }
+        (hash % 360) as f32
+    }
+
+    /// Read a value from the constant pool (index wraps around the pool size)
+    pub fn constant(&self, index: u8) -> f32 {
+        if self.constants.is_empty() {
+            return 0.0;
+        }
+        self.constants[index as usize % self.constants.len()]
+    }
+
+    /// IF/ELSE/THEN positions as (if_pos, else_pos, then_pos), matched by nesting
+    pub fn jump_table(&self) -> Vec<(usize, Option<usize>, usize)> {
+        let mut table = Vec::new();
+        let mut if_stack: Vec<usize> = Vec::new();
+        let mut else_positions: std::collections::HashMap<usize, usize> =
+            std::collections::HashMap::new();
+
+        for (i, word) in self.words.iter().enumerate() {
+            match word {
+                Word::If => {
+                    if_stack.push(i);
+                }
+                Word::Else => {
+                    if let Some(&if_pos) = if_stack.last() {
+                        else_positions.insert(if_pos, i);
+                    }
+                }
+                Word::Then => {
+                    if let Some(if_pos) = if_stack.pop() {
+                        let else_pos = else_positions.get(&if_pos).copied();
+                        table.push((if_pos, else_pos, i));
+                    }
+                }
+                _ => {}
+            }
+        }
+        table
+    }
+
+    /// Position of each label (the last occurrence wins)
+    pub fn label_table(&self) -> [Option<usize>; 4] {
+        let mut table = [None; 4];
+        for (i, word) in self.words.iter().enumerate() {
+            match word {
+                Word::Label0 => table[0] = Some(i),
+                Word::Label1 => table[1] = Some(i),
+                Word::Label2 => table[2] = Some(i),
+                Word::Label3 => table[3] = Some(i),
+                _ => {}
+            }
+        }
+        table
+    }
+
+    /// Positions execution can move to after the word at `index`, mirroring the VM.
+    ///
+    /// Conditional branches report both outcomes since the condition isn't known statically.
+    fn successors(
+        &self,
+        index: usize,
+        jump_table: &[(usize, Option<usize>, usize)],
+        label_table: &[Option<usize>; 4],
+    ) -> Vec<usize> {
+        let len = self.words.len();
+        let next = (index + 1) % len;
+        match self.words[index] {
+            Word::If => match jump_table.iter().find(|(if_pos, _, _)| *if_pos == index) {
+                Some((_, else_pos, then_pos)) => {
+                    vec![next, (else_pos.unwrap_or(*then_pos) + 1) % len]
+                }
+                None => vec![next],
+            },
+            Word::Else => match jump_table
+                .iter()
+                .find(|(_, else_pos, _)| *else_pos == Some(index))
+            {
+                Some((_, _, then_pos)) => vec![(then_pos + 1) % len],
+                None => vec![next],
+            },
+            Word::Jump0 | Word::Jump1 | Word::Jump2 | Word::Jump3 => {
+                let label = match self.words[index] {
+                    Word::Jump0 => 0,
+                    Word::Jump1 => 1,
+                    Word::Jump2 => 2,
+                    _ => 3,
+                };
+                vec![label_table[label].map_or(next, |target| target % len)]
+            }
+            _ => vec![next],
+        }
+    }
+
+    /// For each word, whether execution starting at the first word can ever reach it
+    pub fn reachable(&self) -> Vec<bool> {
+        let mut reachable = vec![false; self.words.len()];
+        if self.words.is_empty() {
+            return reachable;
+        }
+
+        let jump_table = self.jump_table();
+        let label_table = self.label_table();
+        let mut pending = vec![0];
+        reachable[0] = true;
+
+        while let Some(index) = pending.pop() {
+            for next in self.successors(index, &jump_table, &label_table) {
+                if !reachable[next] {
+                    reachable[next] = true;
+                    pending.push(next);
+                }
+            }
+        }
+        reachable
+    }
+
+    /// Indices of words that can never execute
+    pub fn dead_code(&self) -> Vec<usize> {
+        self.reachable()
+            .iter()
+            .enumerate()
+            .filter(|(_, reachable)| !**reachable)
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Count the fewest substitutions, insertions and deletions that turn `base` into
+    /// this genome (edit distance alignment)
+    pub fn diff(&self, base: &Genome) -> GenomeDiff {
+        let (old, new) = (&base.words, &self.words);
+        let width = new.len() + 1;
+        // cost[i * width + j]: edits turning old[..i] into new[..j]
+        let mut cost = vec![0usize; (old.len() + 1) * width];
+        for i in 0..=old.len() {
+            for j in 0..=new.len() {
+                cost[i * width + j] = match (i, j) {
+                    (0, _) => j,
+                    (_, 0) => i,
+                    _ => {
+                        let substitution = usize::from(old[i - 1] != new[j - 1]);
+                        (cost[(i - 1) * width + j - 1] + substitution)
+                            .min(cost[(i - 1) * width + j] + 1)
+                            .min(cost[i * width + j - 1] + 1)
+                    }
+                };
+            }
+        }
+
+        // Walk back along a cheapest path, classifying each step
+        let mut diff = GenomeDiff::default();
+        let (mut i, mut j) = (old.len(), new.len());
+        while i > 0 || j > 0 {
+            let here = cost[i * width + j];
+            if i > 0 && j > 0 {
+                let same = old[i - 1] == new[j - 1];
+                if here == cost[(i - 1) * width + j - 1] + usize::from(!same) {
+                    if same {
+                        diff.unchanged += 1;
+                    } else {
+                        diff.substituted += 1;
+                    }
+                    i -= 1;
+                    j -= 1;
+                    continue;
+                }
+            }
+            if i > 0 && here == cost[(i - 1) * width + j] + 1 {
+                diff.deleted += 1;
+                i -= 1;
+            } else {
+                diff.inserted += 1;
+                j -= 1;
+            }
+        }
+        diff
+    }
+
+    /// Recombine with a partner's genome (one-point crossover).
+    ///
+    /// The child takes this genome's words up to a random cut and the partner's words from
+    /// the corresponding relative position on, so its length stays close to the parents'.
+    /// Each constant comes from either parent with equal chance.
+    pub fn crossover(&self, other: &Genome, rng: &mut impl Rng) -> Self {
+        let cut = rng.gen_range(0..=self.words.len());
+        let other_cut = if self.words.is_empty() {
+            0
+        } else {
+            cut * other.words.len() / self.words.len()
+        };
+        let words: Vec<Word> = self.words[..cut]
+            .iter()
+            .chain(&other.words[other_cut..])
+            .copied()
+            .collect();
+
+        let constants = self
+            .constants
+            .iter()
+            .enumerate()
+            .map(|(i, &own)| match other.constants.get(i) {
+                Some(&theirs) if rng.gen_bool(0.5) => theirs,
+                _ => own,
+            })
+            .collect();
+
+        let (rates, diet, body_size) = if rng.gen_bool(0.5) {
+            (other.rates, other.diet, other.body_size)
+        } else {
+            (self.rates, self.diet, self.body_size)
+        };
+
+        if words.is_empty() {
+            return self.clone();
+        }
+        Self {
+            words,
+            constants,
+            rates,
+            diet,
+            body_size,
+        }
     }
 
     /// Create a mutated copy of this genome
-    /// Each word has independent chances based on config rates
-    pub fn mutate(&self) -> Self {
-        let mut rng = rand::thread_rng();
+    /// Each word has independent chances based on the genome's own rates, or the
+    /// config's when rates do not evolve
+    ///
+    /// Also returns the list of word-level changes, indexed into the new genome.
+    pub fn mutate(&self, config: &MutationConfig, rng: &mut impl Rng) -> (Self, Vec<Mutation>) {
+        let rates = if config.evolve_rates {
+            self.rates
+        } else {
+            config.rates
+        };
         let mut new_words = Vec::new();
+        let mut mutations = Vec::new();
 
         for &word in &self.words {
-            let should_delete = rng.gen_range(0..100) < DELETION_RATE;
+            let should_delete = rng.gen_range(0.0..100.0) < rates.deletion;
 
             if should_delete {
                 // Skip this word (delete it)
+                mutations.push(Mutation::Deleted {
+                    index: new_words.len(),
+                    was: word,
+                });
                 continue;
             }
 
-            let should_mutate = rng.gen_range(0..100) < MUTATION_RATE;
-            let word_to_add = if should_mutate { Word::random() } else { word };
-
+            let should_mutate = rng.gen_range(0.0..100.0) < rates.substitution;
+            let word_to_add = if should_mutate {
+                Word::random(rng)
+            } else {
+                word
+            };
+
+            if should_mutate {
+                mutations.push(Mutation::Substituted {
+                    index: new_words.len(),
+                    was: word,
+                });
+            }
             new_words.push(word_to_add);
 
             // Check for duplication
-            let should_duplicate = rng.gen_range(0..100) < DUPLICATION_RATE;
+            let should_duplicate = rng.gen_range(0.0..100.0) < rates.duplication;
             if should_duplicate {
+                mutations.push(Mutation::Inserted {
+                    index: new_words.len(),
+                });
                 new_words.push(word_to_add);
             }
         }
 
+        if rng.gen_range(0..100) < config.insertion {
+            mutations.push(Self::insert_random_block(&mut new_words, rng));
+        }
+
+        if rng.gen_range(0..100) < config.block_duplication {
+            mutations.extend(Self::duplicate_block(&mut new_words, rng));
+        }
+
+        if rng.gen_range(0..100) < config.block_deletion {
+            mutations.extend(Self::delete_block(&mut new_words, rng));
+        }
+
+        if rng.gen_range(0..100) < config.inversion {
+            mutations.extend(Self::invert_segment(&mut new_words, rng));
+        }
+
+        if rng.gen_range(0..100) < config.translocation {
+            mutations.extend(Self::translocate_segment(&mut new_words, rng));
+        }
+
+        mutations.extend(Self::cap_length(
+            &mut new_words,
+            config.max_length,
+            config.bloat_control,
+            rng,
+        ));
+
+        // Substituted words and moved or reversed blocks can leave THEN/ELSE ahead of
+        // their IF
+        Self::drop_unopened_branches(&mut new_words);
+
         // Ensure genome doesn't become empty
         if new_words.is_empty() {
-            new_words.push(Word::random());
+            new_words.push(Word::random(rng));
         }
 
         // Balance IF/THEN/ELSE
         Self::balance_control_flow(&mut new_words);
 
-        Self { words: new_words }
+        // THENs added for IFs left open by the cut can push the genome back over the cap;
+        // drop the last IF together with a THEN until it fits
+        while new_words.len() > config.max_length.max(1)
+            && let Some(last_if) = new_words.iter().rposition(|w| *w == Word::If)
+        {
+            new_words.remove(last_if);
+            Self::balance_control_flow(&mut new_words);
+        }
+
+        let genome = Self {
+            words: new_words,
+            constants: self.mutate_constants(rng),
+            rates: if config.evolve_rates {
+                self.rates.mutate(rng)
+            } else {
+                self.rates
+            },
+            diet: self.diet.mutate(rng),
+            body_size: Self::mutate_body_size(self.body_size, rng),
+        };
+        (genome, mutations)
+    }
+
+    /// Pick the start and length of a random run of BLOCK_MIN_LENGTH to BLOCK_MAX_LENGTH
+    /// (but at most `max_length`) words out of `len`, if there is room for one
+    fn random_block(len: usize, max_length: usize, rng: &mut impl Rng) -> Option<(usize, usize)> {
+        let max_length = max_length.min(BLOCK_MAX_LENGTH);
+        if max_length < BLOCK_MIN_LENGTH {
+            return None;
+        }
+        let length = rng.gen_range(BLOCK_MIN_LENGTH..=max_length);
+        let start = rng.gen_range(0..=len - length);
+        Some((start, length))
+    }
+
+    /// Splice one to INSERTION_MAX_LENGTH new random words in at a random position
+    fn insert_random_block(words: &mut Vec<Word>, rng: &mut impl Rng) -> Mutation {
+        let length = rng.gen_range(1..=INSERTION_MAX_LENGTH.max(1));
+        let index = rng.gen_range(0..=words.len());
+        words.splice(index..index, (0..length).map(|_| Word::random(rng)));
+        Mutation::Spliced { index, length }
+    }
+
+    /// Insert a copy of a random run of words right after it
+    fn duplicate_block(words: &mut Vec<Word>, rng: &mut impl Rng) -> Option<Mutation> {
+        let (start, length) = Self::random_block(words.len(), words.len(), rng)?;
+        let index = start + length;
+        let block = words[start..index].to_vec();
+        words.splice(index..index, block);
+        Some(Mutation::BlockInserted { index, length })
+    }
+
+    /// Remove a random run of words, always keeping at least one
+    fn delete_block(words: &mut Vec<Word>, rng: &mut impl Rng) -> Option<Mutation> {
+        let max_length = words.len().saturating_sub(1);
+        let (index, length) = Self::random_block(words.len(), max_length, rng)?;
+        words.drain(index..index + length);
+        Some(Mutation::BlockDeleted { index, length })
+    }
+
+    /// Copy a random run of 1 to `max_length` words, for horizontal gene transfer
+    pub fn sample_segment(&self, max_length: usize, rng: &mut impl Rng) -> Option<Vec<Word>> {
+        let max_length = max_length.min(self.words.len());
+        if max_length == 0 {
+            return None;
+        }
+        let length = rng.gen_range(1..=max_length);
+        let start = rng.gen_range(0..=self.words.len() - length);
+        Some(self.words[start..start + length].to_vec())
+    }
+
+    /// Copy of this genome with a segment received from another animal inserted at a
+    /// random position
+    pub fn with_transferred(&self, segment: &[Word], rng: &mut impl Rng) -> (Self, Mutation) {
+        let mut genome = self.clone();
+        let index = rng.gen_range(0..=genome.words.len());
+        genome.words.splice(index..index, segment.iter().copied());
+        Self::balance_control_flow(&mut genome.words);
+        let mutation = Mutation::Transferred {
+            index,
+            length: segment.len(),
+        };
+        (genome, mutation)
+    }
+
+    /// Scale body size by log-normal noise, within MIN_BODY_SIZE..=MAX_BODY_SIZE
+    fn mutate_body_size(size: f32, rng: &mut impl Rng) -> f32 {
+        let noise = Normal::new(0.0, BODY_SIZE_MUTATION_SIGMA).unwrap();
+        (size * noise.sample(rng).exp()).clamp(MIN_BODY_SIZE, MAX_BODY_SIZE)
+    }
+
+    /// Cut a genome longer than `max_length` (at least one word) back to it
+    fn cap_length(
+        words: &mut Vec<Word>,
+        max_length: usize,
+        strategy: BloatControl,
+        rng: &mut impl Rng,
+    ) -> Option<Mutation> {
+        let length = words
+            .len()
+            .checked_sub(max_length.max(1))
+            .filter(|&n| n > 0)?;
+        let index = match strategy {
+            BloatControl::DropTail => words.len() - length,
+            BloatControl::DropSegment => rng.gen_range(0..=words.len() - length),
+        };
+        words.drain(index..index + length);
+        Some(Mutation::BlockDeleted { index, length })
+    }
+
+    /// Reverse a random run of at least two words
+    fn invert_segment(words: &mut [Word], rng: &mut impl Rng) -> Option<Mutation> {
+        if words.len() < 2 {
+            return None;
+        }
+        let length = rng.gen_range(2..=words.len().min(INVERSION_MAX_LENGTH));
+        let start = rng.gen_range(0..=words.len() - length);
+        let end = start + length;
+        words[start..end].reverse();
+        Some(Mutation::Inverted { start, end })
+    }
+
+    /// Cut a random run of words and reinsert it somewhere else
+    fn translocate_segment(words: &mut Vec<Word>, rng: &mut impl Rng) -> Option<Mutation> {
+        if words.len() < 2 {
+            return None;
+        }
+        let length = rng.gen_range(1..=(words.len() - 1).min(TRANSLOCATION_MAX_LENGTH));
+        let from = rng.gen_range(0..=words.len() - length);
+        let block: Vec<Word> = words.drain(from..from + length).collect();
+
+        // Any gap in the remaining words except the one the block came from
+        let mut to = rng.gen_range(0..words.len());
+        if to >= from {
+            to += 1;
+        }
+        words.splice(to..to, block);
+        Some(Mutation::Translocated { from, to, length })
+    }
+
+    /// Remove THEN and ELSE words that come before any open IF
+    fn drop_unopened_branches(words: &mut Vec<Word>) {
+        let mut depth = 0usize;
+        words.retain(|word| match word {
+            Word::If => {
+                depth += 1;
+                true
+            }
+            Word::Then if depth > 0 => {
+                depth -= 1;
+                true
+            }
+            Word::Then | Word::Else => depth > 0,
+            _ => true,
+        });
+    }
+
+    /// Jitter constants with gaussian noise proportional to their magnitude
+    fn mutate_constants(&self, rng: &mut impl Rng) -> Vec<f32> {
+        let noise = Normal::new(0.0, CONSTANT_MUTATION_SIGMA).unwrap();
+
+        self.constants
+            .iter()
+            .map(|&value| {
+                if rng.gen_range(0..100) < CONSTANT_MUTATION_RATE {
+                    // Scale by at least 1.0 so constants near zero can still move
+                    value + noise.sample(rng) * value.abs().max(1.0)
+                } else {
+                    value
+                }
+            })
+            .collect()
     }
 
     /// Balance IF/THEN/ELSE to ensure valid control flow
@@ -444,47 +1751,295 @@ impl Genome {
     }
 }
 
-/// Control flow context for tracking IF/THEN/ELSE
-#[derive(Debug, Clone)]
-pub struct IfContext {
-    pub if_position: usize,
-    pub else_position: Option<usize>,
-    pub then_position: Option<usize>,
-    pub condition_result: bool,
-    pub in_else_branch: bool,
+/// What happens when a word pushes onto a full stack
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum StackOverflowPolicy {
+    /// Discard the bottom of the stack to make room
+    DropOldest,
+    /// Discard the value being pushed
+    #[default]
+    DropNew,
+    /// Discard the value and count the whole word as skipped, like a stack underflow
+    SkipWord,
+    /// Treat the overflow as a fatal VM error
+    Kill,
+}
+
+impl StackOverflowPolicy {
+    pub const ALL: [StackOverflowPolicy; 4] = [
+        StackOverflowPolicy::DropOldest,
+        StackOverflowPolicy::DropNew,
+        StackOverflowPolicy::SkipWord,
+        StackOverflowPolicy::Kill,
+    ];
+}
+
+impl fmt::Display for StackOverflowPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StackOverflowPolicy::DropOldest => write!(f, "drop-oldest"),
+            StackOverflowPolicy::DropNew => write!(f, "drop-new"),
+            StackOverflowPolicy::SkipWord => write!(f, "skip-word"),
+            StackOverflowPolicy::Kill => write!(f, "kill"),
+        }
+    }
+}
+
+impl std::str::FromStr for StackOverflowPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|policy| policy.to_string() == s)
+            .ok_or_else(|| format!("unknown overflow policy '{}'", s))
+    }
+}
+
+/// How the VM stores floats and bools
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum StackMode {
+    /// One stack holding both; popping the wrong type skips the word
+    #[default]
+    Mixed,
+    /// Experimental: a float stack and a separate bool stack, each with the full
+    /// capacity. Words pop the type they need, so no word is skipped for a type
+    /// mismatch; stack words (dup, swap, ...) act on the float stack
+    Typed,
+}
+
+impl StackMode {
+    pub const ALL: [StackMode; 2] = [StackMode::Mixed, StackMode::Typed];
+}
+
+impl fmt::Display for StackMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StackMode::Mixed => write!(f, "mixed"),
+            StackMode::Typed => write!(f, "typed"),
+        }
+    }
+}
+
+impl std::str::FromStr for StackMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|mode| mode.to_string() == s)
+            .ok_or_else(|| format!("unknown stack mode '{}'", s))
+    }
+}
+
+/// Runtime settings for the genome virtual machine
+#[derive(Resource)]
+pub struct VmSettings {
+    pub stack_capacity: usize,
+    pub overflow_policy: StackOverflowPolicy,
+    /// Clamp arithmetic results to +-SAFE_MATH_LIMIT and turn NaN into 0, so runaway
+    /// values never reach movement words
+    pub safe_math: bool,
+    pub stack_mode: StackMode,
+}
+
+impl Default for VmSettings {
+    fn default() -> Self {
+        Self {
+            stack_capacity: STACK_CAPACITY,
+            overflow_policy: StackOverflowPolicy::default(),
+            safe_math: false,
+            stack_mode: StackMode::default(),
+        }
+    }
+}
+
+/// One executed instruction recorded in an execution trace
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct TraceEntry {
+    pub instruction_pointer: usize,
+    pub word: Word,
+    /// Top of the stack after the word ran
+    pub stack_top: Option<StackValue>,
 }
 
 /// Execution state for a genome
-#[derive(Component)]
+#[derive(Component, Serialize, Deserialize)]
 pub struct GenomeExecutor {
     pub instruction_pointer: usize,
+    /// The shared stack, or only the floats under `StackMode::Typed`
     pub stack: Vec<StackValue>,
+    /// Bools under `StackMode::Typed`; empty otherwise
+    #[serde(default)]
+    pub bool_stack: Vec<bool>,
+    #[serde(default)]
+    pub stack_mode: StackMode,
+    /// Whether the current word pushed to the bool stack, so its trace entry shows that
+    /// top instead of the float one
+    #[serde(skip)]
+    wrote_bool: bool,
     pub instructions_executed_this_frame: u32,
     pub max_instructions_per_frame: u32,
-    pub if_stack: Vec<IfContext>,
     pub jump_table: Vec<(usize, Option<usize>, usize)>, // (if_pos, else_pos, then_pos)
     pub label_table: [Option<usize>; 4], // Maps label index (0-3) to position in genome
+    /// Most recently executed instructions, only recorded while tracing is enabled
+    pub trace: Option<VecDeque<TraceEntry>>,
+    /// Executed instructions not yet written by a trace export, only collected while
+    /// exporting
+    #[serde(skip)]
+    exported: Option<Vec<TraceEntry>>,
+    /// Condition checked against every executed instruction, set from a `Watchpoint`
+    #[serde(skip)]
+    pub watch: Option<WatchCondition>,
+    /// First instruction that met the watch condition since the last check
+    #[serde(skip)]
+    watch_hit: Option<TraceEntry>,
+    pub stack_capacity: usize,
+    pub overflow_policy: StackOverflowPolicy,
+    /// Number of pushes that hit a full stack over this animal's lifetime
+    pub stack_overflows: u32,
+    /// Number of words skipped (stack underflow, type mismatch or overflow) over this
+    /// animal's lifetime
+    pub words_skipped: u32,
+    #[serde(default)]
+    pub safe_math: bool,
+    /// Number of arithmetic results clamped or zeroed by safe math over this animal's
+    /// lifetime
+    #[serde(default)]
+    pub saturations: u32,
+    /// Policy of an overflow that must change the current word's outcome; checked after
+    /// each word
+    #[serde(skip)]
+    pending_overflow: Option<StackOverflowPolicy>,
+    /// Most instructions per frame regardless of energy; lowered while starving if the
+    /// starvation warning reduces budgets
+    #[serde(default = "default_budget_cap")]
+    pub budget_cap: u32,
+}
+
+fn default_budget_cap() -> u32 {
+    MAX_INSTRUCTIONS_PER_FRAME
 }
 
 impl GenomeExecutor {
     pub fn new(energy: u32) -> Self {
         Self {
             instruction_pointer: 0,
-            stack: Vec::with_capacity(256),
+            stack: Vec::with_capacity(STACK_CAPACITY),
+            bool_stack: Vec::new(),
+            stack_mode: StackMode::default(),
+            wrote_bool: false,
             instructions_executed_this_frame: 0,
-            max_instructions_per_frame: (energy * 1).min(MAX_INSTRUCTIONS_PER_FRAME),
-            if_stack: Vec::new(),
+            max_instructions_per_frame: energy.min(MAX_INSTRUCTIONS_PER_FRAME),
             jump_table: Vec::new(),
             label_table: [None; 4],
+            trace: None,
+            exported: None,
+            watch: None,
+            watch_hit: None,
+            stack_capacity: STACK_CAPACITY,
+            overflow_policy: StackOverflowPolicy::default(),
+            stack_overflows: 0,
+            words_skipped: 0,
+            safe_math: false,
+            saturations: 0,
+            pending_overflow: None,
+            budget_cap: MAX_INSTRUCTIONS_PER_FRAME,
+        }
+    }
+
+    /// Pick up the current VM settings, trimming the oldest values if the stack shrank.
+    /// Switching stack mode moves the bools between the shared and the bool stack
+    pub fn apply_settings(&mut self, settings: &VmSettings) {
+        self.stack_capacity = settings.stack_capacity;
+        self.overflow_policy = settings.overflow_policy;
+        self.safe_math = settings.safe_math;
+        if self.stack_mode != settings.stack_mode {
+            self.stack_mode = settings.stack_mode;
+            match self.stack_mode {
+                StackMode::Typed => {
+                    let bools = self.stack.iter().filter_map(StackValue::as_bool);
+                    self.bool_stack = bools.collect();
+                    self.stack.retain(|value| value.as_float().is_some());
+                }
+                StackMode::Mixed => {
+                    let bools = self.bool_stack.drain(..).map(StackValue::Bool);
+                    self.stack.extend(bools);
+                }
+            }
+        }
+        trim_oldest(&mut self.stack, self.stack_capacity);
+        trim_oldest(&mut self.bool_stack, self.stack_capacity);
+    }
+
+    /// Overflow under the SkipWord or Kill policy since the last check, if any
+    pub fn take_overflow(&mut self) -> Option<StackOverflowPolicy> {
+        self.pending_overflow.take()
+    }
+
+    /// Turn execution tracing on or off; disabling discards the recorded trace
+    pub fn set_tracing(&mut self, enabled: bool) {
+        if enabled != self.trace.is_some() {
+            self.trace = enabled.then(|| VecDeque::with_capacity(EXECUTION_TRACE_LENGTH));
+        }
+    }
+
+    /// Turn collection of instructions for a trace export on or off
+    pub fn set_exporting(&mut self, enabled: bool) {
+        if enabled != self.exported.is_some() {
+            self.exported = enabled.then(Vec::new);
+        }
+    }
+
+    /// Instructions executed since the last call, while exporting
+    pub fn take_exported(&mut self) -> Vec<TraceEntry> {
+        self.exported
+            .as_mut()
+            .map(std::mem::take)
+            .unwrap_or_default()
+    }
+
+    /// Instruction that met the watch condition since the last call, if any
+    pub fn take_watch_hit(&mut self) -> Option<TraceEntry> {
+        self.watch_hit.take()
+    }
+
+    /// Record an executed instruction if tracing or exporting is enabled, and keep it if
+    /// it is the first to meet the watch condition. The recorded top is that of the
+    /// stack the word pushed to
+    pub fn record_trace(&mut self, instruction_pointer: usize, word: Word) {
+        let entry = TraceEntry {
+            instruction_pointer,
+            word,
+            stack_top: if std::mem::take(&mut self.wrote_bool) {
+                self.bool_stack.last().copied().map(StackValue::Bool)
+            } else {
+                self.stack.last().copied()
+            },
+        };
+        if self.watch_hit.is_none()
+            && self
+                .watch
+                .is_some_and(|condition| condition.matches(&entry))
+        {
+            self.watch_hit = Some(entry);
+        }
+        if let Some(trace) = self.trace.as_mut() {
+            if trace.len() >= EXECUTION_TRACE_LENGTH {
+                trace.pop_front();
+            }
+            trace.push_back(entry);
+        }
+        if let Some(exported) = self.exported.as_mut() {
+            exported.push(entry);
         }
     }
 
     pub fn reset_for_frame(&mut self, energy: u32) {
         // DO NOT reset instruction_pointer (keep circular execution position)
         // DO NOT clear stack (persist values across frames)
-        self.if_stack.clear(); // Clear control flow only
         self.instructions_executed_this_frame = 0;
-        self.max_instructions_per_frame = (energy * 1).min(MAX_INSTRUCTIONS_PER_FRAME);
+        self.max_instructions_per_frame = energy.min(self.budget_cap);
     }
 
     pub fn can_execute(&self) -> bool {
@@ -504,61 +2059,62 @@ impl GenomeExecutor {
 
     /// Build jump table for IF/THEN/ELSE control flow
     pub fn build_jump_table(&mut self, genome: &Genome) {
-        self.jump_table.clear();
-        let mut if_stack: Vec<usize> = Vec::new();
-        let mut else_positions: std::collections::HashMap<usize, usize> =
-            std::collections::HashMap::new();
-
-        for (i, word) in genome.words.iter().enumerate() {
-            match word {
-                Word::If => {
-                    if_stack.push(i);
-                }
-                Word::Else => {
-                    if let Some(&if_pos) = if_stack.last() {
-                        else_positions.insert(if_pos, i);
-                    }
-                }
-                Word::Then => {
-                    if let Some(if_pos) = if_stack.pop() {
-                        let else_pos = else_positions.get(&if_pos).copied();
-                        self.jump_table.push((if_pos, else_pos, i));
-                    }
-                }
-                _ => {}
-            }
-        }
+        self.jump_table = genome.jump_table();
     }
 
     /// Build label table for jump targets
     pub fn build_label_table(&mut self, genome: &Genome) {
-        // Reset all labels to None
-        self.label_table = [None; 4];
+        self.label_table = genome.label_table();
+    }
 
-        // Scan genome for label positions
-        for (i, word) in genome.words.iter().enumerate() {
-            match word {
-                Word::Label0 => self.label_table[0] = Some(i),
-                Word::Label1 => self.label_table[1] = Some(i),
-                Word::Label2 => self.label_table[2] = Some(i),
-                Word::Label3 => self.label_table[3] = Some(i),
-                _ => {}
+    /// Push a value to its stack, applying the overflow policy when full
+    pub fn push(&mut self, value: StackValue) {
+        let (capacity, policy) = (self.stack_capacity, self.overflow_policy);
+        let overflowed = match (self.stack_mode, value) {
+            (StackMode::Typed, StackValue::Bool(value)) => {
+                self.wrote_bool = true;
+                push_bounded(&mut self.bool_stack, value, capacity, policy)
+            }
+            _ => push_bounded(&mut self.stack, value, capacity, policy),
+        };
+        if overflowed {
+            self.stack_overflows += 1;
+            if matches!(
+                policy,
+                StackOverflowPolicy::SkipWord | StackOverflowPolicy::Kill
+            ) {
+                self.pending_overflow = Some(policy);
             }
         }
     }
 
     /// Push float to stack
     pub fn push_float(&mut self, value: f32) {
-        if self.stack.len() < 256 {
-            self.stack.push(StackValue::Float(value));
-        }
+        self.push(StackValue::Float(value));
+    }
+
+    /// Push the result of an arithmetic word, made finite first under safe math
+    pub fn push_result(&mut self, value: f32) {
+        let value = if self.safe_math {
+            let safe = if value.is_nan() {
+                0.0
+            } else {
+                value.clamp(-SAFE_MATH_LIMIT, SAFE_MATH_LIMIT)
+            };
+            // NaN never equals itself, so it counts too
+            if safe != value {
+                self.saturations += 1;
+            }
+            safe
+        } else {
+            value
+        };
+        self.push_float(value);
     }
 
     /// Push bool to stack
     pub fn push_bool(&mut self, value: bool) {
-        if self.stack.len() < 256 {
-            self.stack.push(StackValue::Bool(value));
-        }
+        self.push(StackValue::Bool(value));
     }
 
     /// Pop float from stack
@@ -568,7 +2124,10 @@ impl GenomeExecutor {
 
     /// Pop bool from stack
     pub fn pop_bool(&mut self) -> Option<bool> {
-        self.stack.pop()?.as_bool()
+        match self.stack_mode {
+            StackMode::Mixed => self.stack.pop()?.as_bool(),
+            StackMode::Typed => self.bool_stack.pop(),
+        }
     }
 
     /// Pop any value from stack
@@ -582,11 +2141,606 @@ impl GenomeExecutor {
     }
 }
 
-/// Sensor data for an animal (4 directional smell sensors)
-#[derive(Component, Default)]
+/// Push onto a stack holding at most `capacity` values; when full, make room only under
+/// the DropOldest policy. Returns whether the stack was full
+fn push_bounded<T>(
+    stack: &mut Vec<T>,
+    value: T,
+    capacity: usize,
+    policy: StackOverflowPolicy,
+) -> bool {
+    if stack.len() < capacity {
+        stack.push(value);
+        return false;
+    }
+    if policy == StackOverflowPolicy::DropOldest && !stack.is_empty() {
+        stack.remove(0);
+        stack.push(value);
+    }
+    true
+}
+
+/// Drop the bottom of a stack until it holds at most `capacity` values
+fn trim_oldest<T>(stack: &mut Vec<T>, capacity: usize) {
+    if stack.len() > capacity {
+        let excess = stack.len() - capacity;
+        stack.drain(..excess);
+    }
+}
+
+/// Kind of thing an animal sees first in its vision cone
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+pub enum SightKind {
+    Plant,
+    Animal,
+}
+
+impl fmt::Display for SightKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SightKind::Plant => write!(f, "plant"),
+            SightKind::Animal => write!(f, "animal"),
+        }
+    }
+}
+
+/// Source a scent channel smells
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScentChannel {
+    Plant,
+    Animal,
+    /// Lingering scent where an animal died
+    Corpse,
+}
+
+impl ScentChannel {
+    pub const ALL: [ScentChannel; 3] = [
+        ScentChannel::Plant,
+        ScentChannel::Animal,
+        ScentChannel::Corpse,
+    ];
+}
+
+impl fmt::Display for ScentChannel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScentChannel::Plant => write!(f, "plant"),
+            ScentChannel::Animal => write!(f, "animal"),
+            ScentChannel::Corpse => write!(f, "corpse"),
+        }
+    }
+}
+
+/// A directional sensor's reading in each quadrant around an animal
+#[derive(Debug, Clone, Copy, Default, PartialEq, Reflect)]
+pub struct Quadrants {
+    pub front: Option<f32>,
+    pub back: Option<f32>,
+    pub left: Option<f32>,
+    pub right: Option<f32>,
+}
+
+impl Quadrants {
+    /// The same reading in every quadrant
+    pub fn splat(value: Option<f32>) -> Self {
+        Self {
+            front: value,
+            back: value,
+            left: value,
+            right: value,
+        }
+    }
+}
+
+/// Sensor data for an animal (4 directional smell sensors per scent channel, 4
+/// directional animal sensors, 4 directional hearing sensors, forward vision plus
+/// contact)
+#[derive(Component, Reflect, Default)]
+#[reflect(Component)]
 pub struct Sensors {
-    pub smell_front: Option<f32>,
-    pub smell_back: Option<f32>,
-    pub smell_left: Option<f32>,
-    pub smell_right: Option<f32>,
+    /// Scent of each channel in each quadrant, as reported by the current `ScentModel`
+    pub plant_scent: Quadrants,
+    pub animal_scent: Quadrants,
+    pub corpse_scent: Quadrants,
+    /// Distance to the nearest other animal in each quadrant
+    pub nearest_animal: Quadrants,
+    /// Distance to the nearest call made last tick by another animal in each quadrant
+    pub hearing: Quadrants,
+    pub touching_plant: bool,
+    pub touching_animal: bool,
+    /// Energy is below the starvation threshold
+    pub starving: bool,
+    /// Distance to and kind of the nearest plant or animal inside the vision cone
+    pub sight: Option<(f32, SightKind)>,
+    /// Pheromone level in the grid cell the animal stands in
+    pub pheromone: f32,
+    /// Number of plants and other animals whose bodies overlap this animal's body
+    pub contacts: u32,
+    /// Energy of the nearest plant within sensing range; None (read as 0) if there is none
+    pub nearest_plant_energy: Option<u32>,
+    /// Number of other animals within `CROWDING_RADIUS`
+    pub crowding: u32,
+    /// Facing direction as a fraction of a full turn, growing as the animal turns left
+    pub heading: f32,
+    /// World position; the world spans ±WORLD_BOUNDS on both axes around the origin
+    pub position: Vec2,
+    /// Distance moved since the previous sensor update
+    pub speed: f32,
+    /// Change in heading since the previous sensor update, as a fraction of a turn
+    /// between -0.5 and 0.5; positive when turning left
+    pub turn_rate: f32,
+    /// Whether `position` and `heading` hold a reading to measure motion against
+    pub tracked: bool,
+}
+
+impl Sensors {
+    pub fn scent(&self, channel: ScentChannel) -> &Quadrants {
+        match channel {
+            ScentChannel::Plant => &self.plant_scent,
+            ScentChannel::Animal => &self.animal_scent,
+            ScentChannel::Corpse => &self.corpse_scent,
+        }
+    }
+
+    pub fn scent_mut(&mut self, channel: ScentChannel) -> &mut Quadrants {
+        match channel {
+            ScentChannel::Plant => &mut self.plant_scent,
+            ScentChannel::Animal => &mut self.animal_scent,
+            ScentChannel::Corpse => &mut self.corpse_scent,
+        }
+    }
+
+    /// Record this update's position and heading, measuring speed and turn rate against
+    /// the previous ones. A fresh animal reads as still on its first update
+    pub fn track_motion(&mut self, position: Vec2, heading: f32) {
+        if self.tracked {
+            self.speed = position.distance(self.position);
+            self.turn_rate = (heading - self.heading + 0.5).rem_euclid(1.0) - 0.5;
+        } else {
+            self.speed = 0.0;
+            self.turn_rate = 0.0;
+        }
+        self.position = position;
+        self.heading = heading;
+        self.tracked = true;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn genome_serde_round_trip() {
+        let mut genome = Genome::seed();
+        genome.words.push(Word::PushFloat(f32::NAN));
+        genome.words.push(Word::PushFloat(0.1));
+
+        let json = serde_json::to_string(&genome).unwrap();
+        assert!(json.contains(&format!("\"version\":{}", GENOME_FORMAT_VERSION)));
+
+        let loaded: Genome = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.constants, genome.constants);
+        assert_eq!(loaded.to_source(), genome.to_source());
+    }
+
+    #[test]
+    fn push_const_pushes_from_the_pool() {
+        use crate::vm::{Body, GenomeVm};
+
+        // Indices past the end wrap around the pool
+        let genome =
+            Genome::parse(".constants 1.5 -2.0 7.25\nconst0 const2 const1 const4").unwrap();
+        let mut executor = GenomeExecutor::new(100);
+        let mut body = Body {
+            energy: 100,
+            split_ratio: DEFAULT_SPLIT_RATIO,
+            age: 0.0,
+            lifespan: MAX_LIFESPAN,
+            split_cost: SPLIT_ENERGY_COST,
+        };
+        let mut vm = GenomeVm::new(&genome, &mut executor);
+        for _ in 0..genome.words.len() {
+            vm.step(&mut body, &Sensors::default());
+        }
+        assert_eq!(
+            executor.stack,
+            [1.5, 7.25, -2.0, -2.0].map(StackValue::Float).to_vec()
+        );
+    }
+
+    #[test]
+    fn constant_mutation_jitters_values() {
+        let genome = Genome::parse(".constants 100.0 -3.0 0.0 250.0\nnop").unwrap();
+        use rand::SeedableRng;
+        let mut rng = rand::rngs::StdRng::seed_from_u64(7);
+        let mut changed = 0;
+        for _ in 0..200 {
+            let mutated = genome.mutate_constants(&mut rng);
+            assert_eq!(mutated.len(), genome.constants.len());
+            for (&old, &new) in genome.constants.iter().zip(&mutated) {
+                if new != old {
+                    changed += 1;
+                    // A replacement would land anywhere; jitter stays near the old value
+                    let scale = old.abs().max(1.0) * CONSTANT_MUTATION_SIGMA;
+                    assert!((new - old).abs() < 10.0 * scale, "{old} -> {new}");
+                }
+            }
+        }
+        // About CONSTANT_MUTATION_RATE% of 800 draws
+        assert!(changed > 20 && changed < 200, "{changed} constants changed");
+    }
+
+    #[test]
+    fn constant_pool_survives_serialization() {
+        let genome = Genome::parse(".constants 0.1 -42.5 1e-7 300.0\nconst1 const3").unwrap();
+
+        let json: Genome = serde_json::from_str(&serde_json::to_string(&genome).unwrap()).unwrap();
+        assert_eq!(json.constants, genome.constants);
+        let ron: Genome = ron::from_str(&ron::to_string(&genome).unwrap()).unwrap();
+        assert_eq!(ron.constants, genome.constants);
+        assert_eq!(ron.words, genome.words);
+        // And through genome source, which writes constants with `{:?}`
+        assert_eq!(
+            Genome::parse(&genome.to_source()).unwrap().constants,
+            genome.constants
+        );
+    }
+
+    #[test]
+    fn unversioned_genome_is_migrated() {
+        let json =
+            r#"{"words":["Dup",{"PushFloat":1.5},{"PushConst":3},"SetColor"],"constants":[2.0]}"#;
+        let genome: Genome = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            genome.words,
+            vec![
+                Word::Dup,
+                Word::PushFloat(1.5),
+                Word::PushConst(3),
+                Word::SetColor
+            ]
+        );
+        assert_eq!(genome.constants, vec![2.0]);
+    }
+
+    #[test]
+    fn crossover_joins_parent_prefix_and_partner_suffix() {
+        let parent = Genome::parse(".constants 1.0 2.0\ndup dup dup dup").unwrap();
+        let partner = Genome::parse(".constants 3.0 4.0\nnop nop nop nop").unwrap();
+
+        use rand::SeedableRng;
+        let mut rng = rand::rngs::StdRng::seed_from_u64(7);
+        for _ in 0..50 {
+            let child = parent.crossover(&partner, &mut rng);
+            assert!(!child.words.is_empty());
+            let split = child.words.iter().take_while(|w| **w == Word::Dup).count();
+            assert!(child.words[split..].iter().all(|w| *w == Word::Nop));
+            assert_eq!(child.words.len(), 4);
+            assert_eq!(child.constants.len(), 2);
+        }
+    }
+
+    #[test]
+    fn diff_counts_edits() {
+        let base = Genome::parse("dup drop swap over").unwrap();
+        let evolved = Genome::parse("dup nop swap over eat eat").unwrap();
+        assert_eq!(
+            evolved.diff(&base),
+            GenomeDiff {
+                substituted: 1,
+                inserted: 2,
+                deleted: 0,
+                unchanged: 3,
+            }
+        );
+        let shortened = Genome::parse("dup swap over").unwrap();
+        assert_eq!(shortened.diff(&base).deleted, 1);
+        assert_eq!(shortened.diff(&base).unchanged, 3);
+        assert_eq!(base.diff(&base).distance(), 0);
+    }
+
+    #[test]
+    fn newer_genome_version_is_rejected() {
+        let json = format!(
+            r#"{{"version":{},"words":["dup"],"constants":[]}}"#,
+            GENOME_FORMAT_VERSION + 1
+        );
+        assert!(serde_json::from_str::<Genome>(&json).is_err());
+    }
+
+    #[test]
+    fn inversion_reverses_a_contiguous_run() {
+        use rand::SeedableRng;
+
+        let original = Genome::parse("dup drop swap over eat split nop rot").unwrap();
+        let mut rng = rand::rngs::StdRng::seed_from_u64(7);
+        for _ in 0..50 {
+            let mut words = original.words.clone();
+            let Some(Mutation::Inverted { start, end }) =
+                Genome::invert_segment(&mut words, &mut rng)
+            else {
+                panic!("expected an inversion");
+            };
+            assert!(end - start >= 2);
+            assert_eq!(words[..start], original.words[..start]);
+            assert_eq!(words[end..], original.words[end..]);
+            assert!(
+                words[start..end]
+                    .iter()
+                    .eq(original.words[start..end].iter().rev())
+            );
+        }
+        assert!(Genome::invert_segment(&mut [Word::Dup], &mut rng).is_none());
+    }
+
+    #[test]
+    fn translocation_moves_a_contiguous_run() {
+        use rand::SeedableRng;
+
+        let original = Genome::parse("dup drop swap over eat split nop rot").unwrap();
+        let mut rng = rand::rngs::StdRng::seed_from_u64(7);
+        for _ in 0..50 {
+            let mut words = original.words.clone();
+            let Some(Mutation::Translocated { from, to, length }) =
+                Genome::translocate_segment(&mut words, &mut rng)
+            else {
+                panic!("expected a translocation");
+            };
+            assert_ne!(from, to);
+            assert_eq!(words[to..to + length], original.words[from..from + length]);
+
+            // Taking the block back out leaves the rest in order
+            let mut rest = words.clone();
+            rest.drain(to..to + length);
+            let mut expected = original.words.clone();
+            expected.drain(from..from + length);
+            assert_eq!(rest, expected);
+        }
+        assert!(Genome::translocate_segment(&mut vec![Word::Dup], &mut rng).is_none());
+    }
+
+    #[test]
+    fn unopened_branches_are_dropped() {
+        let mut words = Genome::parse("then dup else if drop else then then")
+            .unwrap()
+            .words;
+        Genome::drop_unopened_branches(&mut words);
+        assert_eq!(words, Genome::parse("dup if drop else then").unwrap().words);
+    }
+
+    #[test]
+    fn block_duplication_and_deletion() {
+        use rand::SeedableRng;
+
+        let original = Genome::parse("dup drop swap over eat split nop rot").unwrap();
+        let mut rng = rand::rngs::StdRng::seed_from_u64(7);
+        for _ in 0..50 {
+            let mut words = original.words.clone();
+            let Some(Mutation::BlockInserted { index, length }) =
+                Genome::duplicate_block(&mut words, &mut rng)
+            else {
+                panic!("expected a block duplication");
+            };
+            assert!((BLOCK_MIN_LENGTH..=BLOCK_MAX_LENGTH).contains(&length));
+            assert_eq!(words[index..index + length], words[index - length..index]);
+            words.drain(index..index + length);
+            assert_eq!(words, original.words);
+
+            let mut words = original.words.clone();
+            let Some(Mutation::BlockDeleted { index, length }) =
+                Genome::delete_block(&mut words, &mut rng)
+            else {
+                panic!("expected a block deletion");
+            };
+            assert_eq!(words.len(), original.words.len() - length);
+            assert_eq!(words[..index], original.words[..index]);
+            assert_eq!(words[index..], original.words[index + length..]);
+        }
+        assert!(Genome::delete_block(&mut vec![Word::Dup, Word::Drop], &mut rng).is_none());
+    }
+
+    #[test]
+    fn insertion_splices_new_words_anywhere() {
+        use rand::SeedableRng;
+
+        let original = Genome::parse("dup drop swap").unwrap();
+        let mut rng = rand::rngs::StdRng::seed_from_u64(3);
+        let mut positions = std::collections::HashSet::new();
+        for _ in 0..200 {
+            let mut words = original.words.clone();
+            let Mutation::Spliced { index, length } =
+                Genome::insert_random_block(&mut words, &mut rng)
+            else {
+                panic!("expected an insertion");
+            };
+            assert!((1..=INSERTION_MAX_LENGTH).contains(&length));
+            assert_eq!(words.len(), original.words.len() + length);
+            words.drain(index..index + length);
+            assert_eq!(words, original.words);
+            positions.insert(index);
+        }
+        // Before the first word, between any two and after the last
+        assert_eq!(positions.len(), original.words.len() + 1);
+    }
+
+    #[test]
+    fn mutation_rates_are_inherited_and_bounded() {
+        let mut genome = Genome::seed();
+        genome.rates.deletion = MAX_EVOLVED_RATE;
+
+        let json = serde_json::to_string(&genome).unwrap();
+        let loaded: Genome = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.rates, genome.rates);
+
+        let without_rates = r#"{"version":1,"words":["dup"],"constants":[]}"#;
+        let loaded: Genome = serde_json::from_str(without_rates).unwrap();
+        assert_eq!(loaded.rates, MutationRates::default());
+
+        let mut rng = rand::thread_rng();
+        let mut rates = genome.rates;
+        for _ in 0..1000 {
+            rates = rates.mutate(&mut rng);
+            for rate in [rates.substitution, rates.duplication, rates.deletion] {
+                assert!((MIN_EVOLVED_RATE..=MAX_EVOLVED_RATE).contains(&rate));
+            }
+        }
+    }
+
+    #[test]
+    fn random_words_keep_the_original_mix() {
+        const SAMPLES: usize = 200_000;
+        use rand::SeedableRng;
+        let mut rng = rand::rngs::StdRng::seed_from_u64(7);
+        let words: Vec<Word> = (0..SAMPLES).map(|_| Word::random(&mut rng)).collect();
+        let share = |matches: &dyn Fn(&Word) -> bool| {
+            words.iter().filter(|word| matches(word)).count() as f64 / SAMPLES as f64
+        };
+
+        // Shares of the original table, each scaled down by the extended words' share
+        let original = 1.0 - EXTENDED_WORD_SHARE;
+        for (name, expected, actual) in [
+            (
+                "plant smell",
+                0.2,
+                share(&|word| {
+                    matches!(
+                        word,
+                        Word::SmellFront | Word::SmellBack | Word::SmellLeft | Word::SmellRight
+                    )
+                }),
+            ),
+            ("eat", 0.05, share(&|word| *word == Word::Eat)),
+            (
+                "move backward",
+                0.02,
+                share(&|word| *word == Word::MoveBackward),
+            ),
+            (
+                "jumps",
+                0.005,
+                share(&|word| {
+                    matches!(word, Word::Jump0 | Word::Jump1 | Word::Jump2 | Word::Jump3)
+                }),
+            ),
+            (
+                "dup, swap, energy",
+                0.00375,
+                share(&|word| matches!(word, Word::Dup | Word::Swap | Word::Energy)),
+            ),
+        ] {
+            let expected = expected * original;
+            assert!(
+                (actual - expected).abs() < expected * 0.2,
+                "{name}: {actual} vs {expected}"
+            );
+        }
+    }
+
+    #[test]
+    fn hue_depends_only_on_words() {
+        let genome = Genome::parse(".constants 1.0\ndup drop swap").unwrap();
+        let jittered = Genome::parse(".constants 2.0\ndup drop swap").unwrap();
+        let other = Genome::parse(".constants 1.0\ndup drop eat").unwrap();
+
+        assert!((0.0..360.0).contains(&genome.hue()));
+        assert_eq!(genome.hue(), jittered.hue());
+        assert_ne!(genome.hue(), other.hue());
+    }
+
+    #[test]
+    fn diet_efficiencies_trade_off() {
+        let mut rng = rand::thread_rng();
+        let mut diet = Diet::default();
+        assert_eq!(
+            Diet::digest(EAT_AMOUNT, diet.plant_efficiency()),
+            EAT_AMOUNT
+        );
+        for _ in 0..1000 {
+            diet = diet.mutate(&mut rng);
+            assert!((0.0..=1.0).contains(&diet.herbivory));
+            let total = diet.plant_efficiency() + diet.meat_efficiency();
+            assert!((total - DIET_TOTAL_EFFICIENCY).abs() < 1e-5);
+        }
+
+        let without_diet = r#"{"version":1,"words":["dup"],"constants":[]}"#;
+        let loaded: Genome = serde_json::from_str(without_diet).unwrap();
+        assert_eq!(loaded.diet, Diet::default());
+    }
+
+    #[test]
+    fn fixed_mutation_config_overrides_genome_rates() {
+        let mut genome = Genome::seed();
+        genome.rates.substitution = MAX_EVOLVED_RATE;
+        use rand::SeedableRng;
+        let mut rng = rand::rngs::StdRng::seed_from_u64(7);
+        let (child, mutations) = genome.mutate(&MutationConfig::disabled(), &mut rng);
+        assert!(mutations.is_empty());
+        assert_eq!(child.words, genome.words);
+        assert_eq!(child.rates, genome.rates);
+    }
+
+    #[test]
+    fn offspring_are_cut_back_to_the_length_cap() {
+        let genome = Genome::parse("dup drop swap over rot nop eat split").unwrap();
+        let mut config = MutationConfig::disabled();
+        config.max_length = 5;
+
+        use rand::SeedableRng;
+        let mut rng = rand::rngs::StdRng::seed_from_u64(7);
+        let (child, mutations) = genome.mutate(&config, &mut rng);
+        assert_eq!(child.words, genome.words[..5]);
+        assert_eq!(
+            mutations,
+            vec![Mutation::BlockDeleted {
+                index: 5,
+                length: 3
+            }]
+        );
+
+        config.bloat_control = BloatControl::DropSegment;
+        let nested = Genome::parse("if if if dup dup dup dup then then then").unwrap();
+        for _ in 0..50 {
+            let (child, _) = nested.mutate(&config, &mut rng);
+            assert!(child.words.len() <= 5);
+            let ifs = child.words.iter().filter(|w| **w == Word::If).count();
+            let thens = child.words.iter().filter(|w| **w == Word::Then).count();
+            assert_eq!(ifs, thens);
+        }
+    }
+
+    #[test]
+    fn body_size_is_inherited_within_bounds() {
+        let mut rng = rand::thread_rng();
+        let mut size = DEFAULT_BODY_SIZE;
+        for _ in 0..1000 {
+            size = Genome::mutate_body_size(size, &mut rng);
+            assert!((MIN_BODY_SIZE..=MAX_BODY_SIZE).contains(&size));
+        }
+
+        let without_size = r#"{"version":1,"words":["dup"],"constants":[]}"#;
+        let loaded: Genome = serde_json::from_str(without_size).unwrap();
+        assert_eq!(loaded.body_size, DEFAULT_BODY_SIZE);
+    }
+
+    #[test]
+    fn transferred_segments_come_from_the_donor() {
+        let mut rng = rand::thread_rng();
+        let donor = Genome::parse("eat eat eat eat").unwrap();
+        let recipient = Genome::parse("dup drop").unwrap();
+        assert!(donor.sample_segment(0, &mut rng).is_none());
+
+        for _ in 0..20 {
+            let segment = donor.sample_segment(3, &mut rng).unwrap();
+            assert!((1..=3).contains(&segment.len()));
+
+            let (child, mutation) = recipient.with_transferred(&segment, &mut rng);
+            let Mutation::Transferred { index, length } = mutation else {
+                panic!("expected a transfer, got {mutation:?}");
+            };
+            assert_eq!(length, segment.len());
+            assert_eq!(child.words[index..index + length], segment[..]);
+            assert_eq!(child.words.len(), recipient.words.len() + length);
+        }
+    }
 }
@@ -0,0 +1,239 @@
+use crate::animal::{Animal, Lineage};
+use crate::config::*;
+use crate::genome::Genome;
+use crate::species::Species;
+use crate::{RunName, SimulationTick};
+use bevy::prelude::*;
+use rand::seq::IteratorRandom;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// Event requesting that genomes be saved to this run's genome bank, each under a name
+#[derive(Event, Clone)]
+pub struct SaveGenomes {
+    pub genomes: Vec<(String, Genome)>,
+}
+
+/// Error while saving a genome to the bank
+#[derive(Debug)]
+pub enum BankError {
+    Io(std::io::Error),
+    Ron(ron::Error),
+}
+
+impl fmt::Display for BankError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BankError::Io(err) => write!(f, "{}", err),
+            BankError::Ron(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl From<std::io::Error> for BankError {
+    fn from(err: std::io::Error) -> Self {
+        BankError::Io(err)
+    }
+}
+
+impl From<ron::Error> for BankError {
+    fn from(err: ron::Error) -> Self {
+        BankError::Ron(err)
+    }
+}
+
+/// Directory holding the genomes banked during a run; import it to spawn them again
+pub fn bank_dir(run: &RunName) -> PathBuf {
+    run.output_dir().join(GENOME_BANK_DIR)
+}
+
+/// Write a genome with all its heritable traits to `<dir>/<name>.ron`
+pub fn save_genome(dir: &Path, name: &str, genome: &Genome) -> Result<PathBuf, BankError> {
+    std::fs::create_dir_all(dir)?;
+    let path = dir.join(format!("{}.{}", name, GENOME_BANK_EXTENSION));
+    let text = ron::ser::to_string_pretty(genome, ron::ser::PrettyConfig::default())?;
+    std::fs::write(&path, text)?;
+    Ok(path)
+}
+
+/// Resource controlling periodic sampling of living genomes into the genome bank, for
+/// tracing how the gene pool changes over a run without replaying it
+#[derive(Resource)]
+pub struct PopulationSampling {
+    pub enabled: bool,
+    /// Ticks between samples
+    pub interval: u64,
+    /// Genomes per sample; smaller populations are sampled whole
+    pub size: usize,
+}
+
+impl Default for PopulationSampling {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval: POPULATION_SAMPLE_INTERVAL,
+            size: POPULATION_SAMPLE_SIZE,
+        }
+    }
+}
+
+/// A sampled animal: its genome and where it stood when sampled
+#[derive(Clone, Serialize, Deserialize)]
+pub struct SampledAnimal {
+    /// Entity bits; ids may be reused after the animal dies
+    pub entity: u64,
+    pub lineage_id: Option<u64>,
+    pub generation: Option<u32>,
+    pub birth_tick: Option<u64>,
+    pub species: Option<u32>,
+    pub age: f32,
+    pub energy: u32,
+    pub offspring: u32,
+    pub energy_eaten: u64,
+    pub genome: Genome,
+}
+
+/// One population sample as written to `<bank>/samples/tick-<tick>.ron`
+#[derive(Clone, Serialize, Deserialize)]
+pub struct PopulationSample {
+    pub tick: u64,
+    /// Living animals when the sample was taken
+    pub population: usize,
+    pub animals: Vec<SampledAnimal>,
+}
+
+/// Write a population sample into a run's genome bank directory
+pub fn save_sample(dir: &Path, sample: &PopulationSample) -> Result<PathBuf, BankError> {
+    let dir = dir.join(POPULATION_SAMPLE_DIR);
+    std::fs::create_dir_all(&dir)?;
+    let path = dir.join(format!("tick-{:08}.{}", sample.tick, GENOME_BANK_EXTENSION));
+    let text = ron::ser::to_string_pretty(sample, ron::ser::PrettyConfig::default())?;
+    std::fs::write(&path, text)?;
+    Ok(path)
+}
+
+/// System to write a random sample of living genomes to the bank every `interval` ticks
+#[allow(clippy::type_complexity)]
+pub fn sample_population_genomes(
+    run: Res<RunName>,
+    tick: Res<SimulationTick>,
+    sampling: Res<PopulationSampling>,
+    animals: Query<(Entity, &Animal, &Genome, Option<&Lineage>, Option<&Species>)>,
+) {
+    if !sampling.enabled || !tick.0.is_multiple_of(sampling.interval.max(1)) {
+        return;
+    }
+
+    let sample = PopulationSample {
+        tick: tick.0,
+        population: animals.iter().len(),
+        animals: animals
+            .iter()
+            .choose_multiple(&mut rand::thread_rng(), sampling.size)
+            .into_iter()
+            .map(|(entity, animal, genome, lineage, species)| SampledAnimal {
+                entity: entity.to_bits(),
+                lineage_id: lineage.map(|lineage| lineage.lineage_id),
+                generation: lineage.map(|lineage| lineage.generation),
+                birth_tick: lineage.map(|lineage| lineage.birth_tick),
+                species: species.map(|species| species.0),
+                age: animal.age,
+                energy: animal.energy,
+                offspring: animal.offspring,
+                energy_eaten: animal.energy_eaten,
+                genome: genome.clone(),
+            })
+            .collect(),
+    };
+    if sample.animals.is_empty() {
+        return;
+    }
+
+    let dir = bank_dir(&run);
+    if let Err(err) = save_sample(&dir, &sample) {
+        error!(
+            "Failed to save population sample to {}: {err}",
+            dir.display()
+        );
+    }
+}
+
+/// System to write requested genomes to the bank
+pub fn save_genomes(run: Res<RunName>, mut requests: EventReader<SaveGenomes>) {
+    let dir = bank_dir(&run);
+    for request in requests.read() {
+        let saved = request
+            .genomes
+            .iter()
+            .map(|(name, genome)| save_genome(&dir, name, genome))
+            .collect::<Result<Vec<_>, _>>();
+        match saved {
+            Ok(paths) => info!("Saved {} genome(s) to {}", paths.len(), dir.display()),
+            Err(err) => error!("Failed to save genomes to {}: {err}", dir.display()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::import::load_genomes;
+
+    #[test]
+    fn banked_genomes_load_with_their_traits() {
+        let dir = std::env::temp_dir().join(format!("evo-rs-bank-{}", std::process::id()));
+        let mut genome = Genome::seed();
+        genome.body_size = 1.5;
+        genome.diet.herbivory = 0.25;
+
+        let path = save_genome(&dir, "champion", &genome).unwrap();
+        let loaded = load_genomes(&dir);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let loaded = loaded.unwrap();
+        assert_eq!(loaded.len(), 1);
+        assert_eq!(loaded[0].0, path.display().to_string());
+        assert_eq!(loaded[0].1.words, genome.words);
+        assert_eq!(loaded[0].1.body_size, 1.5);
+        assert_eq!(loaded[0].1.diet.herbivory, 0.25);
+    }
+
+    #[test]
+    fn samples_are_kept_out_of_genome_imports() {
+        let dir = std::env::temp_dir().join(format!("evo-rs-samples-{}", std::process::id()));
+        let sample = PopulationSample {
+            tick: 1000,
+            population: 40,
+            animals: vec![SampledAnimal {
+                entity: Entity::from_raw(3).to_bits(),
+                lineage_id: Some(7),
+                generation: Some(12),
+                birth_tick: Some(600),
+                species: None,
+                age: 6.5,
+                energy: 80,
+                offspring: 2,
+                energy_eaten: 140,
+                genome: Genome::seed(),
+            }],
+        };
+
+        save_genome(&dir, "champion", &Genome::seed()).unwrap();
+        let path = save_sample(&dir, &sample).unwrap();
+        let text = std::fs::read_to_string(&path);
+        let imported = load_genomes(&dir);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert!(path.ends_with("samples/tick-00001000.ron"));
+        let loaded: PopulationSample = ron::from_str(&text.unwrap()).unwrap();
+        assert_eq!(loaded.population, 40);
+        assert_eq!(loaded.animals[0].generation, Some(12));
+        assert_eq!(
+            loaded.animals[0].genome.words,
+            sample.animals[0].genome.words
+        );
+        // Importing the bank spawns the banked genome but not the sampled ones
+        assert_eq!(imported.unwrap().len(), 1);
+    }
+}
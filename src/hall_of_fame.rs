@@ -0,0 +1,169 @@
+use crate::SimulationTick;
+use crate::animal::{Animal, Lineage};
+use crate::config::*;
+use crate::genome::Genome;
+use bevy::prelude::*;
+
+/// Snapshot of an outstanding animal, kept after it dies
+#[derive(Clone)]
+pub struct FameEntry {
+    /// Animal the snapshot was taken from; its id may since have been reused
+    pub entity: Entity,
+    pub birth_tick: u64,
+    pub genome: Genome,
+    /// Oldest age the animal was seen at
+    pub age: f32,
+    pub offspring: u32,
+    pub energy_eaten: u64,
+    /// Tick of the latest snapshot
+    pub tick: u64,
+}
+
+/// Resource archiving the genomes of the best animals ever seen, ranked by age
+#[derive(Resource)]
+pub struct HallOfFame {
+    /// Best first
+    pub entries: Vec<FameEntry>,
+    pub capacity: usize,
+    /// An animal qualifies by reaching any one of these
+    pub min_age: f32,
+    pub min_offspring: u32,
+    pub min_energy_eaten: u64,
+    pub timer: Timer,
+}
+
+impl Default for HallOfFame {
+    fn default() -> Self {
+        Self {
+            entries: Vec::new(),
+            capacity: HALL_OF_FAME_SIZE,
+            min_age: HALL_OF_FAME_MIN_AGE,
+            min_offspring: HALL_OF_FAME_MIN_OFFSPRING,
+            min_energy_eaten: HALL_OF_FAME_MIN_ENERGY_EATEN,
+            timer: Timer::from_seconds(HALL_OF_FAME_INTERVAL, TimerMode::Repeating),
+        }
+    }
+}
+
+impl HallOfFame {
+    pub fn qualifies(&self, animal: &Animal) -> bool {
+        animal.age >= self.min_age
+            || animal.offspring >= self.min_offspring
+            || animal.energy_eaten >= self.min_energy_eaten
+    }
+
+    /// Record a qualifying animal, updating its entry if it is already archived, and
+    /// keep only the best `capacity` entries
+    pub fn consider(
+        &mut self,
+        entity: Entity,
+        birth_tick: u64,
+        animal: &Animal,
+        genome: &Genome,
+        tick: u64,
+    ) {
+        if !self.qualifies(animal) {
+            return;
+        }
+
+        match self
+            .entries
+            .iter_mut()
+            .find(|entry| entry.entity == entity && entry.birth_tick == birth_tick)
+        {
+            Some(entry) => {
+                entry.age = entry.age.max(animal.age);
+                entry.offspring = animal.offspring;
+                entry.energy_eaten = animal.energy_eaten;
+                entry.tick = tick;
+            }
+            None => {
+                // Full and not better than the worst entry
+                if self.entries.len() >= self.capacity
+                    && self
+                        .entries
+                        .last()
+                        .is_some_and(|worst| worst.age >= animal.age)
+                {
+                    return;
+                }
+                self.entries.push(FameEntry {
+                    entity,
+                    birth_tick,
+                    genome: genome.clone(),
+                    age: animal.age,
+                    offspring: animal.offspring,
+                    energy_eaten: animal.energy_eaten,
+                    tick,
+                });
+            }
+        }
+
+        self.entries.sort_by(|a, b| b.age.total_cmp(&a.age));
+        self.entries.truncate(self.capacity);
+    }
+}
+
+/// System to periodically snapshot living animals into the hall of fame
+pub fn update_hall_of_fame(
+    time: Res<Time>,
+    tick: Res<SimulationTick>,
+    mut fame: ResMut<HallOfFame>,
+    animals: Query<(Entity, &Animal, &Genome, Option<&Lineage>)>,
+) {
+    if !fame.timer.tick(time.delta()).just_finished() {
+        return;
+    }
+
+    for (entity, animal, genome, lineage) in animals.iter() {
+        let birth_tick = lineage.map_or(0, |lineage| lineage.birth_tick);
+        fame.consider(entity, birth_tick, animal, genome, tick.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn aged(age: f32) -> Animal {
+        let mut animal = Animal::new(10);
+        animal.age = age;
+        animal
+    }
+
+    #[test]
+    fn keeps_the_oldest_qualifying_animals() {
+        let mut fame = HallOfFame {
+            capacity: 2,
+            min_age: 10.0,
+            min_offspring: 3,
+            min_energy_eaten: 1000,
+            ..Default::default()
+        };
+        let genome = Genome::seed();
+        let entity = |index| Entity::from_raw(index);
+
+        fame.consider(entity(1), 0, &aged(5.0), &genome, 1);
+        assert!(fame.entries.is_empty());
+        let mut parent = aged(5.0);
+        parent.offspring = 3;
+        assert!(fame.qualifies(&parent));
+
+        fame.consider(entity(1), 0, &aged(12.0), &genome, 1);
+        fame.consider(entity(2), 0, &aged(20.0), &genome, 1);
+        fame.consider(entity(3), 0, &aged(11.0), &genome, 1);
+        let ages: Vec<f32> = fame.entries.iter().map(|entry| entry.age).collect();
+        assert_eq!(ages, vec![20.0, 12.0]);
+
+        // The same animal seen again is updated rather than archived twice
+        fame.consider(entity(1), 0, &aged(30.0), &genome, 2);
+        assert_eq!(fame.entries.len(), 2);
+        assert_eq!(fame.entries[0].entity, entity(1));
+        assert_eq!(fame.entries[0].tick, 2);
+
+        // A reused entity id is a different animal
+        fame.consider(entity(1), 5, &aged(25.0), &genome, 3);
+        let ages: Vec<f32> = fame.entries.iter().map(|entry| entry.age).collect();
+        assert_eq!(ages, vec![30.0, 25.0]);
+    }
+}
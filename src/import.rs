@@ -0,0 +1,205 @@
+use crate::camera::MainCamera;
+use crate::config::*;
+use crate::genome::{Genome, ParseError};
+use crate::spawning::SpawnQueue;
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use std::fmt;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+/// Event requesting that genomes be loaded from disk and spawned in a grid
+#[derive(Event, Debug, Clone)]
+pub struct ImportGenomes {
+    /// A genome file, a directory of genome files or a zip archive of them
+    pub path: PathBuf,
+    /// Number of animals to spawn per genome
+    pub copies: usize,
+    /// World position the grid is centered on
+    pub center: Vec2,
+}
+
+/// Error while loading genome files
+#[derive(Debug)]
+pub enum ImportError {
+    Io(std::io::Error),
+    Zip(zip::result::ZipError),
+    Parse {
+        file: String,
+        error: ParseError,
+    },
+    Ron {
+        file: String,
+        error: ron::error::SpannedError,
+    },
+    NoGenomes,
+}
+
+impl fmt::Display for ImportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ImportError::Io(err) => write!(f, "{}", err),
+            ImportError::Zip(err) => write!(f, "{}", err),
+            ImportError::Parse { file, error } => write!(f, "{}: {}", file, error),
+            ImportError::Ron { file, error } => write!(f, "{}: {}", file, error),
+            ImportError::NoGenomes => write!(
+                f,
+                "no .{} or .{} files found",
+                GENOME_FILE_EXTENSION, GENOME_BANK_EXTENSION
+            ),
+        }
+    }
+}
+
+impl From<std::io::Error> for ImportError {
+    fn from(err: std::io::Error) -> Self {
+        ImportError::Io(err)
+    }
+}
+
+impl From<zip::result::ZipError> for ImportError {
+    fn from(err: zip::result::ZipError) -> Self {
+        ImportError::Zip(err)
+    }
+}
+
+fn is_genome_file(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext == GENOME_FILE_EXTENSION || ext == GENOME_BANK_EXTENSION)
+}
+
+/// Read a genome from banked RON, or from genome source for any other file
+fn parse_genome(file: String, text: &str) -> Result<(String, Genome), ImportError> {
+    if Path::new(&file)
+        .extension()
+        .is_some_and(|ext| ext == GENOME_BANK_EXTENSION)
+    {
+        match ron::from_str(text) {
+            Ok(genome) => Ok((file, genome)),
+            Err(error) => Err(ImportError::Ron { file, error }),
+        }
+    } else {
+        match Genome::parse(text) {
+            Ok(genome) => Ok((file, genome)),
+            Err(error) => Err(ImportError::Parse { file, error }),
+        }
+    }
+}
+
+/// Load genomes from a single file, every genome file in a directory, or every genome
+/// file in a zip archive. Genome files are either source (`.gen`) or banked genomes
+/// (`.ron`). Results are named by file and sorted by name.
+pub fn load_genomes(path: &Path) -> Result<Vec<(String, Genome)>, ImportError> {
+    let mut sources = Vec::new();
+
+    if path.is_dir() {
+        for entry in std::fs::read_dir(path)? {
+            let file = entry?.path();
+            if is_genome_file(&file) {
+                let text = std::fs::read_to_string(&file)?;
+                sources.push((file.display().to_string(), text));
+            }
+        }
+    } else if path.extension().is_some_and(|ext| ext == "zip") {
+        let mut archive = zip::ZipArchive::new(std::fs::File::open(path)?)?;
+        for index in 0..archive.len() {
+            let mut file = archive.by_index(index)?;
+            if file.is_file() && is_genome_file(Path::new(file.name())) {
+                let mut text = String::new();
+                file.read_to_string(&mut text)?;
+                sources.push((file.name().to_string(), text));
+            }
+        }
+    } else {
+        sources.push((path.display().to_string(), std::fs::read_to_string(path)?));
+    }
+
+    if sources.is_empty() {
+        return Err(ImportError::NoGenomes);
+    }
+    sources.sort_by(|a, b| a.0.cmp(&b.0));
+
+    sources
+        .into_iter()
+        .map(|(file, text)| parse_genome(file, &text))
+        .collect()
+}
+
+/// Positions of a square-ish grid holding `count` animals, centered on the origin
+pub fn grid_positions(count: usize) -> Vec<Vec2> {
+    let columns = ((count as f32).sqrt().ceil() as usize).max(1);
+    let rows = count.div_ceil(columns).max(1);
+    let center = Vec2::new((columns - 1) as f32, (rows - 1) as f32) * IMPORT_GRID_SPACING / 2.0;
+
+    (0..count)
+        .map(|i| {
+            Vec2::new((i % columns) as f32, (i / columns) as f32) * IMPORT_GRID_SPACING - center
+        })
+        .collect()
+}
+
+/// System to load requested genome files and spawn one grid of animals per request
+pub fn import_genomes(mut queue: ResMut<SpawnQueue>, mut imports: EventReader<ImportGenomes>) {
+    for import in imports.read() {
+        let genomes = match load_genomes(&import.path) {
+            Ok(genomes) => genomes,
+            Err(err) => {
+                error!(
+                    "Failed to import genomes from {}: {err}",
+                    import.path.display()
+                );
+                continue;
+            }
+        };
+
+        // Copies of the same genome end up next to each other in the grid
+        let positions = grid_positions(genomes.len() * import.copies)
+            .into_iter()
+            .map(|position| position + import.center);
+        let animals = genomes
+            .iter()
+            .flat_map(|(_, genome)| std::iter::repeat_n(genome, import.copies));
+        for (genome, position) in animals.zip(positions) {
+            queue.push(genome.clone(), STARTING_ANIMAL_ENERGY, position, 0.0);
+        }
+
+        info!(
+            "Imported {} genome(s) x{} from {} at ({:.0}, {:.0})",
+            genomes.len(),
+            import.copies,
+            import.path.display(),
+            import.center.x,
+            import.center.y
+        );
+    }
+}
+
+/// System to import genome files dropped onto the window, one animal per genome centered
+/// on the cursor. Falls back to the middle of the view when the platform does not report
+/// the cursor during a drag
+pub fn import_dropped_files(
+    mut drops: EventReader<FileDragAndDrop>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    camera: Query<(&Camera, &GlobalTransform), With<MainCamera>>,
+    mut imports: EventWriter<ImportGenomes>,
+) {
+    for drop in drops.read() {
+        let FileDragAndDrop::DroppedFile { path_buf, .. } = drop else {
+            continue;
+        };
+        let Ok((camera, camera_transform)) = camera.get_single() else {
+            continue;
+        };
+        let center = windows
+            .get_single()
+            .ok()
+            .and_then(|window| window.cursor_position())
+            .and_then(|cursor| camera.viewport_to_world_2d(camera_transform, cursor).ok())
+            .unwrap_or_else(|| camera_transform.translation().truncate());
+        imports.send(ImportGenomes {
+            path: path_buf.clone(),
+            copies: 1,
+            center,
+        });
+    }
+}
@@ -0,0 +1,246 @@
+use crate::animal::{Animal, Lineage, Phenotype};
+use crate::carry::{Carried, Carrying};
+use crate::config::*;
+use crate::gene_transfer::TransferredGenes;
+use crate::genome::{Genome, GenomeExecutor, Quadrants, ScentChannel, Sensors, StackMode};
+use crate::plant::{GutSeeds, Plant};
+use crate::species::Species;
+use crate::trace_export::TraceExport;
+use crate::watchpoint::Watchpoint;
+use bevy::ecs::query::QueryData;
+use bevy::prelude::*;
+use bevy_egui::egui;
+
+/// An entity kind the Selected Entity panel can show. New kinds implement this for
+/// their query item and get the shared heading and position for free
+pub trait Inspectable {
+    /// Heading above the details
+    fn kind(&self) -> &'static str;
+
+    fn transform(&self) -> &Transform;
+
+    /// Whether the entity can be pinned to the watchlist
+    fn pinnable(&self) -> bool {
+        false
+    }
+
+    /// Fields specific to this kind of entity
+    fn show_details(&self, ui: &mut egui::Ui);
+}
+
+/// Everything the inspector shows about a plant
+#[derive(QueryData)]
+pub struct PlantInspection {
+    pub plant: &'static Plant,
+    pub transform: &'static Transform,
+    pub carried: Option<&'static Carried>,
+}
+
+impl Inspectable for PlantInspectionItem<'_> {
+    fn kind(&self) -> &'static str {
+        "Plant"
+    }
+
+    fn transform(&self) -> &Transform {
+        self.transform
+    }
+
+    fn show_details(&self, ui: &mut egui::Ui) {
+        ui.label(format!(
+            "Energy: {} / {}",
+            self.plant.energy,
+            Plant::MAX_ENERGY
+        ));
+
+        // Progress bar for energy
+        let energy_ratio = self.plant.energy as f32 / Plant::MAX_ENERGY as f32;
+        let progress_bar = egui::ProgressBar::new(energy_ratio)
+            .text(format!("{}%", (energy_ratio * 100.0) as u32));
+        ui.add(progress_bar);
+
+        if let Some(carried) = self.carried {
+            ui.label(format!("Carried by {:?}", carried.by));
+        }
+    }
+}
+
+/// Everything the inspector and genome viewer use about an animal
+#[derive(QueryData)]
+#[query_data(mutable)]
+pub struct AnimalInspection {
+    pub animal: &'static Animal,
+    pub genome: &'static Genome,
+    pub executor: &'static mut GenomeExecutor,
+    pub sensors: &'static Sensors,
+    pub transform: &'static Transform,
+    pub phenotype: Option<&'static Phenotype>,
+    pub lineage: Option<&'static Lineage>,
+    pub species: Option<&'static Species>,
+    pub carrying: Option<&'static Carrying>,
+    pub gut_seeds: Option<&'static GutSeeds>,
+    pub transferred: Option<&'static TransferredGenes>,
+    pub export: Option<&'static TraceExport>,
+    pub watchpoint: Option<&'static Watchpoint>,
+}
+
+impl Inspectable for AnimalInspectionReadOnlyItem<'_> {
+    fn kind(&self) -> &'static str {
+        "Animal"
+    }
+
+    fn transform(&self) -> &Transform {
+        self.transform
+    }
+
+    fn pinnable(&self) -> bool {
+        true
+    }
+
+    fn show_details(&self, ui: &mut egui::Ui) {
+        let (animal, genome, executor, sensors) =
+            (self.animal, self.genome, self.executor, self.sensors);
+
+        ui.label(format!("Energy: {}", animal.energy));
+        ui.label(format!("Age: {:.1}s / {:.0}s", animal.age, MAX_LIFESPAN));
+        ui.label(format!(
+            "Next split: {:.0}% to offspring",
+            animal.split_ratio * 100.0
+        ));
+        ui.label(format!(
+            "Offspring: {}, lifetime energy eaten: {}",
+            animal.offspring, animal.energy_eaten
+        ));
+        if let Some(lineage) = self.lineage {
+            ui.label(format!(
+                "Lineage #{}, generation {}, born at tick {}",
+                lineage.lineage_id, lineage.generation, lineage.birth_tick
+            ));
+            if let Some(parent) = lineage.parent {
+                ui.label(format!("Parent: {:?}", parent));
+            }
+        }
+        if let Some(species) = self.species {
+            ui.label(format!("Species #{}", species.0));
+        }
+        if let Some(phenotype) = self.phenotype {
+            ui.label(format!(
+                "Body: radius {:.1}, {:.2}x speed, {:.2}x metabolism",
+                phenotype.radius(),
+                phenotype.speed(),
+                phenotype.metabolism()
+            ));
+        }
+        if let Some(carrying) = self.carrying {
+            ui.label(format!("Carrying plant {:?}", carrying.0));
+        }
+        if let Some(GutSeeds(seeds)) = self.gut_seeds
+            && !seeds.is_empty()
+        {
+            ui.label(format!("Seeds in gut: {}", seeds.len()));
+        }
+        if let Some(genes) = self.transferred {
+            ui.label(format!(
+                "Received {} words from {:?} for its offspring",
+                genes.segment.len(),
+                genes.donor
+            ));
+        }
+
+        let rotation_degrees = self
+            .transform
+            .rotation
+            .to_euler(EulerRot::ZXY)
+            .0
+            .to_degrees();
+        ui.label(format!(
+            "Facing: {:.1}° (heading {:.2})",
+            rotation_degrees, sensors.heading
+        ));
+        ui.label(format!(
+            "Moving: {:.2} per tick, turning {:+.3} of a turn",
+            sensors.speed, sensors.turn_rate
+        ));
+
+        ui.separator();
+        ui.label("Sensors:");
+        let distance =
+            |sensor: Option<f32>| sensor.map_or("None".to_string(), |d| format!("{:.1}", d));
+        let quadrants = |sensor: &Quadrants| {
+            format!(
+                "F {} B {} L {} R {}",
+                distance(sensor.front),
+                distance(sensor.back),
+                distance(sensor.left),
+                distance(sensor.right)
+            )
+        };
+        for channel in ScentChannel::ALL {
+            ui.label(format!(
+                "  Smell {}: {}",
+                channel,
+                quadrants(sensors.scent(channel))
+            ));
+        }
+        ui.label(format!(
+            "  Nearest animal: {}",
+            quadrants(&sensors.nearest_animal)
+        ));
+        ui.label(format!("  Hearing: {}", quadrants(&sensors.hearing)));
+        ui.label(format!(
+            "  Touching: plant={} animal={}, {} overlapping",
+            sensors.touching_plant, sensors.touching_animal, sensors.contacts
+        ));
+        match sensors.sight {
+            Some((distance, kind)) => ui.label(format!("  Sees: {} at {:.1}", kind, distance)),
+            None => ui.label("  Sees: nothing"),
+        };
+        ui.label(format!(
+            "  Crowding: {} within {:.0}",
+            sensors.crowding, CROWDING_RADIUS
+        ));
+        if let Some(energy) = sensors.nearest_plant_energy {
+            ui.label(format!("  Nearest plant energy: {}", energy));
+        }
+        ui.label(format!("  Pheromone: {:.2}", sensors.pheromone));
+        if sensors.starving {
+            ui.colored_label(egui::Color32::from_rgb(255, 80, 80), "  Starving!");
+        }
+
+        ui.separator();
+        ui.label("Genome:");
+        ui.label(format!("  Words: {}", genome.words.len()));
+        ui.label(format!(
+            "  Mutation rates: {:.2}% replace, {:.2}% duplicate, {:.2}% delete",
+            genome.rates.substitution, genome.rates.duplication, genome.rates.deletion
+        ));
+        ui.label(format!("  Body size: {:.2}", genome.body_size));
+        ui.label(format!(
+            "  Diet: {:.0}% herbivore ({:.2}x plants, {:.2}x meat)",
+            genome.diet.herbivory * 100.0,
+            genome.diet.plant_efficiency(),
+            genome.diet.meat_efficiency()
+        ));
+        ui.label(format!("  Current IP: {}", executor.instruction_pointer));
+        ui.label(format!(
+            "  Stack Size: {} / {}",
+            executor.stack.len(),
+            executor.stack_capacity
+        ));
+        if executor.stack_mode == StackMode::Typed {
+            ui.label(format!(
+                "  Bool Stack Size: {} / {}",
+                executor.bool_stack.len(),
+                executor.stack_capacity
+            ));
+        }
+        ui.label(format!("  Stack Overflows: {}", executor.stack_overflows));
+        ui.label(format!("  Words Skipped: {}", executor.words_skipped));
+        if executor.safe_math {
+            ui.label(format!("  Saturated Results: {}", executor.saturations));
+        }
+        ui.label(format!(
+            "  Executed: {} / {}",
+            executor.instructions_executed_this_frame, executor.max_instructions_per_frame
+        ));
+    }
+}
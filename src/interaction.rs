@@ -0,0 +1,158 @@
+use crate::animal::{Animal, AttackIntent};
+use crate::config::*;
+use crate::energy_flow::{EnergyFlows, Flow};
+use crate::genome::{Diet, Genome};
+use bevy::prelude::*;
+use std::collections::HashMap;
+
+/// Caps on energy moved between animals, applied per tick across all interactions so
+/// that repeating an interaction word cannot drain or pump an animal in a single frame
+#[derive(Resource)]
+pub struct InteractionLimits {
+    /// Most energy any one animal can lose to others per tick
+    pub max_loss_per_tick: u32,
+    /// Most energy any one animal can gain from others per tick
+    pub max_gain_per_tick: u32,
+}
+
+impl Default for InteractionLimits {
+    fn default() -> Self {
+        Self {
+            max_loss_per_tick: INTERACTION_MAX_LOSS_PER_TICK,
+            max_gain_per_tick: INTERACTION_MAX_GAIN_PER_TICK,
+        }
+    }
+}
+
+/// Energy already moved this tick, per animal
+#[derive(Default)]
+struct TickLedger {
+    lost: HashMap<Entity, u32>,
+    gained: HashMap<Entity, u32>,
+}
+
+impl TickLedger {
+    /// Take up to `amount` from `from`'s remaining loss allowance, returning what was allowed
+    fn allow_loss(&mut self, limits: &InteractionLimits, from: Entity, amount: u32) -> u32 {
+        let lost = self.lost.entry(from).or_default();
+        let allowed = amount.min(limits.max_loss_per_tick.saturating_sub(*lost));
+        *lost += allowed;
+        allowed
+    }
+
+    /// Take up to `amount` from `to`'s remaining gain allowance, returning what was allowed
+    fn allow_gain(&mut self, limits: &InteractionLimits, to: Entity, amount: u32) -> u32 {
+        let gained = self.gained.entry(to).or_default();
+        let allowed = amount.min(limits.max_gain_per_tick.saturating_sub(*gained));
+        *gained += allowed;
+        allowed
+    }
+}
+
+/// System to resolve energy-moving interactions between animals.
+///
+/// Each attacker drains the nearest other animal in strike range and absorbs the drained
+/// energy scaled by its meat efficiency, within the per-tick caps of `InteractionLimits`.
+pub fn resolve_interactions(
+    limits: Res<InteractionLimits>,
+    mut flows: ResMut<EnergyFlows>,
+    mut intents: EventReader<AttackIntent>,
+    mut animals: Query<(Entity, &mut Animal, &Transform, &Genome)>,
+) {
+    if intents.is_empty() {
+        return;
+    }
+
+    let positions: Vec<(Entity, Vec2)> = animals
+        .iter()
+        .map(|(entity, _, transform, _)| (entity, transform.translation.truncate()))
+        .collect();
+    let mut ledger = TickLedger::default();
+
+    for intent in intents.read() {
+        let Some(&(_, attacker_pos)) = positions.iter().find(|(e, _)| *e == intent.attacker) else {
+            continue;
+        };
+
+        let target = positions
+            .iter()
+            .filter(|(e, _)| *e != intent.attacker)
+            .map(|(e, pos)| (*e, attacker_pos.distance(*pos)))
+            .filter(|(_, distance)| *distance <= ATTACK_DISTANCE)
+            .min_by(|a, b| a.1.total_cmp(&b.1));
+        let Some((target, _)) = target else {
+            continue;
+        };
+
+        let drained = {
+            let Ok((_, mut victim, _, _)) = animals.get_mut(target) else {
+                continue;
+            };
+            let drained = ledger.allow_loss(&limits, target, victim.energy.min(ATTACK_DAMAGE));
+            victim.consume_energy(drained);
+            if victim.energy == 0 {
+                victim.killed = true;
+            }
+            drained
+        };
+
+        if let Ok((_, mut attacker, _, genome)) = animals.get_mut(intent.attacker) {
+            let absorbed = Diet::digest(drained, genome.diet.meat_efficiency());
+            let gained = ledger.allow_gain(&limits, intent.attacker, absorbed);
+            attacker.eat(gained);
+            flows.record(Flow::Predation, gained);
+            flows.record_digestion(drained, gained);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::ecs::system::RunSystemOnce;
+
+    #[test]
+    fn many_attackers_share_the_per_tick_caps() {
+        let mut world = World::new();
+        world.init_resource::<InteractionLimits>();
+        world.init_resource::<EnergyFlows>();
+        world.init_resource::<Events<AttackIntent>>();
+
+        let carnivore = Genome {
+            diet: Diet { herbivory: 0.0 },
+            ..Genome::seed()
+        };
+        let target = world
+            .spawn((Animal::new(100), Transform::default(), carnivore.clone()))
+            .id();
+        // Each attacker is closer to the target than to any other attacker
+        let attackers: Vec<Entity> = [(15.0, 0.0), (-15.0, 0.0), (0.0, 15.0), (0.0, -15.0)]
+            .into_iter()
+            .map(|(x, y)| {
+                world
+                    .spawn((
+                        Animal::new(100),
+                        Transform::from_xyz(x, y, 0.0),
+                        carnivore.clone(),
+                    ))
+                    .id()
+            })
+            .collect();
+        for _ in 0..3 {
+            for &attacker in &attackers {
+                world.send_event(AttackIntent { attacker });
+            }
+        }
+
+        world.run_system_once(resolve_interactions).unwrap();
+        let lost = 100 - world.get::<Animal>(target).unwrap().energy;
+        assert_eq!(lost, INTERACTION_MAX_LOSS_PER_TICK);
+        let mut total_gained = 0;
+        for attacker in attackers {
+            let gained = world.get::<Animal>(attacker).unwrap().energy - 100;
+            assert!(gained <= INTERACTION_MAX_GAIN_PER_TICK, "gained {gained}");
+            total_gained += gained;
+        }
+        assert!(total_gained > 0);
+    }
+}
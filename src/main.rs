@@ -1,57 +1,367 @@
+mod aggregate;
 mod animal;
+mod benchmark;
 mod camera;
+mod camera_tour;
+mod carry;
+mod charts;
+mod cli;
+mod component_inspector;
 mod config;
+mod death_marker;
+mod dock;
+mod energy_flow;
+mod fast_forward;
+mod fitness;
+mod gene_transfer;
 mod genome;
+mod genome_bank;
+mod hall_of_fame;
+mod import;
+mod inspector;
+mod interaction;
+mod meteor;
+mod milestones;
 mod outline;
+mod pheromone;
 mod plant;
+mod scenario;
 mod selection;
+mod sim_rng;
+mod spawning;
+mod species;
+mod trace_export;
+mod trails;
+mod ui_refresh;
+mod viewport;
+mod vm;
+#[cfg(test)]
+mod vm_fuzz;
+mod watchlist;
+mod watchpoint;
 
 use animal::{
-    Animal, MetabolismTimer, animal_metabolism, execute_genomes, population_failsafe,
-    remove_dead_animals, spawn_seed_animals, spawn_test_animals, split_animals, update_sensors,
+    Animal, AnimalDied, AnimalIntent, AnimalSplit, AttackIntent, BodyHue, Calls, CorpseScent,
+    FailsafeSettings, GenomeLengthCost, Handedness, LifecycleQueue, Lineage, MetabolismTimer,
+    OffspringDispersal, Phenotype, PlantDepleted, PlantEaten, ReproductionMode,
+    ReproductionSettings, RespawnSource, ScentModel, SensorNoise, SmellRange, SpawnDistribution,
+    SpawnSettings, StarvationWarning, animal_metabolism, apply_color_changes, apply_intents,
+    apply_lifecycle_queue, detect_contacts, execute_genomes, fade_corpse_scents,
+    leave_corpse_scents, population_failsafe, queue_seed_animals, remove_dead_animals,
+    spawn_test_animals, split_animals, update_sensors,
 };
+use benchmark::{BenchmarkRun, finish_benchmark, start_benchmark};
+use bevy::ecs::schedule::ExecutorKind;
+use bevy::ecs::system::SystemParam;
 use bevy::prelude::*;
 use bevy_egui::{EguiContexts, EguiPlugin, egui};
 use camera::{
-    CameraState, MainCamera, camera_pan, camera_touch_controls, camera_zoom, setup_camera,
+    CameraState, camera_keyboard, camera_pan, camera_touch_controls, camera_zoom, setup_camera,
 };
+use camera_tour::{CameraTour, camera_tour_ui, play_camera_tour};
+use carry::{Carried, Carrying, move_carried_plants};
+use charts::{ChartSampleTimer, PopulationHistory, charts_ui, sample_population};
+use cli::{CliArgs, apply_cli_args};
+use component_inspector::component_inspector_ui;
 use config::*;
-use genome::{Genome, GenomeExecutor, Sensors, WordCategory};
-use outline::{manage_selection_outlines, update_outline_positions};
+use death_marker::{DeathMarkers, fade_death_markers, spawn_death_markers};
+use dock::{DockLayout, DockTab, dock_tabs_ui};
+use energy_flow::{EnergyFlows, energy_flow_ui, roll_energy_flows};
+use fast_forward::{FastForward, SimulationStep, run_simulation};
+use fitness::{
+    ExplorationArea, FitnessAppExt, FitnessSampleTimer, KillCount, MeanEnergy,
+    update_fitness_metrics,
+};
+use gene_transfer::{GeneTransfer, transfer_genes};
+use genome::{
+    BloatControl, Genome, GenomeDiff, GenomeExecutor, Mutation, MutationConfig, Sensors, StackMode,
+    StackOverflowPolicy, VmSettings, Word, WordCategory,
+};
+use genome_bank::{
+    PopulationSampling, SaveGenomes, bank_dir, sample_population_genomes, save_genomes,
+};
+use hall_of_fame::{HallOfFame, update_hall_of_fame};
+use import::{ImportGenomes, import_dropped_files, import_genomes};
+use inspector::{AnimalInspection, AnimalInspectionItem, Inspectable, PlantInspection};
+use interaction::{InteractionLimits, resolve_interactions};
+use meteor::{
+    MeteorSettings, MeteorStrike, ScorchedZone, apply_meteor_strikes, random_meteors,
+    recover_scorched_zones,
+};
+use milestones::{Milestone, MilestoneTracker, detect_milestones, record_milestones};
+use outline::{
+    InteractionRings, manage_interaction_rings, manage_selection_outlines,
+    manage_starvation_outlines, update_outline_positions, update_starvation_outlines,
+};
+use pheromone::{PheromoneGrid, update_pheromones};
 use plant::{
-    Plant, PlantConfig, PlantGrowthTimer, PlantSpawnTimer, grow_plants, spawn_plants,
-    update_plant_visuals,
+    GutSeeds, Plant, PlantConfig, PlantGovernor, PlantGrowthTimer, PlantPlacement, PlantRegrowth,
+    PlantScent, PlantShading, PlantSpawnTimer, RegrowMarker, SeedDispersal, disperse_seeds,
+    govern_plant_spawn_rate, grow_plants, ingest_seeds, leave_regrow_markers, regrow_plants,
+    spawn_plants, update_plant_visuals,
 };
-use selection::{Selected, SelectedEntity, handle_selection, update_selection_visuals};
+use scenario::apply_scenario;
+use selection::{
+    Selected, SelectedEntity, SplitFollow, follow_selected_splits, handle_selection,
+    update_selection_visuals,
+};
+use sim_rng::SimRng;
+use spawning::{SpawnBudget, SpawnQueue, drain_spawn_queue};
+use species::{Species, SpeciesRegistry, cluster_species};
+use std::path::{Path, PathBuf};
+use trace_export::{TraceExport, write_trace_exports};
+use trails::{TrailMap, draw_trails, record_trails, setup_trail_overlay};
+use ui_refresh::{UiRefresh, tick_ui_refresh};
+use viewport::{CoordinateGrid, coordinate_grid_ui, viewport_stats_ui};
+use watchlist::{
+    Watchlist, WatchlistSampleTimer, remove_dead_watched, sample_watchlist, watchlist_ui,
+};
+use watchpoint::{WatchCondition, Watchpoint, check_watchpoints};
 
 /// Resource to control simulation state
-#[derive(Resource, PartialEq, Eq, Clone, Copy)]
+#[derive(Resource, PartialEq, Eq, Clone, Copy, Default)]
 pub enum SimulationState {
+    #[default]
     Running,
     Paused,
 }
 
-impl Default for SimulationState {
-    fn default() -> Self {
-        SimulationState::Running
+/// Number of simulation ticks that have run (paused frames are not counted, and a
+/// fast-forward runs many ticks per frame)
+#[derive(Resource, Default)]
+pub struct SimulationTick(pub u64);
+
+/// Name identifying this run in exported files and naming its output directory
+#[derive(Resource)]
+pub struct RunName(pub String);
+
+impl RunName {
+    /// Name for a run starting now, prefixed with the scenario it runs if any
+    pub fn new(scenario: Option<&str>) -> Self {
+        let started = bevy::utils::SystemTime::now()
+            .duration_since(bevy::utils::SystemTime::UNIX_EPOCH)
+            .unwrap_or_default();
+        let prefix: String = scenario
+            .unwrap_or_default()
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() {
+                    c.to_ascii_lowercase()
+                } else {
+                    '-'
+                }
+            })
+            .collect();
+        let prefix = prefix.trim_matches('-');
+        if prefix.is_empty() {
+            Self(format!("run-{}", started.as_secs()))
+        } else {
+            Self(format!("{}-{}", prefix, started.as_secs()))
+        }
+    }
+
+    /// Directory every artifact of this run is written under
+    pub fn output_dir(&self) -> PathBuf {
+        run_output_dir(&self.0)
     }
 }
 
+/// Output directory of the run with the given name
+pub fn run_output_dir(run: &str) -> PathBuf {
+    PathBuf::from(OUTPUT_DIR).join(RUN_OUTPUT_DIR).join(run)
+}
+
+/// Show a directory in the platform's file manager, creating it first if nothing has
+/// been written there yet
+fn open_directory(dir: &Path) -> std::io::Result<()> {
+    std::fs::create_dir_all(dir)?;
+    let opener = if cfg!(target_os = "windows") {
+        "explorer"
+    } else if cfg!(target_os = "macos") {
+        "open"
+    } else {
+        "xdg-open"
+    };
+    std::process::Command::new(opener).arg(dir).spawn()?;
+    Ok(())
+}
+
+/// System to advance the simulation tick counter
+fn advance_simulation_tick(mut tick: ResMut<SimulationTick>) {
+    tick.0 += 1;
+}
+
 fn main() {
-    App::new()
-        .add_plugins(DefaultPlugins.set(WindowPlugin {
-            primary_window: Some(Window {
-                title: "Evolution Ecology Simulator".to_string(),
-                resolution: (1280.0, 720.0).into(),
-                ..default()
-            }),
+    // `aggregate` summarizes finished runs instead of starting the simulation
+    let mut args = std::env::args().skip(1);
+    if args.next().as_deref() == Some("aggregate") {
+        if !aggregate::run(args) {
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    let cli = CliArgs::from_env();
+    let mut app = App::new();
+    app.add_plugins(DefaultPlugins.set(WindowPlugin {
+        primary_window: Some(Window {
+            title: "Evolution Ecology Simulator".to_string(),
+            resolution: (1280.0, 720.0).into(),
             ..default()
-        }))
-        .add_plugins(EguiPlugin)
-        .init_resource::<CameraState>()
+        }),
+        ..default()
+    }))
+    .add_plugins(EguiPlugin)
+    .init_resource::<CameraState>()
+    .init_resource::<UiRefresh>()
+    .init_resource::<CoordinateGrid>()
+    .init_resource::<CameraTour>()
+    .insert_resource(RunName::new(
+        cli.scenario.as_ref().map(|scenario| scenario.name.as_str()),
+    ))
+    .insert_resource(cli)
+    .insert_resource(DockLayout::load())
+    .add_systems(
+        Startup,
+        (
+            setup_camera,
+            setup_trail_overlay,
+            // An imported population or a scenario replaces the default seed animals
+            spawn_test_animals
+                .run_if(|cli: Res<CliArgs>| cli.import.is_none() && cli.scenario.is_none())
+                .after(apply_cli_args)
+                .after(start_benchmark),
+            apply_cli_args,
+            apply_scenario.after(apply_cli_args),
+            start_benchmark.after(apply_cli_args),
+        ),
+    )
+    .add_systems(
+        Update,
+        (
+            // Always run (even when paused)
+            (
+                camera_zoom,
+                camera_pan,
+                camera_touch_controls,
+                camera_keyboard,
+                play_camera_tour,
+            ),
+            handle_selection,
+            update_selection_visuals,
+            // World overlays
+            (
+                manage_selection_outlines,
+                update_outline_positions,
+                manage_starvation_outlines,
+                update_starvation_outlines,
+                manage_interaction_rings,
+                draw_trails,
+            ),
+            tick_ui_refresh
+                .before(dock_tabs_ui)
+                .before(viewport_stats_ui),
+            // Drawn first so the grid sits under the panels
+            coordinate_grid_ui.before(dock_tabs_ui),
+            dock_tabs_ui,
+            ui_system.after(dock_tabs_ui),
+            (
+                watchlist_ui,
+                charts_ui,
+                energy_flow_ui,
+                camera_tour_ui,
+                component_inspector_ui,
+            )
+                .after(ui_system),
+            remove_dead_watched,
+            import_dropped_files.before(import_genomes),
+            import_genomes,
+            save_genomes,
+            viewport_stats_ui,
+            // Runs SimulationStep when the simulation is running or fast-forwarding
+            run_simulation,
+            finish_benchmark
+                .after(run_simulation)
+                .run_if(resource_exists::<BenchmarkRun>),
+        ),
+    );
+    add_simulation(&mut app);
+
+    app.run();
+}
+
+/// Register the simulation's resources, events and systems. Everything here runs
+/// headless; the window, camera and panels are added by `main`
+fn add_simulation(app: &mut App) -> &mut App {
+    // One thread runs the step so systems draw from `SimRng` in the same order every tick
+    app.edit_schedule(SimulationStep, |schedule| {
+        schedule.set_executor_kind(ExecutorKind::SingleThreaded);
+    });
+    app.init_resource::<SimRng>()
         .init_resource::<PlantConfig>()
+        .init_resource::<PlantGovernor>()
+        .init_resource::<SeedDispersal>()
+        .init_resource::<PlantRegrowth>()
+        .init_resource::<PlantShading>()
+        .init_resource::<PheromoneGrid>()
+        .init_resource::<TrailMap>()
+        .init_resource::<InteractionRings>()
         .init_resource::<SelectedEntity>()
         .init_resource::<SimulationState>()
+        .init_resource::<SimulationTick>()
+        .init_resource::<MilestoneTracker>()
+        .init_resource::<Watchlist>()
+        .init_resource::<PopulationHistory>()
+        .init_resource::<MeteorSettings>()
+        .init_resource::<InteractionLimits>()
+        .init_resource::<VmSettings>()
+        .init_resource::<SplitFollow>()
+        .init_resource::<FastForward>()
+        .init_resource::<SpawnSettings>()
+        .init_resource::<ReproductionSettings>()
+        .init_resource::<StarvationWarning>()
+        .init_resource::<LifecycleQueue>()
+        .init_resource::<Calls>()
+        .init_resource::<SpawnBudget>()
+        .init_resource::<SpawnQueue>()
+        .init_resource::<SpeciesRegistry>()
+        .init_resource::<MutationConfig>()
+        .init_resource::<GenomeLengthCost>()
+        .init_resource::<EnergyFlows>()
+        .init_resource::<FailsafeSettings>()
+        .init_resource::<GeneTransfer>()
+        .init_resource::<HallOfFame>()
+        .init_resource::<Handedness>()
+        .init_resource::<ScentModel>()
+        .init_resource::<SmellRange>()
+        .init_resource::<SensorNoise>()
+        .init_resource::<DeathMarkers>()
+        .init_resource::<PopulationSampling>()
+        .add_event::<AnimalDied>()
+        .add_event::<AnimalDied>()
+        .add_event::<AnimalIntent>()
+        .add_event::<AttackIntent>()
+        .add_event::<PlantEaten>()
+        .add_event::<PlantDepleted>()
+        .add_event::<AnimalSplit>()
+        .add_event::<MeteorStrike>()
+        .add_event::<Milestone>()
+        .add_event::<ImportGenomes>()
+        .add_event::<SaveGenomes>()
+        .register_type::<Animal>()
+        .register_type::<Phenotype>()
+        .register_type::<Lineage>()
+        .register_type::<BodyHue>()
+        .register_type::<CorpseScent>()
+        .register_type::<Sensors>()
+        .register_type::<Species>()
+        .register_type::<Plant>()
+        .register_type::<PlantScent>()
+        .register_type::<GutSeeds>()
+        .register_type::<RegrowMarker>()
+        .register_type::<Carrying>()
+        .register_type::<Carried>()
+        .register_type::<ScorchedZone>()
         .insert_resource(PlantSpawnTimer(Timer::from_seconds(
             PLANT_SPAWN_INTERVAL,
             TimerMode::Repeating,
@@ -64,60 +374,302 @@ fn main() {
             METABOLISM_INTERVAL,
             TimerMode::Repeating,
         )))
-        .add_systems(Startup, (setup_camera, spawn_test_animals))
+        .insert_resource(ChartSampleTimer(Timer::from_seconds(
+            CHART_SAMPLE_INTERVAL,
+            TimerMode::Repeating,
+        )))
+        .insert_resource(FitnessSampleTimer(Timer::from_seconds(
+            CHART_SAMPLE_INTERVAL,
+            TimerMode::Repeating,
+        )))
+        .insert_resource(WatchlistSampleTimer(Timer::from_seconds(
+            WATCHLIST_SAMPLE_INTERVAL,
+            TimerMode::Repeating,
+        )))
         .add_systems(
             Update,
-            (
-                // Always run (even when paused)
-                camera_zoom,
-                camera_pan,
-                camera_touch_controls,
-                handle_selection,
-                update_selection_visuals,
-                manage_selection_outlines,
-                update_outline_positions,
-                ui_system,
-            ),
+            // Runs SimulationStep when the simulation is running or fast-forwarding
+            run_simulation,
         )
         .add_systems(
-            Update,
+            SimulationStep,
+            // Systems run in the order listed: the schedule would otherwise break ties with a
+            // per-process hash, and seeded runs would differ between launches
             (
-                // Only run when simulation is running
-                spawn_plants,
-                grow_plants,
-                update_plant_visuals,
-                update_sensors,
-                execute_genomes,
-                split_animals,
-                animal_metabolism,
-                remove_dead_animals,
-                population_failsafe,
+                // Plants and world
+                (
+                    advance_simulation_tick,
+                    govern_plant_spawn_rate,
+                    spawn_plants,
+                    grow_plants,
+                    update_plant_visuals,
+                    disperse_seeds,
+                    regrow_plants,
+                    update_pheromones,
+                    random_meteors,
+                    apply_meteor_strikes,
+                    recover_scorched_zones,
+                    spawn_death_markers,
+                    fade_death_markers,
+                    leave_corpse_scents,
+                    fade_corpse_scents,
+                )
+                    .chain_ignore_deferred(),
+                // Animals
+                (
+                    update_sensors,
+                    detect_contacts.before(execute_genomes),
+                    execute_genomes,
+                    apply_intents.after(execute_genomes),
+                    write_trace_exports.after(execute_genomes),
+                    check_watchpoints.after(execute_genomes),
+                    apply_lifecycle_queue
+                        .after(apply_intents)
+                        .before(split_animals),
+                    ingest_seeds.after(apply_intents),
+                    leave_regrow_markers.after(apply_intents),
+                    resolve_interactions,
+                    transfer_genes,
+                    move_carried_plants,
+                    split_animals,
+                    apply_color_changes.after(apply_intents),
+                    animal_metabolism,
+                    remove_dead_animals,
+                    population_failsafe,
+                    drain_spawn_queue,
+                )
+                    .chain_ignore_deferred(),
+                // Statistics and recording
+                (
+                    sample_watchlist,
+                    sample_population,
+                    follow_selected_splits,
+                    detect_milestones,
+                    record_milestones,
+                    update_fitness_metrics,
+                    cluster_species,
+                    roll_energy_flows,
+                    update_hall_of_fame,
+                    sample_population_genomes,
+                    record_trails,
+                )
+                    .chain_ignore_deferred(),
             )
-                .run_if(|state: Res<SimulationState>| *state == SimulationState::Running),
+                .chain_ignore_deferred(),
         )
-        .run();
+        .add_fitness_metric(MeanEnergy::default())
+        .add_fitness_metric(KillCount::default())
+        .add_fitness_metric(ExplorationArea::default())
+}
+
+/// Headless app simulating from the given command line, for tests
+#[cfg(test)]
+fn headless_app(cli: CliArgs) -> App {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .init_resource::<Assets<Mesh>>()
+        .init_resource::<Assets<ColorMaterial>>()
+        .insert_resource(RunName("test".to_string()))
+        .insert_resource(cli)
+        .add_systems(Startup, (apply_scenario, start_benchmark));
+    add_simulation(&mut app);
+    app
 }
 
+/// Settings resources edited from the UI, grouped to stay under Bevy's system parameter limit
+#[derive(SystemParam)]
+struct UiSettings<'w> {
+    meteor: ResMut<'w, MeteorSettings>,
+    interaction: ResMut<'w, InteractionLimits>,
+    milestones: ResMut<'w, MilestoneTracker>,
+    vm: ResMut<'w, VmSettings>,
+    split_follow: ResMut<'w, SplitFollow>,
+    governor: ResMut<'w, PlantGovernor>,
+    seeds: ResMut<'w, SeedDispersal>,
+    starvation: ResMut<'w, StarvationWarning>,
+    fast_forward: ResMut<'w, FastForward>,
+    spawn: ResMut<'w, SpawnSettings>,
+    reproduction: ResMut<'w, ReproductionSettings>,
+    plant_spawn_timer: Res<'w, PlantSpawnTimer>,
+    dock: Res<'w, DockLayout>,
+    spawn_queue: ResMut<'w, SpawnQueue>,
+    spawn_budget: ResMut<'w, SpawnBudget>,
+    species: ResMut<'w, SpeciesRegistry>,
+}
+
+/// Evolution and population rules edited from the UI, split from `UiSettings` for the
+/// same parameter limit
+#[derive(SystemParam)]
+struct UiRules<'w> {
+    mutation: ResMut<'w, MutationConfig>,
+    length_cost: ResMut<'w, GenomeLengthCost>,
+    failsafe: ResMut<'w, FailsafeSettings>,
+    gene_transfer: ResMut<'w, GeneTransfer>,
+    handedness: ResMut<'w, Handedness>,
+    scent: ResMut<'w, ScentModel>,
+    smell_range: ResMut<'w, SmellRange>,
+    sensor_noise: ResMut<'w, SensorNoise>,
+    regrowth: ResMut<'w, PlantRegrowth>,
+    pheromones: ResMut<'w, PheromoneGrid>,
+    plants: ResMut<'w, PlantConfig>,
+    shading: ResMut<'w, PlantShading>,
+}
+
+/// Records of the run kept from the UI, split from `UiSettings` for the same parameter
+/// limit
+#[derive(SystemParam)]
+struct UiArchive<'w> {
+    run: Res<'w, RunName>,
+    hall_of_fame: Res<'w, HallOfFame>,
+    sampling: ResMut<'w, PopulationSampling>,
+}
+
+/// State of the text inputs in the Simulation Info window
+struct UiForms {
+    source: String,
+    source_error: Option<String>,
+    import_path: String,
+    import_copies: usize,
+    fast_forward_ticks: u32,
+    /// Last diff against the seed genome, for the animal it was computed for
+    seed_diff: Option<(Entity, GenomeDiff)>,
+    /// Number of mutated copies generated by "Preview mutation"
+    preview_count: usize,
+    /// Mutated copies of an animal's genome with the mutations that made them
+    #[allow(clippy::type_complexity)]
+    mutation_preview: Option<(Entity, Vec<(Genome, Vec<Mutation>)>)>,
+    /// Executor state shown in the Genome Viewer, for the animal it was taken from
+    viewer: Option<(Entity, ViewerSnapshot)>,
+    watch_expression: String,
+    watch_error: Option<String>,
+}
+
+/// Genome Viewer text taken at the UI refresh rate, so the viewer neither flickers nor
+/// reformats the whole execution state every frame
+struct ViewerSnapshot {
+    status: String,
+    /// Stack values from the top down
+    stack: Vec<String>,
+    /// Bool stack from the top down, under typed stacks
+    bool_stack: Option<String>,
+    source: String,
+    /// Recorded trace lines, newest first, while tracing
+    trace: Option<Vec<String>>,
+}
+
+impl ViewerSnapshot {
+    fn take(animal: &Animal, genome: &Genome, executor: &GenomeExecutor) -> Self {
+        let top = executor.stack.len().saturating_sub(1);
+        Self {
+            status: format!(
+                "Energy: {} | IP: {} | Executed: {}/{} | Overflows: {} | Skipped: {} | Saturated: {}",
+                animal.energy,
+                executor.instruction_pointer,
+                executor.instructions_executed_this_frame,
+                executor.max_instructions_per_frame,
+                executor.stack_overflows,
+                executor.words_skipped,
+                executor.saturations
+            ),
+            stack: executor
+                .stack
+                .iter()
+                .enumerate()
+                .rev()
+                .map(|(i, value)| {
+                    let prefix = if i == top { "▶ " } else { "  " };
+                    format!("{}[{}] {}", prefix, i, value)
+                })
+                .collect(),
+            bool_stack: (executor.stack_mode == StackMode::Typed).then(|| {
+                let bools: Vec<String> = executor
+                    .bool_stack
+                    .iter()
+                    .rev()
+                    .map(bool::to_string)
+                    .collect();
+                format!("Bools (top first): [{}]", bools.join(", "))
+            }),
+            source: genome.to_source(),
+            trace: executor.trace.as_ref().map(|trace| {
+                trace
+                    .iter()
+                    .rev()
+                    .map(|entry| {
+                        let top = entry
+                            .stack_top
+                            .map_or("-".to_string(), |value| value.to_string());
+                        format!(
+                            "{:3}: {:<16} top={}",
+                            entry.instruction_pointer, entry.word, top
+                        )
+                    })
+                    .collect()
+            }),
+        }
+    }
+}
+
+impl Default for UiForms {
+    fn default() -> Self {
+        Self {
+            source: Genome::seed().to_source(),
+            source_error: None,
+            import_path: String::new(),
+            import_copies: 1,
+            fast_forward_ticks: FAST_FORWARD_DEFAULT_TICKS,
+            seed_diff: None,
+            preview_count: MUTATION_PREVIEW_COUNT,
+            mutation_preview: None,
+            viewer: None,
+            watch_expression: String::new(),
+            watch_error: None,
+        }
+    }
+}
+
+/// Color of a mutation in lists: yellow for substitutions, green for insertions, red
+/// for deletions, blue and purple for rearrangements, cyan for transferred genes
+fn mutation_color(mutation: &Mutation) -> egui::Color32 {
+    match mutation {
+        Mutation::Substituted { .. } => egui::Color32::from_rgb(255, 220, 100),
+        Mutation::Inserted { .. } | Mutation::BlockInserted { .. } | Mutation::Spliced { .. } => {
+            egui::Color32::from_rgb(100, 255, 100)
+        }
+        Mutation::Deleted { .. } | Mutation::BlockDeleted { .. } => {
+            egui::Color32::from_rgb(255, 100, 100)
+        }
+        Mutation::Inverted { .. } => egui::Color32::from_rgb(150, 180, 255),
+        Mutation::Translocated { .. } => egui::Color32::from_rgb(200, 150, 255),
+        Mutation::Transferred { .. } => egui::Color32::from_rgb(100, 230, 230),
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
 fn ui_system(
     mut commands: Commands,
+    mut forms: Local<UiForms>,
     mut contexts: EguiContexts,
     camera_state: Res<CameraState>,
     mut simulation_state: ResMut<SimulationState>,
     selected_entity: Res<SelectedEntity>,
-    _query: Query<&Transform, With<MainCamera>>,
+    mut watchlist: ResMut<Watchlist>,
+    mut meteor_strikes: EventWriter<MeteorStrike>,
+    mut settings: UiSettings,
+    mut rules: UiRules,
+    mut archive: UiArchive,
+    mut ui_refresh: ResMut<UiRefresh>,
     plants: Query<&Plant>,
     animals: Query<&Animal>,
-    selected_plants: Query<(&Plant, &Transform), With<Selected>>,
-    selected_animals: Query<
-        (&Animal, &Genome, &GenomeExecutor, &Sensors, &Transform),
-        With<Selected>,
-    >,
-    mut meshes: ResMut<Assets<Mesh>>,
-    mut materials: ResMut<Assets<ColorMaterial>>,
+    selected_plants: Query<PlantInspection, With<Selected>>,
+    mut selected_animals: Query<AnimalInspection, With<Selected>>,
 ) {
-    egui::Window::new("Simulation Info")
-        .default_pos(egui::pos2(10.0, 10.0))
-        .show(contexts.ctx_mut(), |ui| {
+    let dock = *settings.dock;
+    dock.show(
+        contexts.ctx_mut(),
+        DockTab::Simulation,
+        egui::Window::new("Simulation Info").default_pos(egui::pos2(10.0, 10.0)),
+        |ui| {
             // Pause/Resume and Spawn buttons
             ui.horizontal(|ui| {
                 let button_text = if *simulation_state == SimulationState::Running {
@@ -142,21 +694,555 @@ fn ui_system(
                 ui.label(format!("State: {}", state_text));
             });
 
+            if settings.fast_forward.is_active() {
+                ui.horizontal(|ui| {
+                    let fast_forward = &settings.fast_forward;
+                    ui.add(
+                        egui::ProgressBar::new(fast_forward.progress()).text(format!(
+                            "⏩ {} / {} ticks",
+                            fast_forward.total - fast_forward.remaining,
+                            fast_forward.total
+                        )),
+                    );
+                    if ui.button("Cancel").clicked() {
+                        settings.fast_forward.cancel();
+                    }
+                });
+            } else {
+                ui.horizontal(|ui| {
+                    if ui.button("⏩ Fast-forward").clicked() {
+                        settings.fast_forward.start(forms.fast_forward_ticks);
+                    }
+                    ui.add(
+                        egui::DragValue::new(&mut forms.fast_forward_ticks)
+                            .range(1..=1_000_000)
+                            .speed(100),
+                    );
+                    ui.label("ticks");
+                });
+            }
+            ui.horizontal(|ui| {
+                ui.label("Panel refresh:");
+                ui.add(
+                    egui::DragValue::new(&mut ui_refresh.rate)
+                        .range(UI_REFRESH_MIN_RATE..=UI_REFRESH_MAX_RATE)
+                        .suffix(" Hz"),
+                )
+                .on_hover_text(
+                    "How often the census, charts and genome viewer update, whatever the \
+                     simulation speed",
+                );
+            });
+
             ui.horizontal(|ui| {
                 if ui
                     .button(format!("➕ Spawn {} Animals", MANUAL_SPAWN_COUNT))
                     .clicked()
                 {
-                    spawn_seed_animals(
-                        &mut commands,
-                        &mut meshes,
-                        &mut materials,
+                    queue_seed_animals(
+                        &mut settings.spawn_queue,
+                        &settings.spawn,
                         MANUAL_SPAWN_COUNT,
                         STARTING_ANIMAL_ENERGY,
+                        &mut rand::thread_rng(),
+                    );
+                }
+                egui::ComboBox::from_id_salt("spawn_distribution")
+                    .selected_text(settings.spawn.distribution.to_string())
+                    .show_ui(ui, |ui| {
+                        for distribution in SpawnDistribution::ALL {
+                            ui.selectable_value(
+                                &mut settings.spawn.distribution,
+                                distribution,
+                                distribution.to_string(),
+                            );
+                        }
+                    });
+            });
+            ui.horizontal(|ui| {
+                ui.label("Max spawns per tick:");
+                ui.add(egui::DragValue::new(&mut settings.spawn_budget.per_tick).range(1..=10_000));
+                if !settings.spawn_queue.is_empty() {
+                    ui.label(format!("({} animals queued)", settings.spawn_queue.len()));
+                }
+            });
+            if settings.spawn.distribution == SpawnDistribution::Cluster {
+                ui.horizontal(|ui| {
+                    ui.label("Cluster at");
+                    ui.add(
+                        egui::DragValue::new(&mut settings.spawn.cluster_center.x).prefix("x: "),
+                    );
+                    ui.add(
+                        egui::DragValue::new(&mut settings.spawn.cluster_center.y).prefix("y: "),
+                    );
+                });
+            }
+
+            ui.horizontal(|ui| {
+                ui.label("Reproduction:");
+                egui::ComboBox::from_id_salt("reproduction_mode")
+                    .selected_text(settings.reproduction.mode.to_string())
+                    .show_ui(ui, |ui| {
+                        for mode in ReproductionMode::ALL {
+                            ui.selectable_value(
+                                &mut settings.reproduction.mode,
+                                mode,
+                                mode.to_string(),
+                            );
+                        }
+                    });
+                ui.checkbox(&mut settings.reproduction.mate_required, "Mate required")
+                    .on_hover_text("Splitting needs a partner in range, who pays energy too");
+            });
+
+            if settings.reproduction.mode == ReproductionMode::Sexual {
+                ui.horizontal(|ui| {
+                    ui.checkbox(
+                        &mut settings.reproduction.hybrid_incompatibility,
+                        "Hybrid incompatibility",
+                    )
+                    .on_hover_text(
+                        "Partners whose genomes differ by more edits than this reproduce asexually",
+                    );
+                    ui.add_enabled(
+                        settings.reproduction.hybrid_incompatibility,
+                        egui::DragValue::new(&mut settings.reproduction.max_crossover_distance)
+                            .range(0..=1000)
+                            .suffix(" edits"),
+                    );
+                });
+            }
+
+            ui.horizontal(|ui| {
+                ui.label("Offspring placement:");
+                egui::ComboBox::from_id_salt("offspring_dispersal")
+                    .selected_text(settings.reproduction.dispersal.to_string())
+                    .show_ui(ui, |ui| {
+                        for dispersal in OffspringDispersal::ALL {
+                            ui.selectable_value(
+                                &mut settings.reproduction.dispersal,
+                                dispersal,
+                                dispersal.to_string(),
+                            );
+                        }
+                    });
+            });
+
+            ui.horizontal(|ui| {
+                ui.label("Max genome length:");
+                ui.add(egui::DragValue::new(&mut rules.mutation.max_length).range(1..=10_000));
+                egui::ComboBox::from_id_salt("bloat_control")
+                    .selected_text(rules.mutation.bloat_control.to_string())
+                    .show_ui(ui, |ui| {
+                        for strategy in BloatControl::ALL {
+                            ui.selectable_value(
+                                &mut rules.mutation.bloat_control,
+                                strategy,
+                                strategy.to_string(),
+                            );
+                        }
+                    });
+            });
+
+            ui.horizontal(|ui| {
+                ui.label("Cost per genome word:");
+                ui.add(
+                    egui::DragValue::new(&mut rules.length_cost.split_per_word)
+                        .range(0.0..=5.0)
+                        .speed(0.01)
+                        .prefix("split "),
+                );
+                ui.add(
+                    egui::DragValue::new(&mut rules.length_cost.metabolism_per_word)
+                        .range(0.0..=1.0)
+                        .speed(0.001)
+                        .prefix("metabolism "),
+                )
+                .on_hover_text("Rounded per animal, so small values only bite for long genomes");
+            });
+
+            ui.horizontal(|ui| {
+                ui.label("Left/right:");
+                egui::ComboBox::from_id_salt("handedness")
+                    .selected_text(rules.handedness.to_string())
+                    .show_ui(ui, |ui| {
+                        for handedness in Handedness::ALL {
+                            ui.selectable_value(
+                                &mut *rules.handedness,
+                                handedness,
+                                handedness.to_string(),
+                            );
+                        }
+                    })
+                    .response
+                    .on_hover_text(
+                        "Mirrored swaps left and right for sensors, strafing and turning",
+                    );
+            });
+
+            ui.horizontal(|ui| {
+                ui.label("Smell:");
+                egui::ComboBox::from_id_salt("scent_model")
+                    .selected_text(rules.scent.to_string())
+                    .show_ui(ui, |ui| {
+                        for model in ScentModel::ALL {
+                            ui.selectable_value(&mut *rules.scent, model, model.to_string());
+                        }
+                    })
+                    .response
+                    .on_hover_text(
+                        "Intensity sums energy / distance² of every plant in a quadrant",
+                    );
+                ui.label("within");
+                ui.add(
+                    egui::DragValue::new(&mut rules.smell_range.radius)
+                        .range(0.0..=WORLD_BOUNDS * 3.0)
+                        .speed(5.0),
+                )
+                .on_hover_text(
+                    "Farthest plants, animals and corpses can be smelled or sensed as the \
+                     nearest animal or plant",
+                );
+            });
+
+            ui.horizontal(|ui| {
+                ui.label("Sensor noise:");
+                ui.add(
+                    egui::DragValue::new(&mut rules.sensor_noise.sigma)
+                        .range(0.0..=MAX_SENSOR_NOISE)
+                        .speed(0.01),
+                )
+                .on_hover_text(
+                    "Standard deviation of the gaussian noise scaling each smell, distance, \
+                     sight, pheromone and motion reading; 0 keeps perception exact",
+                );
+            });
+
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut rules.gene_transfer.enabled, "Gene transfer")
+                    .on_hover_text(
+                        "Touching animals occasionally copy a segment of each other's genome \
+                         into their future offspring",
+                    );
+                ui.add_enabled_ui(rules.gene_transfer.enabled, |ui| {
+                    ui.add(
+                        egui::DragValue::new(&mut rules.gene_transfer.probability)
+                            .range(0.0..=1.0)
+                            .speed(0.005)
+                            .prefix("chance "),
+                    )
+                    .on_hover_text(format!(
+                        "Chance per touching animal per check, every {}s",
+                        GENE_TRANSFER_INTERVAL
+                    ));
+                    ui.add(
+                        egui::DragValue::new(&mut rules.gene_transfer.max_length)
+                            .range(1..=100)
+                            .prefix("≤ ")
+                            .suffix(" words"),
+                    );
+                });
+            });
+
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut rules.failsafe.enabled, "Failsafe")
+                    .on_hover_text("Respawn animals when the population falls to the threshold");
+                ui.add_enabled_ui(rules.failsafe.enabled, |ui| {
+                    ui.add(
+                        egui::DragValue::new(&mut rules.failsafe.threshold)
+                            .range(0..=10_000)
+                            .prefix("at ≤ "),
+                    );
+                    ui.add(
+                        egui::DragValue::new(&mut rules.failsafe.cooldown_ticks)
+                            .range(0..=1_000_000)
+                            .speed(10)
+                            .prefix("every ")
+                            .suffix(" ticks"),
+                    );
+                    egui::ComboBox::from_id_salt("failsafe_source")
+                        .selected_text(rules.failsafe.source.to_string())
+                        .show_ui(ui, |ui| {
+                            for source in RespawnSource::ALL {
+                                ui.selectable_value(
+                                    &mut rules.failsafe.source,
+                                    source,
+                                    source.to_string(),
+                                );
+                            }
+                        });
+                });
+            });
+
+            ui.horizontal(|ui| {
+                if ui.button("☄ Meteor Strike").clicked() {
+                    meteor_strikes.send(MeteorStrike::random(
+                        settings.meteor.radius,
+                        &mut rand::thread_rng(),
+                    ));
+                }
+                ui.checkbox(&mut settings.meteor.random_enabled, "Random meteors");
+            });
+
+            ui.horizontal(|ui| {
+                ui.label("⚔ Per tick, max energy lost");
+                ui.add(
+                    egui::DragValue::new(&mut settings.interaction.max_loss_per_tick)
+                        .range(0..=1000),
+                );
+                ui.label("gained");
+                ui.add(
+                    egui::DragValue::new(&mut settings.interaction.max_gain_per_tick)
+                        .range(0..=1000),
+                );
+            });
+
+            ui.checkbox(
+                &mut settings.milestones.auto_screenshot,
+                "📷 Screenshot on milestones",
+            );
+
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut settings.governor.enabled, "🌱 Plant governor");
+                ui.add(
+                    egui::DragValue::new(&mut settings.governor.target_min)
+                        .range(0.1..=100.0)
+                        .speed(0.1),
+                );
+                ui.label("-");
+                ui.add(
+                    egui::DragValue::new(&mut settings.governor.target_max)
+                        .range(0.1..=100.0)
+                        .speed(0.1),
+                );
+                ui.label(format!(
+                    "plants/animal (spawn every {:.2}s)",
+                    settings.plant_spawn_timer.0.duration().as_secs_f32()
+                ));
+            });
+
+            ui.horizontal(|ui| {
+                ui.label("Starving below:");
+                ui.add(egui::DragValue::new(&mut settings.starvation.threshold).range(0..=1000));
+                ui.checkbox(
+                    &mut settings.starvation.reduce_budget,
+                    "Reduce instruction budget",
+                )
+                .on_hover_text(format!(
+                    "Starving animals run at most {} instructions per frame",
+                    STARVATION_INSTRUCTION_BUDGET
+                ));
+            });
+
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut settings.seeds.enabled, "🌰 Seed dispersal");
+                ui.add(
+                    egui::DragValue::new(&mut settings.seeds.probability)
+                        .range(0.0..=1.0)
+                        .speed(0.01)
+                        .prefix("chance: "),
+                );
+                ui.add(
+                    egui::DragValue::new(&mut settings.seeds.delay_ticks)
+                        .range(1..=10_000)
+                        .suffix(" ticks"),
+                );
+            });
+
+            ui.horizontal(|ui| {
+                ui.label("Plant placement:");
+                egui::ComboBox::from_id_salt("plant_placement")
+                    .selected_text(rules.plants.placement.to_string())
+                    .show_ui(ui, |ui| {
+                        for placement in PlantPlacement::ALL {
+                            ui.selectable_value(
+                                &mut rules.plants.placement,
+                                placement,
+                                placement.to_string(),
+                            );
+                        }
+                    })
+                    .response
+                    .on_hover_text(
+                        "Even places each new plant at the best of several random spots, \
+                         the one farthest from other plants, so food does not clump",
                     );
+            });
+
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut rules.regrowth.enabled, "🌱 Regrow in place")
+                    .on_hover_text(
+                        "Plants eaten to nothing may sprout again at the same spot after a delay",
+                    );
+                ui.add(
+                    egui::DragValue::new(&mut rules.regrowth.share)
+                        .range(0.0..=1.0)
+                        .speed(0.01)
+                        .prefix("share: "),
+                );
+                ui.add(
+                    egui::DragValue::new(&mut rules.regrowth.delay_ticks)
+                        .range(1..=10_000)
+                        .suffix(" ticks"),
+                );
+            });
+
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut rules.shading.enabled, "🌳 Shading")
+                    .on_hover_text(format!(
+                        "Mature plants (energy {} or more) slow the growth of plants near \
+                         them, so crowded patches stall and vegetation spreads out",
+                        PLANT_MATURE_ENERGY
+                    ));
+                ui.add(
+                    egui::DragValue::new(&mut rules.shading.radius)
+                        .range(1.0..=200.0)
+                        .prefix("within: "),
+                );
+                ui.add(
+                    egui::DragValue::new(&mut rules.shading.strength)
+                        .range(0.0..=5.0)
+                        .speed(0.01)
+                        .prefix("strength: "),
+                )
+                .on_hover_text("A plant grows with chance 1 / (1 + strength × shading plants)");
+            });
+
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut rules.pheromones.enabled, "🐜 Pheromones")
+                    .on_hover_text(format!("Total on the map: {:.0}", rules.pheromones.total()));
+                ui.add(
+                    egui::DragValue::new(&mut rules.pheromones.diffusion)
+                        .range(0.0..=1.0)
+                        .speed(0.005)
+                        .prefix("spread: "),
+                );
+                ui.add(
+                    egui::DragValue::new(&mut rules.pheromones.decay)
+                        .range(0.0..=1.0)
+                        .speed(0.001)
+                        .prefix("decay: "),
+                );
+                let mut cell_size = rules.pheromones.cell_size();
+                let resized = ui
+                    .add(
+                        egui::DragValue::new(&mut cell_size)
+                            .range(5.0..=100.0)
+                            .prefix("cell: "),
+                    )
+                    .on_hover_text("Side length of a grid cell; smaller cells cost more per tick")
+                    .changed();
+                if resized {
+                    rules.pheromones.set_cell_size(cell_size);
+                }
+                ui.add(
+                    egui::DragValue::new(&mut rules.pheromones.update_interval)
+                        .range(1..=60)
+                        .prefix("every: ")
+                        .suffix(" ticks"),
+                )
+                .on_hover_text(
+                    "Diffuse less often to save time on fine grids; readings are blended \
+                     between passes",
+                );
+                if ui.small_button("Clear").clicked() {
+                    rules.pheromones.clear();
+                }
+            });
+
+            // Hand-written genomes, spawned at the center of the view
+            egui::CollapsingHeader::new("Spawn from source").show(ui, |ui| {
+                ui.add(
+                    egui::TextEdit::multiline(&mut forms.source)
+                        .code_editor()
+                        .desired_rows(6),
+                );
+                if ui.button("➕ Spawn").clicked() {
+                    match Genome::parse(&forms.source) {
+                        Ok(genome) => {
+                            settings.spawn_queue.push(
+                                genome,
+                                STARTING_ANIMAL_ENERGY,
+                                camera_state.position,
+                                0.0,
+                            );
+                            forms.source_error = None;
+                        }
+                        Err(err) => forms.source_error = Some(err.to_string()),
+                    }
+                }
+                if let Some(error) = &forms.source_error {
+                    ui.colored_label(egui::Color32::from_rgb(255, 100, 100), error);
                 }
             });
 
+            // Seed from a genome file, a directory of them or a zip archive
+            egui::CollapsingHeader::new("Import genomes").show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Path:");
+                    ui.text_edit_singleline(&mut forms.import_path)
+                        .on_hover_text(format!(
+                            "Genome source (.{}) or banked genome (.{}) files; banked genomes \
+                         are saved under {}/. Dropping a genome file onto the world spawns \
+                         it at the cursor",
+                            GENOME_FILE_EXTENSION,
+                            GENOME_BANK_EXTENSION,
+                            bank_dir(&archive.run).display()
+                        ));
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Copies each:");
+                    ui.add(egui::DragValue::new(&mut forms.import_copies).range(1..=100));
+                    if ui.button("📂 Import").clicked() && !forms.import_path.is_empty() {
+                        commands.send_event(ImportGenomes {
+                            path: forms.import_path.clone().into(),
+                            copies: forms.import_copies,
+                            center: Vec2::ZERO,
+                        });
+                    }
+                });
+            });
+
+            ui.horizontal(|ui| {
+                ui.label("Stack size:");
+                ui.add(egui::DragValue::new(&mut settings.vm.stack_capacity).range(1..=1024));
+                egui::ComboBox::from_id_salt("overflow_policy")
+                    .selected_text(settings.vm.overflow_policy.to_string())
+                    .show_ui(ui, |ui| {
+                        for policy in StackOverflowPolicy::ALL {
+                            ui.selectable_value(
+                                &mut settings.vm.overflow_policy,
+                                policy,
+                                policy.to_string(),
+                            );
+                        }
+                    });
+                ui.checkbox(&mut settings.vm.safe_math, "Safe math")
+                    .on_hover_text(format!(
+                        "Clamp arithmetic results to ±{} and turn NaN into 0",
+                        SAFE_MATH_LIMIT
+                    ));
+            });
+            ui.horizontal(|ui| {
+                ui.label("Stacks:");
+                egui::ComboBox::from_id_salt("stack_mode")
+                    .selected_text(settings.vm.stack_mode.to_string())
+                    .show_ui(ui, |ui| {
+                        for mode in StackMode::ALL {
+                            ui.selectable_value(
+                                &mut settings.vm.stack_mode,
+                                mode,
+                                mode.to_string(),
+                            );
+                        }
+                    })
+                    .response
+                    .on_hover_text(
+                        "Experimental: typed keeps floats and bools on separate stacks, so \
+                         no word is skipped for popping the wrong type",
+                    );
+            });
+
             ui.separator();
             ui.heading("Camera Controls");
             ui.separator();
@@ -172,6 +1258,8 @@ fn ui_system(
             ui.label("• Mouse Wheel - Zoom in/out");
             ui.label("• Middle Mouse - Pan camera");
             ui.label("• Touch - Pinch to zoom, drag to pan");
+            ui.label("• + / - / 0 - Step zoom in/out, reset zoom");
+            ui.label("• PageUp / PageDown - Pan a screen up/down");
             ui.label("• Left Click - Select entity");
 
             ui.separator();
@@ -197,192 +1285,531 @@ fn ui_system(
                 ui.label(format!("Animal Total Energy: {}", total_energy));
                 ui.label(format!("Animal Avg Energy: {:.1}", avg_energy));
             }
-        });
 
-    // Show selected entity stats
-    if selected_entity.entity.is_some() {
-        egui::Window::new("Selected Entity")
-            .default_pos(egui::pos2(10.0, 300.0))
-            .show(contexts.ctx_mut(), |ui| {
-                // Check if it's a plant
-                if let Ok((plant, transform)) = selected_plants.get_single() {
-                    ui.heading("Plant");
-                    ui.separator();
-
-                    ui.label(format!("Energy: {} / {}", plant.energy, Plant::MAX_ENERGY));
-
-                    // Progress bar for energy
-                    let energy_ratio = plant.energy as f32 / Plant::MAX_ENERGY as f32;
-                    let progress_bar = egui::ProgressBar::new(energy_ratio)
-                        .text(format!("{}%", (energy_ratio * 100.0) as u32));
-                    ui.add(progress_bar);
-
-                    ui.separator();
-                    ui.label(format!(
-                        "Position: ({:.1}, {:.1})",
-                        transform.translation.x, transform.translation.y
-                    ));
-                } else if let Ok((animal, genome, executor, sensors, transform)) =
-                    selected_animals.get_single()
+            ui.label(format!("Species: {}", settings.species.species.len()));
+            ui.horizontal(|ui| {
+                ui.label("Species distance:");
+                ui.add(egui::DragValue::new(&mut settings.species.threshold).range(1..=100));
+            });
+
+            ui.horizontal(|ui| {
+                let dir = archive.run.output_dir();
+                ui.label(format!("Run: {}", archive.run.0))
+                    .on_hover_text(format!("Exports are written under {}", dir.display()));
+                if ui.button("📂 Open output").clicked()
+                    && let Err(err) = open_directory(&dir)
                 {
-                    ui.heading("Animal");
-                    ui.separator();
+                    error!("Failed to open {}: {err}", dir.display());
+                }
+            });
 
-                    ui.label(format!("Energy: {}", animal.energy));
-                    ui.label(format!(
-                        "Age: {:.1}s / {:.0}s",
-                        animal.age,
-                        config::MAX_LIFESPAN
-                    ));
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut archive.sampling.enabled, "📸 Sample")
+                    .on_hover_text("Write random living genomes to this run's genome bank");
+                ui.add(egui::DragValue::new(&mut archive.sampling.size).range(1..=1000));
+                ui.label("genomes every");
+                ui.add(
+                    egui::DragValue::new(&mut archive.sampling.interval)
+                        .range(1..=1_000_000)
+                        .speed(10),
+                );
+                ui.label("ticks");
+            });
 
-                    ui.separator();
+            let hall_of_fame = &archive.hall_of_fame;
+            egui::CollapsingHeader::new(format!(
+                "Hall of Fame ({} genomes)",
+                hall_of_fame.entries.len()
+            ))
+            .show(ui, |ui| {
+                ui.horizontal(|ui| {
                     ui.label(format!(
-                        "Position: ({:.1}, {:.1})",
-                        transform.translation.x, transform.translation.y
+                        "Animals reaching {:.0}s, {} offspring or {} energy eaten, oldest first",
+                        hall_of_fame.min_age,
+                        hall_of_fame.min_offspring,
+                        hall_of_fame.min_energy_eaten
                     ));
+                    if ui
+                        .add_enabled(
+                            !hall_of_fame.entries.is_empty(),
+                            egui::Button::new("💾 Save all"),
+                        )
+                        .on_hover_text("Write every genome to this run's genome bank")
+                        .clicked()
+                    {
+                        commands.send_event(SaveGenomes {
+                            genomes: hall_of_fame
+                                .entries
+                                .iter()
+                                .enumerate()
+                                .map(|(rank, entry)| {
+                                    (format!("fame-{:02}", rank + 1), entry.genome.clone())
+                                })
+                                .collect(),
+                        });
+                    }
+                });
+                for (rank, entry) in hall_of_fame.entries.iter().enumerate() {
+                    ui.horizontal(|ui| {
+                        ui.monospace(format!(
+                            "{:2}. {:5.1}s {:3} offspring {:6} eaten {:3} words",
+                            rank + 1,
+                            entry.age,
+                            entry.offspring,
+                            entry.energy_eaten,
+                            entry.genome.words.len()
+                        ));
+                        if ui.small_button("Spawn").clicked() {
+                            settings.spawn_queue.push(
+                                entry.genome.clone(),
+                                STARTING_ANIMAL_ENERGY,
+                                camera_state.position,
+                                0.0,
+                            );
+                        }
+                    });
+                }
+            });
+        },
+    );
 
-                    let rotation_degrees =
-                        transform.rotation.to_euler(EulerRot::ZXY).0.to_degrees();
-                    ui.label(format!("Facing: {:.1}°", rotation_degrees));
+    // Show selected entity stats
+    if selected_entity.entity.is_some() {
+        dock.show(
+            contexts.ctx_mut(),
+            DockTab::Inspector,
+            egui::Window::new("Selected Entity").default_pos(egui::pos2(10.0, 300.0)),
+            |ui| {
+                let plant = selected_plants.get_single().ok();
+                let animal = selected_animals.get_single().ok();
+                let inspected = plant
+                    .as_ref()
+                    .map(|plant| plant as &dyn Inspectable)
+                    .or(animal.as_ref().map(|animal| animal as &dyn Inspectable));
+                let Some(inspected) = inspected else {
+                    ui.label("Unknown entity type");
+                    return;
+                };
 
-                    ui.separator();
-                    ui.label("Sensors:");
-                    if let Some(dist) = sensors.smell_front {
-                        ui.label(format!("  Front: {:.1}", dist));
-                    } else {
-                        ui.label("  Front: None");
-                    }
-                    if let Some(dist) = sensors.smell_back {
-                        ui.label(format!("  Back: {:.1}", dist));
-                    } else {
-                        ui.label("  Back: None");
-                    }
-                    if let Some(dist) = sensors.smell_left {
-                        ui.label(format!("  Left: {:.1}", dist));
-                    } else {
-                        ui.label("  Left: None");
-                    }
-                    if let Some(dist) = sensors.smell_right {
-                        ui.label(format!("  Right: {:.1}", dist));
-                    } else {
-                        ui.label("  Right: None");
+                ui.horizontal(|ui| {
+                    ui.heading(inspected.kind());
+                    if let Some(entity) = selected_entity.entity
+                        && inspected.pinnable()
+                    {
+                        if watchlist.is_pinned(entity) {
+                            if ui.button("Unpin").clicked() {
+                                watchlist.unpin(entity);
+                            }
+                        } else if ui
+                            .add_enabled(!watchlist.is_full(), egui::Button::new("📌 Pin"))
+                            .clicked()
+                        {
+                            watchlist.pin(entity);
+                        }
                     }
+                });
+                ui.separator();
 
-                    ui.separator();
-                    ui.label("Genome:");
-                    ui.label(format!("  Words: {}", genome.words.len()));
-                    ui.label(format!("  Current IP: {}", executor.instruction_pointer));
-                    ui.label(format!("  Stack Size: {}", executor.stack.len()));
-                    ui.label(format!(
-                        "  Executed: {} / {}",
-                        executor.instructions_executed_this_frame,
-                        executor.max_instructions_per_frame
-                    ));
-                } else {
-                    ui.label("Unknown entity type");
-                }
-            });
+                inspected.show_details(ui);
+
+                ui.separator();
+                let translation = inspected.transform().translation;
+                ui.label(format!(
+                    "Position: ({:.1}, {:.1})",
+                    translation.x, translation.y
+                ));
+            },
+        );
     }
 
     // Show genome viewer for selected animals
-    if selected_entity.entity.is_some() {
-        if let Ok((animal, genome, executor, _sensors, _transform)) = selected_animals.get_single()
-        {
+    if selected_entity.entity.is_some()
+        && let Ok(AnimalInspectionItem {
+            animal,
+            genome,
+            mut executor,
+            transform,
+            export,
+            watchpoint,
+            ..
+        }) = selected_animals.get_single_mut()
+    {
+        let entity = selected_entity.entity.unwrap_or(Entity::PLACEHOLDER);
+        let snapshot = match forms.viewer.take() {
+            Some((shown, snapshot)) if shown == entity && !ui_refresh.is_due() => snapshot,
+            _ => ViewerSnapshot::take(animal, genome, &executor),
+        };
+        dock.show(
+            contexts.ctx_mut(),
+            DockTab::Genome,
             egui::Window::new("Genome Viewer")
                 .default_pos(egui::pos2(300.0, 10.0))
-                .default_size(egui::vec2(500.0, 600.0))
-                .show(contexts.ctx_mut(), |ui| {
-                    ui.heading(format!(
-                        "Stack Machine Genome ({} words)",
-                        genome.words.len()
-                    ));
-                    ui.separator();
+                .default_size(egui::vec2(500.0, 600.0)),
+            |ui| {
+                ui.heading(format!(
+                    "Stack Machine Genome ({} words)",
+                    genome.words.len()
+                ));
+                ui.separator();
 
-                    ui.label(format!(
-                        "Energy: {} | IP: {} | Executed: {}/{}",
-                        animal.energy,
-                        executor.instruction_pointer,
-                        executor.instructions_executed_this_frame,
-                        executor.max_instructions_per_frame
-                    ));
+                ui.label(&snapshot.status);
 
-                    ui.separator();
+                // Recent split of this animal and what the offspring inherited
+                ui.checkbox(
+                    &mut settings.split_follow.auto_follow,
+                    "Follow offspring after split",
+                );
+                if let Some(playback) = &settings.split_follow.playback {
+                    egui::Frame::group(ui.style())
+                        .fill(egui::Color32::from_rgba_unmultiplied(100, 255, 100, 30))
+                        .show(ui, |ui| {
+                            ui.strong(format!(
+                                "Split! Offspring {:?} inherited {} mutation(s)",
+                                playback.child,
+                                playback.mutations.len()
+                            ));
+                            if let Some(partner) = playback.partner {
+                                ui.label(format!("Recombined with {:?}", partner));
+                            }
+                            for mutation in &playback.mutations {
+                                ui.colored_label(
+                                    mutation_color(mutation),
+                                    egui::RichText::new(mutation.to_string()).monospace(),
+                                );
+                            }
+                            if settings.split_follow.auto_follow {
+                                ui.label(format!(
+                                    "Following offspring in {:.1}s",
+                                    playback.timer.remaining_secs()
+                                ));
+                            }
+                        });
+                }
 
-                    // Stack visualization
-                    ui.heading("Stack");
-                    if executor.stack.is_empty() {
-                        ui.colored_label(egui::Color32::GRAY, "  (empty)");
-                    } else {
-                        // Display stack top-to-bottom (reversed)
-                        for (i, value) in executor.stack.iter().enumerate().rev() {
-                            let is_top = i == executor.stack.len() - 1;
-                            let prefix = if is_top { "▶ " } else { "  " };
-                            ui.monospace(format!("{}[{}] {}", prefix, i, value));
-                        }
+                ui.separator();
+
+                // Stack visualization
+                ui.heading("Stack");
+                if snapshot.stack.is_empty() {
+                    ui.colored_label(egui::Color32::GRAY, "  (empty)");
+                } else {
+                    // Display stack top-to-bottom
+                    for line in &snapshot.stack {
+                        ui.monospace(line);
+                    }
+                }
+                if let Some(bools) = &snapshot.bool_stack {
+                    ui.monospace(bools);
+                }
+
+                ui.separator();
+
+                // Constant pool (read by PushConst)
+                ui.heading("Constants");
+                ui.horizontal_wrapped(|ui| {
+                    for (i, value) in genome.constants.iter().enumerate() {
+                        ui.monospace(format!("c{}={:.2}", i, value));
                     }
+                });
 
-                    ui.separator();
-                    ui.heading("Program");
+                ui.separator();
 
-                    // Scrollable area for words
+                // Text form of the genome, for sharing or editing and respawning
+                egui::CollapsingHeader::new("Source").show(ui, |ui| {
+                    if ui.button("📋 Copy").clicked() {
+                        ui.ctx().copy_text(snapshot.source.clone());
+                    }
                     egui::ScrollArea::vertical()
-                        .auto_shrink([false; 2])
+                        .id_salt("genome_source")
+                        .max_height(150.0)
                         .show(ui, |ui| {
-                            for (index, word) in genome.words.iter().enumerate() {
-                                // Check if this is the currently executing word
-                                let is_current = index == executor.instruction_pointer;
-
-                                // Get word category for color
-                                let category = word.category();
-                                let text_color = match category {
-                                    WordCategory::Stack => egui::Color32::from_rgb(100, 150, 255), // Blue
-                                    WordCategory::Sensor => egui::Color32::from_rgb(200, 100, 255), // Purple
-                                    WordCategory::Arithmetic => {
-                                        egui::Color32::from_rgb(255, 220, 100)
-                                    } // Yellow
-                                    WordCategory::Control => egui::Color32::from_rgb(255, 150, 50), // Orange
-                                    WordCategory::Action => egui::Color32::from_rgb(100, 255, 100), // Green
-                                    WordCategory::Special => egui::Color32::from_rgb(150, 150, 150), // Gray
-                                };
-
-                                // Create the word text with stack effect
-                                let text =
-                                    format!("{:3}: {}  {}", index, word, word.stack_effect());
-
-                                // Draw with background highlight if current word
-                                if is_current {
-                                    let (rect, response) = ui.allocate_exact_size(
-                                        egui::vec2(ui.available_width(), 18.0),
-                                        egui::Sense::hover(),
-                                    );
+                            ui.monospace(&snapshot.source);
+                        });
+                });
 
-                                    // Draw highlight background
-                                    ui.painter().rect_filled(
-                                        rect,
-                                        egui::Rounding::same(2.0),
-                                        egui::Color32::from_rgba_unmultiplied(255, 255, 0, 80), // Yellow highlight
-                                    );
+                ui.separator();
 
-                                    // Draw text on top
-                                    ui.painter().text(
-                                        rect.left_center() + egui::vec2(5.0, 0.0),
-                                        egui::Align2::LEFT_CENTER,
-                                        &text,
-                                        egui::FontId::monospace(11.0),
-                                        text_color,
-                                    );
+                // Recent execution history, recorded only while enabled
+                egui::CollapsingHeader::new("Execution Trace").show(ui, |ui| {
+                    let mut tracing = executor.trace.is_some();
+                    ui.horizontal(|ui| {
+                        if ui.checkbox(&mut tracing, "Record trace").changed() {
+                            executor.set_tracing(tracing);
+                        }
+                        let Some(entity) = selected_entity.entity else {
+                            return;
+                        };
+                        match export {
+                            Some(export) => {
+                                if ui.button("⏹ Stop export").clicked() {
+                                    executor.set_exporting(false);
+                                    commands.entity(entity).remove::<TraceExport>();
+                                }
+                                ui.label(format!("{} words written", export.rows))
+                                    .on_hover_text(export.path.as_ref().map_or(
+                                        String::new(),
+                                        |path| path.display().to_string(),
+                                    ));
+                            }
+                            None => {
+                                if ui
+                                    .button("💾 Export to file")
+                                    .on_hover_text(
+                                        "Write every word this animal runs, with the tick \
+                                         and its sensors, to a CSV file until it dies",
+                                    )
+                                    .clicked()
+                                {
+                                    commands.entity(entity).insert(TraceExport::default());
+                                }
+                            }
+                        }
+                    });
+                    if let Some(trace) = &snapshot.trace {
+                        egui::ScrollArea::vertical()
+                            .id_salt("execution_trace")
+                            .max_height(150.0)
+                            .show(ui, |ui| {
+                                // Newest first
+                                for line in trace {
+                                    ui.monospace(line);
+                                }
+                            });
+                    }
+                });
 
-                                    response
-                                } else {
-                                    ui.add(egui::Label::new(
-                                        egui::RichText::new(text)
-                                            .color(text_color)
-                                            .font(egui::FontId::monospace(11.0)),
-                                    ))
-                                };
+                // Conditional pause on what a word leaves on the stack
+                egui::CollapsingHeader::new("Watchpoint").show(ui, |ui| {
+                    let Some(entity) = selected_entity.entity else {
+                        return;
+                    };
+                    let Some(watchpoint) = watchpoint else {
+                        ui.horizontal(|ui| {
+                            ui.label("Pause when");
+                            ui.text_edit_singleline(&mut forms.watch_expression)
+                                .on_hover_text(
+                                    "[word] <op> <number>: '> 500' checks the stack top \
+                                     after every word, 'energy < 5' only after energy",
+                                );
+                            if ui.button("👁 Watch").clicked() {
+                                match forms.watch_expression.parse::<WatchCondition>() {
+                                    Ok(condition) => {
+                                        forms.watch_error = None;
+                                        commands
+                                            .entity(entity)
+                                            .insert(Watchpoint::new(condition));
+                                    }
+                                    Err(err) => forms.watch_error = Some(err),
+                                }
                             }
                         });
+                        if let Some(error) = &forms.watch_error {
+                            ui.colored_label(egui::Color32::from_rgb(255, 100, 100), error);
+                        }
+                        return;
+                    };
+
+                    ui.horizontal(|ui| {
+                        ui.monospace(watchpoint.condition.to_string());
+                        ui.label(format!("{} hits", watchpoint.hits));
+                        if ui.button("✖ Clear").clicked() {
+                            executor.watch = None;
+                            commands.entity(entity).remove::<Watchpoint>();
+                        }
+                    });
+                    if let Some((tick, entry)) = watchpoint.last_hit {
+                        ui.label(format!(
+                            "Last hit at tick {}: {} at {} left {}",
+                            tick,
+                            entry.word,
+                            entry.instruction_pointer,
+                            entry
+                                .stack_top
+                                .map_or("nothing".to_string(), |top| top.to_string())
+                        ));
+                    }
                 });
-        }
+
+                ui.horizontal(|ui| {
+                    if let Some(entity) = selected_entity.entity
+                        && ui
+                            .button("💾 Save genome")
+                            .on_hover_text("Write this genome to this run's genome bank")
+                            .clicked()
+                    {
+                        commands.send_event(SaveGenomes {
+                            genomes: vec![(format!("animal-{}", entity), genome.clone())],
+                        });
+                    }
+                    if ui.button("🧬 Diff vs seed").clicked() {
+                        forms.seed_diff = selected_entity
+                            .entity
+                            .map(|entity| (entity, genome.diff(&Genome::seed())));
+                    }
+                    if let Some((entity, diff)) = forms.seed_diff
+                        && selected_entity.entity == Some(entity)
+                    {
+                        ui.label(format!(
+                            "{} edits: {} changed, {} inserted, {} deleted ({} of {} seed words kept)",
+                            diff.distance(),
+                            diff.substituted,
+                            diff.inserted,
+                            diff.deleted,
+                            diff.unchanged,
+                            Genome::seed().words.len()
+                        ));
+                    }
+                });
+
+                // Mutational neighbourhood: mutated copies that can be spawned by hand
+                egui::CollapsingHeader::new("Mutation Preview").show(ui, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.add(
+                            egui::DragValue::new(&mut forms.preview_count)
+                                .range(1..=50)
+                                .suffix(" copies"),
+                        );
+                        if ui.button("🎲 Preview mutation").clicked() {
+                            // Previews stay off the simulation's generator so looking
+                            // never changes a seeded run
+                            let mut rng = rand::thread_rng();
+                            let copies = (0..forms.preview_count)
+                                .map(|_| genome.mutate(&rules.mutation, &mut rng))
+                                .collect();
+                            forms.mutation_preview =
+                                selected_entity.entity.map(|entity| (entity, copies));
+                        }
+                    });
+
+                    let Some((entity, copies)) = &forms.mutation_preview else {
+                        return;
+                    };
+                    if selected_entity.entity != Some(*entity) {
+                        return;
+                    }
+                    egui::ScrollArea::vertical()
+                        .id_salt("mutation_preview")
+                        .max_height(250.0)
+                        .show(ui, |ui| {
+                            for (i, (copy, mutations)) in copies.iter().enumerate() {
+                                let diff = copy.diff(genome);
+                                ui.horizontal(|ui| {
+                                    ui.strong(format!("#{}", i + 1));
+                                    ui.label(format!(
+                                        "{} edits: {} changed, {} inserted, {} deleted",
+                                        diff.distance(),
+                                        diff.substituted,
+                                        diff.inserted,
+                                        diff.deleted
+                                    ));
+                                    if ui.button("➕ Spawn").clicked() {
+                                        settings.spawn_queue.push(
+                                            copy.clone(),
+                                            STARTING_ANIMAL_ENERGY,
+                                            transform.translation.truncate(),
+                                            0.0,
+                                        );
+                                    }
+                                });
+                                if mutations.is_empty() {
+                                    ui.colored_label(egui::Color32::GRAY, "  (identical words)");
+                                }
+                                for mutation in mutations {
+                                    ui.colored_label(
+                                        mutation_color(mutation),
+                                        egui::RichText::new(format!("  {}", mutation))
+                                            .monospace(),
+                                    );
+                                }
+                            }
+                        });
+                });
+
+                ui.separator();
+                ui.heading("Program");
+
+                // Words that can never execute are grayed out
+                let dead_code = genome.dead_code();
+                if !dead_code.is_empty() {
+                    ui.colored_label(
+                        egui::Color32::GRAY,
+                        format!("{} unreachable word(s)", dead_code.len()),
+                    );
+                }
+
+                // Scrollable area for words
+                egui::ScrollArea::vertical()
+                    .auto_shrink([false; 2])
+                    .show(ui, |ui| {
+                        for (index, word) in genome.words.iter().enumerate() {
+                            // Check if this is the currently executing word
+                            let is_current = index == executor.instruction_pointer;
+
+                            // Get word category for color
+                            let category = word.category();
+                            let text_color = match category {
+                                WordCategory::Stack => egui::Color32::from_rgb(100, 150, 255), // Blue
+                                WordCategory::Sensor => egui::Color32::from_rgb(200, 100, 255), // Purple
+                                WordCategory::Arithmetic => {
+                                    egui::Color32::from_rgb(255, 220, 100)
+                                } // Yellow
+                                WordCategory::Control => egui::Color32::from_rgb(255, 150, 50), // Orange
+                                WordCategory::Action => egui::Color32::from_rgb(100, 255, 100), // Green
+                                WordCategory::Special => egui::Color32::from_rgb(150, 150, 150), // Gray
+                            };
+                            let text_color = if dead_code.binary_search(&index).is_err() {
+                                text_color
+                            } else {
+                                egui::Color32::from_rgb(80, 80, 80) // Dark gray
+                            };
+
+                            // Create the word text with stack effect
+                            let text = match word {
+                                Word::PushConst(idx) => format!(
+                                    "{:3}: {} (={:.2})  {}",
+                                    index,
+                                    word,
+                                    genome.constant(*idx),
+                                    word.stack_effect()
+                                ),
+                                _ => format!("{:3}: {}  {}", index, word, word.stack_effect()),
+                            };
+
+                            // Draw with background highlight if current word
+                            if is_current {
+                                let (rect, response) = ui.allocate_exact_size(
+                                    egui::vec2(ui.available_width(), 18.0),
+                                    egui::Sense::hover(),
+                                );
+
+                                // Draw highlight background
+                                ui.painter().rect_filled(
+                                    rect,
+                                    egui::Rounding::same(2.0),
+                                    egui::Color32::from_rgba_unmultiplied(255, 255, 0, 80), // Yellow highlight
+                                );
+
+                                // Draw text on top
+                                ui.painter().text(
+                                    rect.left_center() + egui::vec2(5.0, 0.0),
+                                    egui::Align2::LEFT_CENTER,
+                                    &text,
+                                    egui::FontId::monospace(11.0),
+                                    text_color,
+                                );
+
+                                response
+                            } else {
+                                ui.add(egui::Label::new(
+                                    egui::RichText::new(text)
+                                        .color(text_color)
+                                        .font(egui::FontId::monospace(11.0)),
+                                ))
+                            };
+                        }
+                    });
+            },
+        );
+        forms.viewer = Some((entity, snapshot));
     }
 }
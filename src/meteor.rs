@@ -0,0 +1,163 @@
+use crate::animal::{Animal, AnimalDied, DeathCause};
+use crate::charts::PopulationHistory;
+use crate::config::*;
+use crate::energy_flow::{EnergyFlows, Flow};
+use crate::plant::Plant;
+use crate::sim_rng::SimRng;
+use bevy::prelude::*;
+use rand::Rng;
+
+/// Settings for meteor catastrophes
+#[derive(Resource)]
+pub struct MeteorSettings {
+    pub random_enabled: bool,
+    pub radius: f32,
+}
+
+impl Default for MeteorSettings {
+    fn default() -> Self {
+        Self {
+            random_enabled: false,
+            radius: METEOR_RADIUS,
+        }
+    }
+}
+
+/// Event requesting a meteor strike centered on a world position
+#[derive(Event, Debug, Clone, Copy)]
+pub struct MeteorStrike {
+    pub center: Vec2,
+    pub radius: f32,
+}
+
+impl MeteorStrike {
+    /// A strike at a random position inside the world bounds
+    pub fn random(radius: f32, rng: &mut impl Rng) -> Self {
+        Self {
+            center: Vec2::new(
+                rng.gen_range(-WORLD_BOUNDS..WORLD_BOUNDS),
+                rng.gen_range(-WORLD_BOUNDS..WORLD_BOUNDS),
+            ),
+            radius,
+        }
+    }
+}
+
+/// Scorched area left behind by a meteor where nothing spawns until it recovers
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct ScorchedZone {
+    pub radius: f32,
+    pub timer: Timer,
+}
+
+/// Check whether a position lies inside any scorched zone
+pub fn is_scorched<'a>(
+    position: Vec2,
+    zones: impl IntoIterator<Item = (&'a ScorchedZone, &'a Transform)>,
+) -> bool {
+    zones
+        .into_iter()
+        .any(|(zone, transform)| position.distance(transform.translation.truncate()) <= zone.radius)
+}
+
+/// `position` moved just past the edge of any scorched zone covering it, given zones as
+/// (center, radius). None if moving out of one zone keeps landing in another
+pub fn escape_scorched(mut position: Vec2, zones: &[(Vec2, f32)]) -> Option<Vec2> {
+    for _ in 0..=zones.len() {
+        let Some(&(center, radius)) = zones
+            .iter()
+            .find(|(center, radius)| position.distance(*center) <= *radius)
+        else {
+            return Some(position);
+        };
+        let away = (position - center).try_normalize().unwrap_or(Vec2::X);
+        position = center + away * (radius + SCORCH_ESCAPE_MARGIN);
+    }
+    None
+}
+
+/// System to trigger meteors at random when enabled
+pub fn random_meteors(
+    time: Res<Time>,
+    settings: Res<MeteorSettings>,
+    mut rng: ResMut<SimRng>,
+    mut strikes: EventWriter<MeteorStrike>,
+) {
+    if !settings.random_enabled {
+        return;
+    }
+
+    // Poisson process with a mean of one strike per METEOR_RANDOM_INTERVAL seconds
+    let chance = (time.delta_secs() / METEOR_RANDOM_INTERVAL) as f64;
+    if rng.gen_bool(chance.clamp(0.0, 1.0)) {
+        strikes.send(MeteorStrike::random(settings.radius, &mut *rng));
+    }
+}
+
+/// System to resolve meteor strikes: wipe everything in range and scorch the ground
+#[allow(clippy::too_many_arguments)]
+pub fn apply_meteor_strikes(
+    mut commands: Commands,
+    mut strikes: EventReader<MeteorStrike>,
+    mut deaths: EventWriter<AnimalDied>,
+    mut history: ResMut<PopulationHistory>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut flows: ResMut<EnergyFlows>,
+    animals: Query<(Entity, &Animal, &Transform)>,
+    plants: Query<(Entity, &Plant, &Transform)>,
+) {
+    for strike in strikes.read() {
+        for (entity, animal, transform) in animals.iter() {
+            if transform.translation.truncate().distance(strike.center) <= strike.radius {
+                flows.record(Flow::Death, animal.energy);
+                deaths.send(AnimalDied {
+                    entity,
+                    cause: DeathCause::Meteor,
+                    position: transform.translation.truncate(),
+                });
+                commands.entity(entity).despawn();
+            }
+        }
+
+        for (entity, plant, transform) in plants.iter() {
+            if transform.translation.truncate().distance(strike.center) <= strike.radius {
+                flows.record(Flow::Death, plant.energy);
+                commands.entity(entity).despawn();
+            }
+        }
+
+        commands.spawn((
+            ScorchedZone {
+                radius: strike.radius,
+                timer: Timer::from_seconds(SCORCH_DURATION, TimerMode::Once),
+            },
+            Mesh2d(meshes.add(Circle::new(strike.radius))),
+            MeshMaterial2d(materials.add(ColorMaterial::from_color(Color::srgba(
+                0.25, 0.12, 0.05, 0.6,
+            )))),
+            Transform::from_xyz(strike.center.x, strike.center.y, -0.5),
+        ));
+
+        history.add_marker("meteor");
+    }
+}
+
+/// System to fade scorched zones and remove them once they have recovered
+pub fn recover_scorched_zones(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut zones: Query<(Entity, &mut ScorchedZone, &MeshMaterial2d<ColorMaterial>)>,
+) {
+    for (entity, mut zone, material) in zones.iter_mut() {
+        if zone.timer.tick(time.delta()).finished() {
+            commands.entity(entity).despawn();
+        } else if let Some(material) = materials.get_mut(&material.0) {
+            material
+                .color
+                .set_alpha(0.6 * zone.timer.fraction_remaining());
+        }
+    }
+}
@@ -0,0 +1,114 @@
+use crate::animal::{Animal, RespawnSource};
+use crate::charts::PopulationHistory;
+use crate::config::*;
+use crate::{RunName, SimulationTick};
+use bevy::prelude::*;
+use bevy::render::view::screenshot::{Screenshot, save_to_disk};
+use std::path::PathBuf;
+
+/// Notable moments in a run that are worth recording
+#[derive(Event, Debug, Clone, Copy)]
+pub enum Milestone {
+    /// The oldest living animal has beaten the previous lifespan record
+    LifespanRecord { age: f32 },
+    /// The last animal has died
+    Extinction,
+    /// The population failsafe respawned animals
+    Failsafe {
+        population: usize,
+        source: RespawnSource,
+    },
+}
+
+impl Milestone {
+    /// Short name used in chart markers and screenshot file names
+    pub fn slug(&self) -> &'static str {
+        match self {
+            Milestone::LifespanRecord { .. } => "lifespan-record",
+            Milestone::Extinction => "extinction",
+            Milestone::Failsafe { .. } => "failsafe",
+        }
+    }
+}
+
+impl std::fmt::Display for Milestone {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Milestone::LifespanRecord { age } => write!(f, "new lifespan record ({:.1}s)", age),
+            Milestone::Extinction => write!(f, "extinction"),
+            Milestone::Failsafe { population, source } => write!(
+                f,
+                "failsafe respawn from {} at population {}",
+                source, population
+            ),
+        }
+    }
+}
+
+/// Settings and bookkeeping for milestone detection
+#[derive(Resource)]
+pub struct MilestoneTracker {
+    pub auto_screenshot: bool,
+    pub screenshot_dir: PathBuf,
+    pub lifespan_record: f32,
+    pub had_animals: bool,
+}
+
+impl Default for MilestoneTracker {
+    fn default() -> Self {
+        Self {
+            auto_screenshot: false,
+            screenshot_dir: PathBuf::from("screenshots"),
+            lifespan_record: 0.0,
+            had_animals: false,
+        }
+    }
+}
+
+/// System to detect milestone events from the current population
+pub fn detect_milestones(
+    mut tracker: ResMut<MilestoneTracker>,
+    mut milestones: EventWriter<Milestone>,
+    animals: Query<&Animal>,
+) {
+    let has_animals = !animals.is_empty();
+    if tracker.had_animals && !has_animals {
+        milestones.send(Milestone::Extinction);
+    }
+    tracker.had_animals = has_animals;
+
+    // Only report records in coarse steps so a single long-lived animal doesn't
+    // fire an event every frame
+    let oldest = animals.iter().map(|a| a.age).fold(0.0, f32::max);
+    if oldest >= tracker.lifespan_record + LIFESPAN_RECORD_STEP {
+        tracker.lifespan_record = oldest;
+        milestones.send(Milestone::LifespanRecord { age: oldest });
+    }
+}
+
+/// System to log milestones, mark them on the charts and optionally take a screenshot
+pub fn record_milestones(
+    mut commands: Commands,
+    tick: Res<SimulationTick>,
+    run: Res<RunName>,
+    tracker: Res<MilestoneTracker>,
+    mut history: ResMut<PopulationHistory>,
+    mut milestones: EventReader<Milestone>,
+) {
+    for milestone in milestones.read() {
+        info!("Milestone at tick {}: {}", tick.0, milestone);
+        history.add_marker(milestone.slug());
+
+        if tracker.auto_screenshot {
+            let dir = run.output_dir().join(&tracker.screenshot_dir);
+            if let Err(err) = std::fs::create_dir_all(&dir) {
+                error!("Cannot create screenshot directory: {err}");
+                continue;
+            }
+            let path = dir.join(format!("tick-{:08}-{}.png", tick.0, milestone.slug()));
+            commands
+                .spawn(Screenshot::primary_window())
+                .observe(save_to_disk(path));
+        }
+    }
+}
@@ -1,5 +1,9 @@
+use crate::animal::{Animal, Phenotype};
+use crate::config::*;
+use crate::genome::Sensors;
 use crate::selection::Selected;
 use bevy::prelude::*;
+use std::collections::HashSet;
 
 /// Component that marks an outline entity linked to a selected entity
 #[derive(Component)]
@@ -7,6 +11,82 @@ pub struct SelectionOutline {
     pub parent: Entity,
 }
 
+/// Component that marks a ring showing one interaction range of the selected animal
+#[derive(Component)]
+pub struct InteractionRing {
+    pub parent: Entity,
+}
+
+/// Resource toggling the interaction range rings around the selected animal
+#[derive(Resource, Default)]
+pub struct InteractionRings {
+    pub visible: bool,
+}
+
+/// Distances at which an animal can eat, attack, find a mate, count as crowding and
+/// see, with the color of each ring
+fn interaction_ranges(phenotype: &Phenotype) -> [(f32, Color); 5] {
+    [
+        (phenotype.eat_distance(), Color::srgba(0.3, 1.0, 0.3, 0.8)),
+        (ATTACK_DISTANCE, Color::srgba(1.0, 0.3, 0.2, 0.8)),
+        (MATE_DISTANCE, Color::srgba(1.0, 0.5, 0.9, 0.7)),
+        (CROWDING_RADIUS, Color::srgba(1.0, 0.7, 0.2, 0.5)),
+        (VISION_RANGE, Color::srgba(0.4, 0.7, 1.0, 0.4)),
+    ]
+}
+
+/// Legend for the ring colors
+pub const INTERACTION_RING_LEGEND: &str = "Rings around the selected animal: green eat, red attack, pink mate, orange crowding, \
+     blue vision range";
+
+/// System to ring the selected animal with its interaction ranges while enabled, keeping
+/// the rings on it as it moves
+#[allow(clippy::type_complexity)]
+pub fn manage_interaction_rings(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    settings: Res<InteractionRings>,
+    selected: Query<(Entity, &Transform, &Phenotype), (With<Selected>, With<Animal>)>,
+    mut rings: Query<(Entity, &InteractionRing, &mut Transform), Without<Selected>>,
+) {
+    let target = selected.get_single().ok().filter(|_| settings.visible);
+
+    let mut ringed = false;
+    for (ring_entity, ring, mut ring_transform) in rings.iter_mut() {
+        match target {
+            Some((entity, transform, _)) if ring.parent == entity => {
+                ringed = true;
+                ring_transform.translation.x = transform.translation.x;
+                ring_transform.translation.y = transform.translation.y;
+            }
+            _ => commands.entity(ring_entity).despawn(),
+        }
+    }
+
+    if let Some((entity, transform, phenotype)) = target
+        && !ringed
+    {
+        for (radius, color) in interaction_ranges(phenotype) {
+            commands.spawn((
+                InteractionRing { parent: entity },
+                Mesh2d(meshes.add(Annulus::new(
+                    radius - INTERACTION_RING_WIDTH / 2.0,
+                    radius + INTERACTION_RING_WIDTH / 2.0,
+                ))),
+                MeshMaterial2d(materials.add(ColorMaterial::from_color(color))),
+                Transform::from_xyz(transform.translation.x, transform.translation.y, 0.05),
+            ));
+        }
+    }
+}
+
+/// Component that marks a pulsing warning ring around a starving animal
+#[derive(Component)]
+pub struct StarvationOutline {
+    pub parent: Entity,
+}
+
 /// System to add/remove outlines for selected entities
 pub fn manage_selection_outlines(
     mut commands: Commands,
@@ -56,3 +136,54 @@ pub fn update_outline_positions(
         }
     }
 }
+
+/// System to ring starving animals and drop the ring once they recover or die
+pub fn manage_starvation_outlines(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    animals: Query<(Entity, &Sensors, &Transform), With<Animal>>,
+    outlines: Query<(Entity, &StarvationOutline)>,
+) {
+    let mut outlined = HashSet::new();
+    for (outline_entity, outline) in outlines.iter() {
+        let starving = animals
+            .get(outline.parent)
+            .is_ok_and(|(_, sensors, _)| sensors.starving);
+        if starving {
+            outlined.insert(outline.parent);
+        } else {
+            commands.entity(outline_entity).despawn();
+        }
+    }
+
+    for (entity, sensors, transform) in animals.iter() {
+        if sensors.starving && !outlined.contains(&entity) {
+            commands.spawn((
+                StarvationOutline { parent: entity },
+                Mesh2d(meshes.add(Annulus::new(11.0, 13.0))),
+                MeshMaterial2d(materials.add(ColorMaterial::from_color(
+                    Color::srgba(1.0, 0.1, 0.1, 0.8), // Red warning ring
+                ))),
+                Transform::from_xyz(transform.translation.x, transform.translation.y, 0.1),
+            ));
+        }
+    }
+}
+
+/// System to keep starvation rings on their animals and pulse them (in real time, so the
+/// warning stays visible while paused)
+pub fn update_starvation_outlines(
+    time: Res<Time<Real>>,
+    animals: Query<&Transform, (With<Animal>, Without<StarvationOutline>)>,
+    mut outlines: Query<(&StarvationOutline, &mut Transform)>,
+) {
+    let pulse = 1.0 + 0.15 * (time.elapsed_secs() * 6.0).sin();
+    for (outline, mut outline_transform) in outlines.iter_mut() {
+        if let Ok(parent_transform) = animals.get(outline.parent) {
+            outline_transform.translation.x = parent_transform.translation.x;
+            outline_transform.translation.y = parent_transform.translation.y;
+            outline_transform.scale = parent_transform.scale * pulse;
+        }
+    }
+}
@@ -0,0 +1,237 @@
+use crate::config::*;
+use bevy::prelude::*;
+
+/// Resource holding a chemical field over the world. Animals add to it with the
+/// `deposit-pheromone` word and read it with `pheromone`; over time it spreads into
+/// neighbouring cells and evaporates, so repeated deposits wear in trails and mark
+/// territory.
+///
+/// Large grids can be diffused less often than every tick: each pass then covers the
+/// ticks since the last one, and readings move from the field before the pass to the
+/// field after it over the following ticks
+#[derive(Resource)]
+pub struct PheromoneGrid {
+    pub enabled: bool,
+    /// Fraction of each cell's pheromone shared out to its four neighbours per tick
+    pub diffusion: f32,
+    /// Fraction of all pheromone that evaporates per tick
+    pub decay: f32,
+    /// Ticks between diffusion passes
+    pub update_interval: u32,
+    /// Ticks since the last pass
+    elapsed: u32,
+    /// Side length of a cell; change it with `set_cell_size`
+    cell_size: f32,
+    /// World coordinate of the grid's lower-left corner on both axes
+    origin: f32,
+    /// Cells per side
+    width: usize,
+    values: Vec<f32>,
+    /// Values before the last pass, blended into readings until the next one
+    previous: Vec<f32>,
+    /// Buffer the next pass's values are diffused into
+    scratch: Vec<f32>,
+}
+
+impl Default for PheromoneGrid {
+    fn default() -> Self {
+        Self::new(WORLD_BOUNDS, PHEROMONE_CELL_SIZE)
+    }
+}
+
+impl PheromoneGrid {
+    /// An empty grid covering ±`bounds` on both axes
+    pub fn new(bounds: f32, cell_size: f32) -> Self {
+        let width = ((bounds * 2.0 / cell_size).ceil() as usize).max(1);
+        Self {
+            enabled: true,
+            diffusion: PHEROMONE_DIFFUSION,
+            decay: PHEROMONE_DECAY,
+            update_interval: PHEROMONE_UPDATE_INTERVAL,
+            elapsed: 0,
+            cell_size,
+            origin: -bounds,
+            width,
+            values: vec![0.0; width * width],
+            previous: vec![0.0; width * width],
+            scratch: vec![0.0; width * width],
+        }
+    }
+
+    pub fn cell_size(&self) -> f32 {
+        self.cell_size
+    }
+
+    /// Change the grid's resolution, keeping the settings and resampling what it holds
+    /// at the centre of each new cell
+    pub fn set_cell_size(&mut self, cell_size: f32) {
+        let mut resized = Self::new(-self.origin, cell_size);
+        resized.enabled = self.enabled;
+        resized.diffusion = self.diffusion;
+        resized.decay = self.decay;
+        resized.update_interval = self.update_interval;
+        for index in 0..resized.values.len() {
+            let (x, y) = (index % resized.width, index / resized.width);
+            let centre = Vec2::new(x as f32 + 0.5, y as f32 + 0.5) * cell_size + resized.origin;
+            resized.values[index] = self.sample(centre);
+        }
+        resized.previous.copy_from_slice(&resized.values);
+        *self = resized;
+    }
+
+    /// Index of the cell containing `position`, or None outside the grid
+    fn cell(&self, position: Vec2) -> Option<usize> {
+        let x = ((position.x - self.origin) / self.cell_size).floor();
+        let y = ((position.y - self.origin) / self.cell_size).floor();
+        let inside = |v: f32| v >= 0.0 && v < self.width as f32;
+        (inside(x) && inside(y)).then(|| y as usize * self.width + x as usize)
+    }
+
+    /// Pheromone at `position`; 0 outside the grid
+    pub fn sample(&self, position: Vec2) -> f32 {
+        let Some(cell) = self.cell(position) else {
+            return 0.0;
+        };
+        // Reaches the latest pass's values on the tick before the next pass
+        let blend = (self.elapsed + 1) as f32 / self.update_interval.max(1) as f32;
+        self.previous[cell] + (self.values[cell] - self.previous[cell]) * blend.min(1.0)
+    }
+
+    /// Add `amount` to the cell at `position`, up to the per-cell cap
+    pub fn deposit(&mut self, position: Vec2, amount: f32) {
+        if let Some(cell) = self.cell(position) {
+            // Both ends of the blend, so the deposit is sensed at once
+            for values in [&mut self.values, &mut self.previous] {
+                values[cell] = (values[cell] + amount).min(PHEROMONE_MAX);
+            }
+        }
+    }
+
+    /// Pheromone summed over the whole grid
+    pub fn total(&self) -> f32 {
+        self.values.iter().sum()
+    }
+
+    pub fn clear(&mut self) {
+        self.values.fill(0.0);
+        self.previous.fill(0.0);
+    }
+
+    /// Advance one tick, diffusing once `update_interval` ticks have passed
+    pub fn step(&mut self) {
+        self.elapsed += 1;
+        if self.elapsed < self.update_interval.max(1) {
+            return;
+        }
+        let ticks = std::mem::take(&mut self.elapsed);
+        self.previous.copy_from_slice(&self.values);
+        self.diffuse(ticks);
+    }
+
+    /// Spread and evaporate in one pass as much as `ticks` ticks would. Edge cells treat
+    /// missing neighbours as themselves, so diffusion alone never loses pheromone off
+    /// the map
+    fn diffuse(&mut self, ticks: u32) {
+        let width = self.width;
+        let ticks = ticks as i32;
+        let diffusion = 1.0 - (1.0 - self.diffusion.clamp(0.0, 1.0)).powi(ticks);
+        let keep = (1.0 - self.decay.clamp(0.0, 1.0)).powi(ticks);
+        let values = &self.values;
+        for (index, next) in self.scratch.iter_mut().enumerate() {
+            let (x, y) = (index % width, index / width);
+            let own = values[index];
+            let neighbour = |nx: Option<usize>, ny: Option<usize>| match (nx, ny) {
+                (Some(nx), Some(ny)) if nx < width && ny < width => values[ny * width + nx],
+                _ => own,
+            };
+            let around = neighbour(x.checked_sub(1), Some(y))
+                + neighbour(Some(x + 1), Some(y))
+                + neighbour(Some(x), y.checked_sub(1))
+                + neighbour(Some(x), Some(y + 1));
+            *next = (own * (1.0 - diffusion) + around * diffusion / 4.0) * keep;
+        }
+        std::mem::swap(&mut self.values, &mut self.scratch);
+    }
+}
+
+/// System to advance the pheromone grid by one tick
+pub fn update_pheromones(mut grid: ResMut<PheromoneGrid>) {
+    if grid.enabled {
+        grid.step();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deposits_spread_and_evaporate() {
+        let mut grid = PheromoneGrid::new(20.0, 10.0);
+        grid.decay = 0.0;
+        grid.diffusion = 0.4;
+        let corner = Vec2::new(-15.0, -15.0);
+        grid.deposit(corner, 8.0);
+        assert_eq!(grid.sample(corner), 8.0);
+        assert_eq!(grid.sample(Vec2::new(25.0, 0.0)), 0.0);
+
+        // Diffusion moves pheromone to neighbours without losing any at the edges
+        grid.step();
+        assert!(grid.sample(corner) < 8.0);
+        assert!(grid.sample(Vec2::new(-5.0, -15.0)) > 0.0);
+        assert_eq!(grid.sample(Vec2::new(15.0, 15.0)), 0.0);
+        assert!((grid.total() - 8.0).abs() < 1e-4);
+
+        grid.decay = 0.5;
+        grid.step();
+        assert!((grid.total() - 4.0).abs() < 1e-4);
+
+        grid.deposit(corner, PHEROMONE_MAX * 2.0);
+        assert_eq!(grid.sample(corner), PHEROMONE_MAX);
+    }
+
+    #[test]
+    fn resizing_keeps_the_readings() {
+        let mut grid = PheromoneGrid::new(20.0, 10.0);
+        grid.decay = 0.3;
+        let spot = Vec2::new(5.0, -15.0);
+        grid.deposit(spot, 6.0);
+
+        grid.set_cell_size(5.0);
+        assert_eq!(grid.cell_size(), 5.0);
+        assert_eq!(grid.decay, 0.3);
+        assert_eq!(grid.sample(spot), 6.0);
+        assert_eq!(grid.sample(Vec2::new(9.0, -11.0)), 6.0);
+        assert_eq!(grid.sample(Vec2::new(11.0, -15.0)), 0.0);
+        assert_eq!(grid.total(), 24.0);
+    }
+
+    #[test]
+    fn slower_updates_cover_the_skipped_ticks_and_blend_readings() {
+        let mut grid = PheromoneGrid::new(20.0, 10.0);
+        grid.update_interval = 4;
+        grid.diffusion = 0.2;
+        grid.decay = 0.1;
+        let corner = Vec2::new(-15.0, -15.0);
+        grid.deposit(corner, 8.0);
+
+        // Nothing moves until the interval has passed
+        for _ in 0..3 {
+            grid.step();
+            assert_eq!(grid.sample(corner), 8.0);
+        }
+
+        // One pass evaporates as much as four ticks would
+        grid.step();
+        assert!((grid.total() - 8.0 * 0.9f32.powi(4)).abs() < 1e-4);
+
+        // Readings move a quarter of the way per tick towards the new field
+        let before = 8.0;
+        let after = grid.values[grid.cell(corner).unwrap()];
+        assert!((grid.sample(corner) - (before + (after - before) * 0.25)).abs() < 1e-4);
+        grid.step();
+        grid.step();
+        grid.step();
+        assert!((grid.sample(corner) - after).abs() < 1e-4);
+    }
+}
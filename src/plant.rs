@@ -1,15 +1,26 @@
+use crate::animal::{Animal, PlantDepleted, PlantEaten};
 use crate::config::*;
+use crate::energy_flow::{EnergyFlows, Flow};
+use crate::meteor::{ScorchedZone, is_scorched};
+use crate::sim_rng::SimRng;
+use crate::spawning::SpawnBudget;
 use bevy::prelude::*;
 use rand::Rng;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fmt;
+use std::time::Duration;
 
 /// Plant component that stores energy
-#[derive(Component)]
+#[derive(Component, Reflect)]
+#[reflect(Component)]
 pub struct Plant {
     pub energy: u32,
 }
 
 /// Marker component indicating this entity emits plant scent
-#[derive(Component)]
+#[derive(Component, Reflect)]
+#[reflect(Component)]
 pub struct PlantScent;
 
 impl Plant {
@@ -28,20 +39,73 @@ impl Plant {
     }
 }
 
+/// Where spawned plants are placed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+pub enum PlantPlacement {
+    /// Uniformly at random; plants clump by chance
+    #[default]
+    Uniform,
+    /// Blue noise: the best of several random candidates, the one farthest from every
+    /// existing plant, so food fills gaps instead of clumping
+    Even,
+}
+
+impl PlantPlacement {
+    pub const ALL: [PlantPlacement; 2] = [PlantPlacement::Uniform, PlantPlacement::Even];
+}
+
+impl fmt::Display for PlantPlacement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PlantPlacement::Uniform => write!(f, "uniform"),
+            PlantPlacement::Even => write!(f, "even"),
+        }
+    }
+}
+
+impl std::str::FromStr for PlantPlacement {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|placement| placement.to_string() == s)
+            .ok_or_else(|| format!("unknown plant placement '{}'", s))
+    }
+}
+
 /// Configuration resource for plant spawning and growth
 #[derive(Resource)]
 pub struct PlantConfig {
     pub world_bounds: f32,
+    pub placement: PlantPlacement,
 }
 
 impl Default for PlantConfig {
     fn default() -> Self {
         Self {
             world_bounds: WORLD_BOUNDS,
+            placement: PlantPlacement::default(),
         }
     }
 }
 
+/// Of `candidates`, the one whose nearest plant in `plants` is farthest away
+/// (Mitchell's best-candidate sampling)
+fn best_candidate(candidates: impl IntoIterator<Item = Vec2>, plants: &[Vec2]) -> Option<Vec2> {
+    candidates
+        .into_iter()
+        .map(|candidate| {
+            let gap = plants
+                .iter()
+                .map(|plant| plant.distance_squared(candidate))
+                .fold(f32::INFINITY, f32::min);
+            (candidate, gap)
+        })
+        .max_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(candidate, _)| candidate)
+}
+
 /// Timer resource for plant spawning
 #[derive(Resource)]
 pub struct PlantSpawnTimer(pub Timer);
@@ -50,7 +114,178 @@ pub struct PlantSpawnTimer(pub Timer);
 #[derive(Resource)]
 pub struct PlantGrowthTimer(pub Timer);
 
+/// Homeostat that nudges the plant spawn interval to keep the plant:animal ratio in a band
+#[derive(Resource)]
+pub struct PlantGovernor {
+    pub enabled: bool,
+    pub target_min: f32,
+    pub target_max: f32,
+    pub timer: Timer,
+}
+
+impl Default for PlantGovernor {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            target_min: GOVERNOR_TARGET_RATIO_MIN,
+            target_max: GOVERNOR_TARGET_RATIO_MAX,
+            timer: Timer::from_seconds(GOVERNOR_INTERVAL, TimerMode::Repeating),
+        }
+    }
+}
+
+/// Settings for seeds carried by animals from the plants they eat (endozoochory)
+#[derive(Resource)]
+pub struct SeedDispersal {
+    pub enabled: bool,
+    /// Chance per successful Eat that a seed is swallowed
+    pub probability: f32,
+    /// Ticks before a swallowed seed is dropped where the animal then is
+    pub delay_ticks: u32,
+}
+
+impl Default for SeedDispersal {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            probability: SEED_DISPERSAL_PROBABILITY,
+            delay_ticks: SEED_DISPERSAL_DELAY_TICKS,
+        }
+    }
+}
+
+/// Seeds an animal has swallowed, as ticks left until each is dropped
+#[derive(Component, Reflect, Default)]
+#[reflect(Component)]
+pub struct GutSeeds(pub Vec<u32>);
+
+/// Settings for plants regrowing where they were eaten to nothing, so good sites stay
+/// good and foragers can profit from returning to them
+#[derive(Resource)]
+pub struct PlantRegrowth {
+    pub enabled: bool,
+    /// Chance a depleted plant leaves a regrow marker; the rest of the plant supply
+    /// still comes from random global spawns
+    pub share: f32,
+    /// Ticks before a marker sprouts
+    pub delay_ticks: u32,
+}
+
+impl Default for PlantRegrowth {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            share: PLANT_REGROWTH_SHARE,
+            delay_ticks: PLANT_REGROWTH_DELAY_TICKS,
+        }
+    }
+}
+
+/// Settings for light competition: mature plants nearby slow a plant's growth, so dense
+/// patches stall while plants spreading into open ground thrive
+#[derive(Resource)]
+pub struct PlantShading {
+    pub enabled: bool,
+    pub radius: f32,
+    pub strength: f32,
+}
+
+impl Default for PlantShading {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            radius: PLANT_SHADING_RADIUS,
+            strength: PLANT_SHADING_STRENGTH,
+        }
+    }
+}
+
+impl PlantShading {
+    /// Chance a plant grows this growth tick with `neighbours` mature plants nearby
+    pub fn growth_chance(&self, neighbours: u32) -> f32 {
+        1.0 / (1.0 + self.strength.max(0.0) * neighbours as f32)
+    }
+}
+
+/// For each plant, the number of other mature plants within `radius`. Mature plants are
+/// bucketed into cells one radius wide, so each count only looks at the 3×3 cells around
+/// the plant
+fn shading_neighbours(plants: &[(Vec2, bool)], radius: f32) -> Vec<u32> {
+    let radius = radius.max(f32::EPSILON);
+    let cell = |position: Vec2| (position / radius).floor().as_ivec2();
+    let mut grid: HashMap<IVec2, Vec<Vec2>> = HashMap::new();
+    for &(position, mature) in plants {
+        if mature {
+            grid.entry(cell(position)).or_default().push(position);
+        }
+    }
+
+    plants
+        .iter()
+        .map(|&(position, mature)| {
+            let center = cell(position);
+            let within = (-1..=1)
+                .flat_map(|x| (-1..=1).map(move |y| center + IVec2::new(x, y)))
+                .filter_map(|neighbour| grid.get(&neighbour))
+                .flatten()
+                .filter(|other| other.distance(position) <= radius)
+                .count();
+            // A mature plant finds itself in the grid
+            within as u32 - mature as u32
+        })
+        .collect()
+}
+
+/// Invisible marker where a plant was eaten to nothing, counting down the ticks until
+/// a sprout grows there
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct RegrowMarker {
+    pub ticks_left: u32,
+}
+
+/// System to adjust the plant spawn interval when the plant:animal ratio leaves the target band
+pub fn govern_plant_spawn_rate(
+    time: Res<Time>,
+    mut governor: ResMut<PlantGovernor>,
+    mut spawn_timer: ResMut<PlantSpawnTimer>,
+    plants: Query<(), With<Plant>>,
+    animals: Query<(), With<Animal>>,
+) {
+    if !governor.enabled || !governor.timer.tick(time.delta()).just_finished() {
+        return;
+    }
+
+    // Nothing to balance against while the population is extinct
+    let animal_count = animals.iter().count();
+    if animal_count == 0 {
+        return;
+    }
+    let ratio = plants.iter().count() as f32 / animal_count as f32;
+
+    let current = spawn_timer.0.duration().as_secs_f32();
+    let adjusted = if ratio < governor.target_min {
+        current * (1.0 - GOVERNOR_STEP)
+    } else if ratio > governor.target_max {
+        current * (1.0 + GOVERNOR_STEP)
+    } else {
+        return;
+    }
+    .clamp(GOVERNOR_MIN_SPAWN_INTERVAL, GOVERNOR_MAX_SPAWN_INTERVAL);
+
+    if adjusted != current {
+        info!(
+            "Plant governor: ratio {:.2} outside {:.1}-{:.1}, spawn interval {:.2}s -> {:.2}s",
+            ratio, governor.target_min, governor.target_max, current, adjusted
+        );
+        spawn_timer
+            .0
+            .set_duration(Duration::from_secs_f32(adjusted));
+    }
+}
+
 /// System to spawn new plants at regular intervals
+#[allow(clippy::too_many_arguments)]
 pub fn spawn_plants(
     time: Res<Time>,
     mut timer: ResMut<PlantSpawnTimer>,
@@ -58,35 +293,214 @@ pub fn spawn_plants(
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<ColorMaterial>>,
+    mut budget: ResMut<SpawnBudget>,
+    mut rng: ResMut<SimRng>,
+    scorched_zones: Query<(&ScorchedZone, &Transform)>,
+    plants: Query<&Transform, With<Plant>>,
 ) {
     if timer.0.tick(time.delta()).just_finished() {
-        let mut rng = rand::thread_rng();
+        let bounds = config.world_bounds;
+        let mut random_position = || {
+            Vec2::new(
+                rng.gen_range(-bounds..bounds),
+                rng.gen_range(-bounds..bounds),
+            )
+        };
 
-        // Random position within world bounds
-        let x = rng.gen_range(-config.world_bounds..config.world_bounds);
-        let y = rng.gen_range(-config.world_bounds..config.world_bounds);
+        // Random position within world bounds, avoiding scorched ground
+        let position = match config.placement {
+            PlantPlacement::Uniform => random_position(),
+            PlantPlacement::Even => {
+                let plants: Vec<Vec2> = plants
+                    .iter()
+                    .map(|transform| transform.translation.truncate())
+                    .collect();
+                let candidates = (0..PLANT_PLACEMENT_CANDIDATES).map(|_| random_position());
+                best_candidate(candidates, &plants).unwrap_or_default()
+            }
+        };
+        if is_scorched(position, scorched_zones.iter()) || !budget.try_take() {
+            return;
+        }
 
-        // Spawn plant entity
-        commands.spawn((
-            Plant::new(),
-            PlantScent,
-            Mesh2d(meshes.add(Circle::new(8.0))),
-            MeshMaterial2d(materials.add(ColorMaterial::from_color(Color::srgb(0.2, 0.8, 0.2)))),
-            Transform::from_xyz(x, y, 0.0),
-        ));
+        spawn_plant(&mut commands, &mut meshes, &mut materials, position, 0);
     }
 }
 
-/// System to grow existing plants (increment energy)
+/// Spawn a plant entity holding `energy` at the given position
+pub fn spawn_plant(
+    commands: &mut Commands,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<ColorMaterial>,
+    position: Vec2,
+    energy: u32,
+) {
+    let mut plant = Plant::new();
+    plant.add_energy(energy);
+    commands.spawn((
+        plant,
+        PlantScent,
+        Mesh2d(meshes.add(Circle::new(PLANT_RADIUS))),
+        MeshMaterial2d(materials.add(ColorMaterial::from_color(Color::srgb(0.2, 0.8, 0.2)))),
+        Transform::from_xyz(position.x, position.y, 0.0),
+    ));
+}
+
+/// System to roll for a swallowed seed each time an animal eats
+pub fn ingest_seeds(
+    mut commands: Commands,
+    settings: Res<SeedDispersal>,
+    mut rng: ResMut<SimRng>,
+    mut plants_eaten: EventReader<PlantEaten>,
+    mut guts: Query<Option<&mut GutSeeds>, With<Animal>>,
+) {
+    if !settings.enabled {
+        plants_eaten.clear();
+        return;
+    }
+
+    for &PlantEaten { animal } in plants_eaten.read() {
+        if !rng.gen_bool(settings.probability.clamp(0.0, 1.0) as f64) {
+            continue;
+        }
+        match guts.get_mut(animal) {
+            Ok(Some(mut gut)) => gut.0.push(settings.delay_ticks),
+            // First seed: the component only lands once commands are applied
+            Ok(None) => {
+                commands
+                    .entity(animal)
+                    .try_insert(GutSeeds(vec![settings.delay_ticks]));
+            }
+            Err(_) => {}
+        }
+    }
+}
+
+/// System to count down swallowed seeds and drop each as a seedling where its animal is
+pub fn disperse_seeds(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    config: Res<PlantConfig>,
+    mut budget: ResMut<SpawnBudget>,
+    mut guts: Query<(&mut GutSeeds, &Transform)>,
+    scorched_zones: Query<(&ScorchedZone, &Transform)>,
+) {
+    for (mut gut, transform) in guts.iter_mut() {
+        // Animals can wander past the plant bounds; seeds landing there or on scorched
+        // ground do not take
+        let position = transform.translation.truncate();
+        let fertile = position.abs().max_element() <= config.world_bounds
+            && !is_scorched(position, scorched_zones.iter());
+
+        let mut dropped = 0;
+        gut.0.retain_mut(|ticks| {
+            *ticks = ticks.saturating_sub(1);
+            if *ticks > 0 {
+                return true;
+            }
+            if fertile && budget.try_take() {
+                dropped += 1;
+                return false;
+            }
+            // Without spawn budget the seed stays in the gut until a later tick
+            fertile
+        });
+
+        for _ in 0..dropped {
+            spawn_plant(&mut commands, &mut meshes, &mut materials, position, 0);
+        }
+    }
+}
+
+/// System to leave a regrow marker where some of the depleted plants stood
+pub fn leave_regrow_markers(
+    mut commands: Commands,
+    settings: Res<PlantRegrowth>,
+    mut rng: ResMut<SimRng>,
+    mut depleted: EventReader<PlantDepleted>,
+) {
+    if !settings.enabled {
+        depleted.clear();
+        return;
+    }
+
+    for &PlantDepleted { position } in depleted.read() {
+        if rng.gen_bool(settings.share.clamp(0.0, 1.0) as f64) {
+            commands.spawn((
+                RegrowMarker {
+                    ticks_left: settings.delay_ticks,
+                },
+                Transform::from_xyz(position.x, position.y, 0.0),
+            ));
+        }
+    }
+}
+
+/// System to count down regrow markers and sprout a plant at each one that is due
+pub fn regrow_plants(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut budget: ResMut<SpawnBudget>,
+    mut markers: Query<(Entity, &mut RegrowMarker, &Transform)>,
+    scorched_zones: Query<(&ScorchedZone, &Transform)>,
+) {
+    for (entity, mut marker, transform) in markers.iter_mut() {
+        marker.ticks_left = marker.ticks_left.saturating_sub(1);
+        if marker.ticks_left > 0 {
+            continue;
+        }
+
+        // A meteor burns the site out for good; without spawn budget the marker waits
+        let position = transform.translation.truncate();
+        if is_scorched(position, scorched_zones.iter()) {
+            commands.entity(entity).despawn();
+        } else if budget.try_take() {
+            spawn_plant(&mut commands, &mut meshes, &mut materials, position, 0);
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+/// System to grow existing plants (increment energy), skipping some shaded plants when
+/// shading is on
 pub fn grow_plants(
     time: Res<Time>,
+    shading: Res<PlantShading>,
     mut timer: ResMut<PlantGrowthTimer>,
-    mut plants: Query<&mut Plant>,
+    mut flows: ResMut<EnergyFlows>,
+    mut rng: ResMut<SimRng>,
+    mut plants: Query<(&mut Plant, &Transform)>,
 ) {
-    if timer.0.tick(time.delta()).just_finished() {
-        for mut plant in plants.iter_mut() {
-            plant.add_energy(PLANT_GROWTH_AMOUNT);
+    if !timer.0.tick(time.delta()).just_finished() {
+        return;
+    }
+
+    let neighbours = if shading.enabled {
+        let positions: Vec<(Vec2, bool)> = plants
+            .iter()
+            .map(|(plant, transform)| {
+                (
+                    transform.translation.truncate(),
+                    plant.energy >= PLANT_MATURE_ENERGY,
+                )
+            })
+            .collect();
+        shading_neighbours(&positions, shading.radius)
+    } else {
+        Vec::new()
+    };
+
+    for (i, (mut plant, _)) in plants.iter_mut().enumerate() {
+        if let Some(&shade) = neighbours.get(i)
+            && !rng.gen_bool(shading.growth_chance(shade) as f64)
+        {
+            continue;
         }
+        let before = plant.energy;
+        plant.add_energy(PLANT_GROWTH_AMOUNT);
+        flows.record(Flow::Solar, plant.energy - before);
     }
 }
 
@@ -98,3 +512,79 @@ pub fn update_plant_visuals(mut plants: Query<(&Plant, &mut Transform), Changed<
         transform.scale = Vec3::splat(scale);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::ecs::system::RunSystemOnce;
+
+    #[test]
+    fn best_candidate_is_farthest_from_existing_plants() {
+        let plants = [Vec2::ZERO, Vec2::new(10.0, 0.0)];
+        let candidates = [
+            Vec2::new(5.0, 0.0),
+            Vec2::new(-30.0, 0.0),
+            Vec2::new(12.0, 3.0),
+        ];
+        assert_eq!(
+            best_candidate(candidates, &plants),
+            Some(Vec2::new(-30.0, 0.0))
+        );
+        assert_eq!(best_candidate([], &plants), None);
+    }
+
+    #[test]
+    fn eaten_plants_drop_seeds_after_the_delay() {
+        let mut world = World::new();
+        world.init_resource::<Events<PlantEaten>>();
+        world.init_resource::<Assets<Mesh>>();
+        world.init_resource::<Assets<ColorMaterial>>();
+        world.init_resource::<PlantConfig>();
+        world.init_resource::<SpawnBudget>();
+        world.insert_resource(SimRng::seeded(1));
+        world.insert_resource(SeedDispersal {
+            enabled: true,
+            probability: 1.0,
+            delay_ticks: 3,
+        });
+        let animal = world
+            .spawn((Animal::new(100), Transform::from_xyz(40.0, -20.0, 0.0)))
+            .id();
+
+        world.send_event(PlantEaten { animal });
+        world.run_system_once(ingest_seeds).unwrap();
+        assert_eq!(world.get::<GutSeeds>(animal).unwrap().0, vec![3]);
+
+        let mut plants = world.query_filtered::<&Transform, With<Plant>>();
+        for _ in 0..2 {
+            world.run_system_once(disperse_seeds).unwrap();
+            assert_eq!(plants.iter(&world).count(), 0);
+        }
+        world.run_system_once(disperse_seeds).unwrap();
+        let seedlings: Vec<Vec2> = plants
+            .iter(&world)
+            .map(|transform| transform.translation.truncate())
+            .collect();
+        assert_eq!(seedlings, vec![Vec2::new(40.0, -20.0)]);
+        assert!(world.get::<GutSeeds>(animal).unwrap().0.is_empty());
+    }
+
+    #[test]
+    fn mature_plants_shade_their_neighbours() {
+        let plants = [
+            (Vec2::ZERO, true),
+            (Vec2::new(30.0, 0.0), true),
+            (Vec2::new(0.0, 35.0), false),
+            (Vec2::new(200.0, 0.0), true),
+        ];
+        // Seedlings shade nothing, and neither do plants beyond the radius
+        assert_eq!(shading_neighbours(&plants, 40.0), vec![1, 1, 1, 0]);
+
+        let shading = PlantShading {
+            strength: 0.5,
+            ..PlantShading::default()
+        };
+        assert_eq!(shading.growth_chance(0), 1.0);
+        assert_eq!(shading.growth_chance(2), 0.5);
+    }
+}
@@ -0,0 +1,339 @@
+use crate::animal::SensorNoise;
+use crate::cli::CliArgs;
+use crate::energy_flow::{EnergyFlows, Flow};
+use crate::genome::{Genome, MutationConfig, ParseError};
+use crate::meteor::MeteorSettings;
+use crate::plant::{
+    Plant, PlantConfig, PlantPlacement, PlantShading, PlantSpawnTimer, SeedDispersal, spawn_plant,
+};
+use crate::sim_rng::SimRng;
+use crate::spawning::SpawnQueue;
+use bevy::prelude::*;
+use serde::Deserialize;
+use std::fmt;
+use std::path::Path;
+
+/// Scenarios shipped with the simulator, selectable by name with `--scenario`
+const BUNDLED: [(&str, &str); 3] = [
+    ("forager", include_str!("../scenarios/forager.ron")),
+    ("benchmark", include_str!("../scenarios/benchmark.ron")),
+    (
+        "predator_prey",
+        include_str!("../scenarios/predator_prey.ron"),
+    ),
+];
+
+/// Error while loading a scenario
+#[derive(Debug)]
+pub enum ScenarioError {
+    Io(std::io::Error),
+    Ron(ron::error::SpannedError),
+    Genome { animal: usize, error: ParseError },
+}
+
+impl fmt::Display for ScenarioError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScenarioError::Io(err) => write!(f, "{}", err),
+            ScenarioError::Ron(err) => write!(f, "{}", err),
+            ScenarioError::Genome { animal, error } => {
+                write!(f, "genome of animal {}: {}", animal, error)
+            }
+        }
+    }
+}
+
+impl From<std::io::Error> for ScenarioError {
+    fn from(err: std::io::Error) -> Self {
+        ScenarioError::Io(err)
+    }
+}
+
+impl From<ron::error::SpannedError> for ScenarioError {
+    fn from(err: ron::error::SpannedError) -> Self {
+        ScenarioError::Ron(err)
+    }
+}
+
+/// Overrides of the default settings; unset fields keep the defaults
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct ScenarioConfig {
+    /// Whether plants keep spawning at random positions
+    pub plant_spawning: Option<bool>,
+    /// Whether random meteors fall
+    pub meteors: Option<bool>,
+    /// Whether offspring mutate
+    pub mutations: Option<bool>,
+    /// Whether eaten plants can be dispersed as seeds
+    pub seed_dispersal: Option<bool>,
+    /// Where randomly spawned plants are placed
+    pub plant_placement: Option<PlantPlacement>,
+    /// Relative standard deviation of the noise added to sensor readings
+    pub sensor_noise: Option<f32>,
+    /// Whether mature plants slow the growth of plants near them
+    pub plant_shading: Option<bool>,
+    /// Seed of the simulation's random numbers, so the run repeats exactly
+    pub seed: Option<u64>,
+}
+
+/// An animal placed by a scenario, as written in the file
+#[derive(Deserialize)]
+struct AnimalRecord {
+    position: (f32, f32),
+    #[serde(default)]
+    rotation: f32,
+    energy: u32,
+    /// Genome source as read by `Genome::parse`; the seed genome when missing
+    #[serde(default)]
+    genome: Option<String>,
+    /// Override for the genome's diet
+    #[serde(default)]
+    herbivory: Option<f32>,
+}
+
+/// A plant placed by a scenario
+#[derive(Debug, Deserialize)]
+pub struct ScenarioPlant {
+    pub position: (f32, f32),
+    #[serde(default)]
+    pub energy: u32,
+}
+
+/// A scenario as written in its RON file
+#[derive(Deserialize)]
+struct ScenarioRecord {
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    ticks: Option<u32>,
+    #[serde(default)]
+    config: ScenarioConfig,
+    #[serde(default)]
+    animals: Vec<AnimalRecord>,
+    #[serde(default)]
+    plants: Vec<ScenarioPlant>,
+}
+
+/// An animal placed by a scenario, with its genome parsed
+#[derive(Clone)]
+pub struct ScenarioAnimal {
+    pub genome: Genome,
+    pub energy: u32,
+    pub position: Vec2,
+    pub rotation: f32,
+}
+
+/// Reproducible initial setup: exact animals and plants plus settings overrides,
+/// replacing the random seed population
+pub struct Scenario {
+    pub name: String,
+    /// Number of ticks a benchmark run of this scenario simulates
+    pub ticks: Option<u32>,
+    pub config: ScenarioConfig,
+    pub animals: Vec<ScenarioAnimal>,
+    pub plants: Vec<ScenarioPlant>,
+}
+
+impl fmt::Debug for Scenario {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Scenario")
+            .field("name", &self.name)
+            .field("animals", &self.animals.len())
+            .field("plants", &self.plants.len())
+            .finish()
+    }
+}
+
+impl Scenario {
+    /// Names of the bundled scenarios
+    pub fn bundled_names() -> impl Iterator<Item = &'static str> {
+        BUNDLED.iter().map(|(name, _)| *name)
+    }
+
+    /// Load a bundled scenario by name, or a scenario file by path
+    pub fn load(name_or_path: &str) -> Result<Self, ScenarioError> {
+        match BUNDLED.iter().find(|(name, _)| *name == name_or_path) {
+            Some((_, source)) => Self::parse(source),
+            None => Self::parse(&std::fs::read_to_string(Path::new(name_or_path))?),
+        }
+    }
+
+    /// Parse a scenario from RON source, checking every genome
+    pub fn parse(source: &str) -> Result<Self, ScenarioError> {
+        let record: ScenarioRecord = ron::from_str(source)?;
+
+        let animals = record
+            .animals
+            .into_iter()
+            .enumerate()
+            .map(|(animal, spec)| {
+                let mut genome = match &spec.genome {
+                    Some(source) => Genome::parse(source)
+                        .map_err(|error| ScenarioError::Genome { animal, error })?,
+                    None => Genome::seed(),
+                };
+                if let Some(herbivory) = spec.herbivory {
+                    genome.diet.herbivory = herbivory.clamp(0.0, 1.0);
+                }
+                Ok(ScenarioAnimal {
+                    genome,
+                    energy: spec.energy,
+                    position: Vec2::new(spec.position.0, spec.position.1),
+                    rotation: spec.rotation,
+                })
+            })
+            .collect::<Result<_, ScenarioError>>()?;
+
+        Ok(Self {
+            name: record.name,
+            ticks: record.ticks,
+            config: record.config,
+            animals,
+            plants: record.plants,
+        })
+    }
+}
+
+/// Startup system to set up the scenario given with `--scenario`
+#[allow(clippy::too_many_arguments)]
+pub fn apply_scenario(
+    cli: Res<CliArgs>,
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut queue: ResMut<SpawnQueue>,
+    mut plant_timer: ResMut<PlantSpawnTimer>,
+    mut plant_config: ResMut<PlantConfig>,
+    mut meteors: ResMut<MeteorSettings>,
+    mut mutation: ResMut<MutationConfig>,
+    mut seeds: ResMut<SeedDispersal>,
+    mut noise: ResMut<SensorNoise>,
+    mut shading: ResMut<PlantShading>,
+    mut rng: ResMut<SimRng>,
+    mut flows: ResMut<EnergyFlows>,
+) {
+    let Some(scenario) = &cli.scenario else {
+        return;
+    };
+    let config = &scenario.config;
+
+    if config.plant_spawning == Some(false) {
+        plant_timer.0.pause();
+    }
+    if let Some(enabled) = config.meteors {
+        meteors.random_enabled = enabled;
+    }
+    if config.mutations == Some(false) {
+        *mutation = MutationConfig::disabled();
+    }
+    if let Some(enabled) = config.seed_dispersal {
+        seeds.enabled = enabled;
+    }
+    if let Some(placement) = config.plant_placement {
+        plant_config.placement = placement;
+    }
+    if let Some(sigma) = config.sensor_noise {
+        noise.sigma = sigma.max(0.0);
+    }
+    if let Some(enabled) = config.plant_shading {
+        shading.enabled = enabled;
+    }
+    if let Some(seed) = config.seed {
+        *rng = SimRng::seeded(seed);
+    }
+
+    for animal in &scenario.animals {
+        queue.push(
+            animal.genome.clone(),
+            animal.energy,
+            animal.position,
+            animal.rotation,
+        );
+    }
+    for plant in &scenario.plants {
+        let position = Vec2::new(plant.position.0, plant.position.1);
+        flows.record(Flow::Introduction, plant.energy.min(Plant::MAX_ENERGY));
+        spawn_plant(
+            &mut commands,
+            &mut meshes,
+            &mut materials,
+            position,
+            plant.energy,
+        );
+    }
+
+    info!(
+        "Loaded scenario '{}': {} animal(s), {} plant(s)",
+        scenario.name,
+        scenario.animals.len(),
+        scenario.plants.len()
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::ecs::system::RunSystemOnce;
+    use rand::RngCore;
+
+    #[test]
+    fn bundled_scenarios_load() {
+        for name in Scenario::bundled_names() {
+            let scenario = Scenario::load(name).unwrap_or_else(|err| panic!("{name}: {err}"));
+            assert!(!scenario.name.is_empty());
+            assert!(!scenario.animals.is_empty());
+        }
+
+        let predator = &Scenario::load("predator_prey").unwrap().animals[0];
+        assert_eq!(predator.genome.diet.herbivory, 0.0);
+        assert_eq!(predator.position, Vec2::new(0.0, -60.0));
+
+        let benchmark = Scenario::load("benchmark").unwrap();
+        assert!(benchmark.ticks.is_some_and(|ticks| ticks > 0));
+    }
+
+    #[test]
+    fn bad_genome_names_the_animal() {
+        let source = r#"(animals: [
+            (position: (0.0, 0.0), energy: 10),
+            (position: (0.0, 0.0), energy: 10, genome: Some("dup wiggle")),
+        ])"#;
+        match Scenario::parse(source) {
+            Err(ScenarioError::Genome { animal, error }) => {
+                assert_eq!(animal, 1);
+                assert_eq!(error.token, "wiggle");
+            }
+            other => panic!("expected a genome error, got {:?}", other.map(|s| s.name)),
+        }
+    }
+
+    #[test]
+    fn scenario_seed_seeds_the_simulation() {
+        let mut cli = CliArgs::parse([]);
+        cli.scenario = Some(Scenario::parse("(config: (seed: Some(42)))").unwrap());
+
+        let mut world = World::new();
+        world.insert_resource(cli);
+        world.init_resource::<Assets<Mesh>>();
+        world.init_resource::<Assets<ColorMaterial>>();
+        world.init_resource::<SpawnQueue>();
+        world.insert_resource(PlantSpawnTimer(Timer::from_seconds(
+            1.0,
+            TimerMode::Repeating,
+        )));
+        world.init_resource::<PlantConfig>();
+        world.init_resource::<MeteorSettings>();
+        world.init_resource::<MutationConfig>();
+        world.init_resource::<SeedDispersal>();
+        world.init_resource::<SensorNoise>();
+        world.init_resource::<PlantShading>();
+        world.init_resource::<SimRng>();
+        world.init_resource::<EnergyFlows>();
+        world.run_system_once(apply_scenario).unwrap();
+
+        let mut expected = SimRng::seeded(42);
+        let mut rng = world.resource_mut::<SimRng>();
+        assert_eq!(rng.next_u64(), expected.next_u64());
+    }
+}
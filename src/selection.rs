@@ -1,4 +1,6 @@
+use crate::animal::AnimalSplit;
 use crate::config::*;
+use crate::genome::Mutation;
 use bevy::prelude::*;
 use bevy::window::PrimaryWindow;
 use bevy_egui::EguiContexts;
@@ -13,7 +15,78 @@ pub struct SelectedEntity {
     pub entity: Option<Entity>,
 }
 
+/// A split of the selected animal, shown in the Genome Viewer for a short while
+pub struct SplitPlayback {
+    pub parent: Entity,
+    pub child: Entity,
+    pub partner: Option<Entity>,
+    pub mutations: Vec<Mutation>,
+    pub timer: Timer,
+}
+
+/// Resource controlling what happens to the selection when the selected animal splits
+#[derive(Resource)]
+pub struct SplitFollow {
+    /// Move the selection to the offspring once the playback ends
+    pub auto_follow: bool,
+    pub playback: Option<SplitPlayback>,
+}
+
+impl Default for SplitFollow {
+    fn default() -> Self {
+        Self {
+            auto_follow: true,
+            playback: None,
+        }
+    }
+}
+
+/// System to start split playback for the selected animal and follow its offspring
+pub fn follow_selected_splits(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut follow: ResMut<SplitFollow>,
+    mut selected_entity: ResMut<SelectedEntity>,
+    mut splits: EventReader<AnimalSplit>,
+) {
+    for split in splits.read() {
+        if selected_entity.entity == Some(split.parent) {
+            follow.playback = Some(SplitPlayback {
+                parent: split.parent,
+                child: split.child,
+                partner: split.partner,
+                mutations: split.mutations.clone(),
+                timer: Timer::from_seconds(SPLIT_PLAYBACK_DURATION, TimerMode::Once),
+            });
+        }
+    }
+
+    let Some(playback) = follow.playback.as_mut() else {
+        return;
+    };
+
+    // Drop the playback if the user selected something else in the meantime
+    if selected_entity.entity != Some(playback.parent) {
+        follow.playback = None;
+        return;
+    }
+
+    if playback.timer.tick(time.delta()).finished() {
+        let child = playback.child;
+        let parent = playback.parent;
+        follow.playback = None;
+
+        if follow.auto_follow {
+            commands.entity(parent).remove::<Selected>();
+            // The offspring may already have died
+            commands.entity(child).try_insert(Selected);
+            selected_entity.entity = Some(child);
+        }
+    }
+}
+
 /// System to handle entity selection via mouse clicks
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
 pub fn handle_selection(
     mouse_button: Res<ButtonInput<MouseButton>>,
     windows: Query<&Window, With<PrimaryWindow>>,
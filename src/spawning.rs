@@ -0,0 +1,207 @@
+use crate::SimulationTick;
+use crate::animal::{Lineage, spawn_animal};
+use crate::config::*;
+use crate::energy_flow::{EnergyFlows, Flow};
+use crate::genome::Genome;
+use crate::meteor::{ScorchedZone, escape_scorched};
+use bevy::prelude::*;
+use std::collections::VecDeque;
+
+/// Resource capping how many entities (offspring, plants, seedlings and queued animals)
+/// may be spawned in one tick, so spawn storms are spread over several frames
+#[derive(Resource)]
+pub struct SpawnBudget {
+    pub per_tick: u32,
+    remaining: u32,
+}
+
+impl Default for SpawnBudget {
+    fn default() -> Self {
+        Self {
+            per_tick: MAX_SPAWNS_PER_TICK,
+            remaining: MAX_SPAWNS_PER_TICK,
+        }
+    }
+}
+
+impl SpawnBudget {
+    /// Start a new tick with the full allowance
+    pub fn refresh(&mut self) {
+        self.remaining = self.per_tick;
+    }
+
+    pub fn is_exhausted(&self) -> bool {
+        self.remaining == 0
+    }
+
+    /// Use up one spawn if any are left this tick
+    pub fn try_take(&mut self) -> bool {
+        if self.is_exhausted() {
+            return false;
+        }
+        self.remaining -= 1;
+        true
+    }
+}
+
+/// A founder animal waiting for spawn budget
+pub struct PendingSpawn {
+    pub genome: Genome,
+    pub energy: u32,
+    pub position: Vec2,
+    pub rotation: f32,
+}
+
+/// Resource holding founder animals (seed, failsafe, imported and hand-written) that
+/// are spawned as the budget allows
+#[derive(Resource, Default)]
+pub struct SpawnQueue(pub VecDeque<PendingSpawn>);
+
+impl SpawnQueue {
+    pub fn push(&mut self, genome: Genome, energy: u32, position: Vec2, rotation: f32) {
+        self.0.push_back(PendingSpawn {
+            genome,
+            energy,
+            position,
+            rotation,
+        });
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+/// System to spawn queued founders in arrival order until the tick's budget runs out.
+/// Founders queued inside a scorched zone are moved just outside it
+#[allow(clippy::too_many_arguments)]
+pub fn drain_spawn_queue(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut queue: ResMut<SpawnQueue>,
+    mut budget: ResMut<SpawnBudget>,
+    mut flows: ResMut<EnergyFlows>,
+    tick: Res<SimulationTick>,
+    scorched_zones: Query<(&ScorchedZone, &Transform)>,
+) {
+    let zones: Vec<(Vec2, f32)> = scorched_zones
+        .iter()
+        .map(|(zone, transform)| (transform.translation.truncate(), zone.radius))
+        .collect();
+
+    while !queue.is_empty() && !budget.is_exhausted() {
+        let Some(pending) = queue.0.pop_front() else {
+            break;
+        };
+        let Some(position) = escape_scorched(pending.position, &zones) else {
+            warn!(
+                "Dropped a queued animal at ({:.0}, {:.0}): no unscorched ground nearby",
+                pending.position.x, pending.position.y
+            );
+            continue;
+        };
+        budget.try_take();
+        flows.record(Flow::Introduction, pending.energy);
+        spawn_animal(
+            &mut commands,
+            &mut meshes,
+            &mut materials,
+            pending.genome,
+            Lineage::founder(tick.0),
+            pending.energy,
+            position,
+            pending.rotation,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::animal::Animal;
+    use bevy::ecs::system::RunSystemOnce;
+
+    #[test]
+    fn queued_founders_never_appear_in_scorched_zones() {
+        let mut world = World::new();
+        world.init_resource::<Assets<Mesh>>();
+        world.init_resource::<Assets<ColorMaterial>>();
+        world.init_resource::<SpawnBudget>();
+        world.init_resource::<EnergyFlows>();
+        world.init_resource::<SimulationTick>();
+        world.spawn((
+            ScorchedZone {
+                radius: 50.0,
+                timer: Timer::from_seconds(SCORCH_DURATION, TimerMode::Once),
+            },
+            Transform::from_xyz(100.0, 0.0, 0.0),
+        ));
+
+        let mut queue = SpawnQueue::default();
+        for position in [Vec2::new(100.0, 0.0), Vec2::new(120.0, 10.0), Vec2::ZERO] {
+            queue.push(Genome::seed(), STARTING_ANIMAL_ENERGY, position, 0.0);
+        }
+        world.insert_resource(queue);
+        world.run_system_once(drain_spawn_queue).unwrap();
+
+        let positions: Vec<Vec2> = world
+            .query_filtered::<&Transform, With<Animal>>()
+            .iter(&world)
+            .map(|transform| transform.translation.truncate())
+            .collect();
+        assert_eq!(positions.len(), 3);
+        assert!(positions.contains(&Vec2::ZERO));
+        for position in positions {
+            assert!(
+                position.distance(Vec2::new(100.0, 0.0)) > 50.0,
+                "{position}"
+            );
+        }
+    }
+
+    #[test]
+    fn queued_founders_spawn_a_budget_at_a_time() {
+        let mut world = World::new();
+        world.init_resource::<Assets<Mesh>>();
+        world.init_resource::<Assets<ColorMaterial>>();
+        world.init_resource::<EnergyFlows>();
+        world.init_resource::<SimulationTick>();
+        let mut budget = SpawnBudget {
+            per_tick: 2,
+            ..default()
+        };
+        budget.refresh();
+        world.insert_resource(budget);
+
+        let mut queue = SpawnQueue::default();
+        for x in 0..5 {
+            queue.push(
+                Genome::seed(),
+                STARTING_ANIMAL_ENERGY,
+                Vec2::new(x as f32, 0.0),
+                0.0,
+            );
+        }
+        world.insert_resource(queue);
+
+        let mut spawned = Vec::new();
+        for _ in 0..3 {
+            world.run_system_once(drain_spawn_queue).unwrap();
+            spawned.push(world.query::<&Animal>().iter(&world).count());
+            // Nothing more spawns until the next tick refreshes the budget
+            world.run_system_once(drain_spawn_queue).unwrap();
+            assert_eq!(
+                world.query::<&Animal>().iter(&world).count(),
+                *spawned.last().unwrap()
+            );
+            world.resource_mut::<SpawnBudget>().refresh();
+        }
+        assert_eq!(spawned, vec![2, 4, 5]);
+        assert!(world.resource::<SpawnQueue>().is_empty());
+    }
+}
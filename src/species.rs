@@ -0,0 +1,114 @@
+use crate::animal::Animal;
+use crate::config::*;
+use crate::genome::Genome;
+use bevy::prelude::*;
+
+/// Species an animal was last clustered into
+#[derive(Component, Reflect, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[reflect(Component)]
+pub struct Species(pub u32);
+
+/// A living species and the genome that founded it
+pub struct SpeciesRepresentative {
+    pub id: u32,
+    pub genome: Genome,
+    pub members: usize,
+}
+
+/// Resource grouping living genomes into species by edit distance to each species'
+/// founding genome
+#[derive(Resource)]
+pub struct SpeciesRegistry {
+    pub species: Vec<SpeciesRepresentative>,
+    /// Most word edits from a founding genome that still count as the same species
+    pub threshold: usize,
+    pub timer: Timer,
+    next_id: u32,
+}
+
+impl Default for SpeciesRegistry {
+    fn default() -> Self {
+        Self {
+            species: Vec::new(),
+            threshold: SPECIES_DISTANCE_THRESHOLD,
+            timer: Timer::from_seconds(SPECIES_CLUSTER_INTERVAL, TimerMode::Repeating),
+            next_id: 1,
+        }
+    }
+}
+
+impl SpeciesRegistry {
+    /// Species for a genome: its current one if still close enough, otherwise the first
+    /// close founder, otherwise a new species founded by this genome
+    pub fn classify(&mut self, genome: &Genome, current: Option<Species>) -> Species {
+        let threshold = self.threshold;
+        let close = |rep: &SpeciesRepresentative| genome.diff(&rep.genome).distance() <= threshold;
+
+        let index = current
+            .and_then(|Species(id)| self.species.iter().position(|rep| rep.id == id))
+            .filter(|&i| close(&self.species[i]))
+            .or_else(|| self.species.iter().position(close));
+
+        let index = index.unwrap_or_else(|| {
+            self.species.push(SpeciesRepresentative {
+                id: self.next_id,
+                genome: genome.clone(),
+                members: 0,
+            });
+            self.next_id += 1;
+            self.species.len() - 1
+        });
+
+        self.species[index].members += 1;
+        Species(self.species[index].id)
+    }
+}
+
+/// System to periodically re-cluster every living animal and drop extinct species
+pub fn cluster_species(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut registry: ResMut<SpeciesRegistry>,
+    animals: Query<(Entity, &Genome, Option<&Species>), With<Animal>>,
+) {
+    if !registry.timer.tick(time.delta()).just_finished() {
+        return;
+    }
+
+    for rep in registry.species.iter_mut() {
+        rep.members = 0;
+    }
+    for (entity, genome, current) in animals.iter() {
+        let species = registry.classify(genome, current.copied());
+        if current != Some(&species) {
+            commands.entity(entity).try_insert(species);
+        }
+    }
+    registry.species.retain(|rep| rep.members > 0);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn genomes_cluster_by_distance_to_founder() {
+        let mut registry = SpeciesRegistry {
+            threshold: 1,
+            ..Default::default()
+        };
+        let founder = Genome::parse("dup drop swap over").unwrap();
+        let near = Genome::parse("dup drop swap eat").unwrap();
+        let far = Genome::parse("eat eat eat eat").unwrap();
+
+        let first = registry.classify(&founder, None);
+        assert_eq!(registry.classify(&near, None), first);
+        let second = registry.classify(&far, None);
+        assert_ne!(second, first);
+
+        // An animal stays in its species while it is close enough to that founder
+        assert_eq!(registry.classify(&near, Some(first)), first);
+        assert_eq!(registry.classify(&founder, Some(second)), first);
+        assert_eq!(registry.species.len(), 2);
+    }
+}
@@ -0,0 +1,130 @@
+use crate::animal::Animal;
+use crate::genome::{GenomeExecutor, Quadrants, Sensors, TraceEntry};
+use crate::{RunName, SimulationTick};
+use bevy::prelude::*;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
+
+/// Streams every word an animal executes, with the tick and its sensors, to a CSV
+/// file until the animal dies or the export is stopped
+#[derive(Component, Default)]
+pub struct TraceExport {
+    /// Opened on the first tick after the export starts
+    writer: Option<BufWriter<File>>,
+    pub path: Option<PathBuf>,
+    pub rows: u64,
+}
+
+const HEADER: &str = "tick,instruction_pointer,word,stack_top,energy,smell_front,smell_back,\
+                      smell_left,smell_right,smell_animal_front,smell_animal_back,\
+                      smell_animal_left,smell_animal_right,smell_corpse_front,smell_corpse_back,\
+                      smell_corpse_left,smell_corpse_right,animal_front,animal_back,animal_left,\
+                      animal_right,hear_front,hear_back,hear_left,hear_right,touching_plant,touching_animal,starving,\
+                      sight_distance,sight_kind,pheromone,contacts,\
+                      nearest_plant_energy,crowding,heading,\
+                      pos_x,pos_y,speed,turn_rate";
+
+impl TraceExport {
+    fn open(&mut self, run: &RunName, entity: Entity, tick: u64) -> std::io::Result<()> {
+        let dir = run.output_dir().join("traces");
+        std::fs::create_dir_all(&dir)?;
+        let path = dir.join(format!("{}-{}-tick-{:08}.csv", run.0, entity, tick));
+        let mut writer = BufWriter::new(File::create(&path)?);
+        writeln!(writer, "{}", HEADER)?;
+        info!(
+            "Exporting execution trace of {} to {}",
+            entity,
+            path.display()
+        );
+        self.writer = Some(writer);
+        self.path = Some(path);
+        Ok(())
+    }
+
+    fn write(
+        &mut self,
+        tick: u64,
+        entries: &[TraceEntry],
+        energy: u32,
+        sensors: &Sensors,
+    ) -> std::io::Result<()> {
+        let Some(writer) = self.writer.as_mut() else {
+            return Ok(());
+        };
+        let optional = |value: Option<f32>| value.map_or(String::new(), |v| v.to_string());
+        let quadrants = |sensor: &Quadrants| {
+            [sensor.front, sensor.back, sensor.left, sensor.right]
+                .map(optional)
+                .join(",")
+        };
+        for entry in entries {
+            writeln!(
+                writer,
+                "{},{},\"{}\",{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{}",
+                tick,
+                entry.instruction_pointer,
+                entry.word,
+                entry
+                    .stack_top
+                    .map_or(String::new(), |value| value.to_string()),
+                energy,
+                quadrants(&sensors.plant_scent),
+                quadrants(&sensors.animal_scent),
+                quadrants(&sensors.corpse_scent),
+                quadrants(&sensors.nearest_animal),
+                quadrants(&sensors.hearing),
+                sensors.touching_plant,
+                sensors.touching_animal,
+                sensors.starving,
+                optional(sensors.sight.map(|(distance, _)| distance)),
+                sensors
+                    .sight
+                    .map_or(String::new(), |(_, kind)| kind.to_string()),
+                sensors.pheromone,
+                sensors.contacts,
+                sensors
+                    .nearest_plant_energy
+                    .map_or(String::new(), |energy| energy.to_string()),
+                sensors.crowding,
+                sensors.heading,
+                sensors.position.x,
+                sensors.position.y,
+                sensors.speed,
+                sensors.turn_rate,
+            )?;
+        }
+        self.rows += entries.len() as u64;
+        Ok(())
+    }
+}
+
+/// System to append each exporting animal's words executed this tick to its file
+pub fn write_trace_exports(
+    mut commands: Commands,
+    tick: Res<SimulationTick>,
+    run: Res<RunName>,
+    mut animals: Query<(
+        Entity,
+        &Animal,
+        &mut GenomeExecutor,
+        &Sensors,
+        &mut TraceExport,
+    )>,
+) {
+    for (entity, animal, mut executor, sensors, mut export) in animals.iter_mut() {
+        let result = if export.writer.is_none() {
+            executor.set_exporting(true);
+            export.open(&run, entity, tick.0)
+        } else {
+            let entries = executor.take_exported();
+            export.write(tick.0, &entries, animal.energy, sensors)
+        };
+
+        if let Err(err) = result {
+            error!("Failed to export execution trace of {}: {err}", entity);
+            executor.set_exporting(false);
+            commands.entity(entity).remove::<TraceExport>();
+        }
+    }
+}
@@ -0,0 +1,192 @@
+use crate::animal::Animal;
+use crate::config::*;
+use bevy::asset::RenderAssetUsages;
+use bevy::prelude::*;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+
+/// Resource counting how often animals pass through each cell of a grid over the world.
+/// Counts fade over time, so the overlay drawn from them shows the paths in use now
+/// rather than everywhere an animal has ever been
+#[derive(Resource)]
+pub struct TrailMap {
+    pub visible: bool,
+    /// Fraction of every count that fades per tick
+    pub decay: f32,
+    cell_size: f32,
+    /// World coordinate of the grid's lower-left corner on both axes
+    origin: f32,
+    /// Cells per side
+    width: usize,
+    visits: Vec<f32>,
+    /// Overlay texture, one pixel per cell, created on startup
+    image: Option<Handle<Image>>,
+    redraw: Timer,
+}
+
+impl Default for TrailMap {
+    fn default() -> Self {
+        Self::new(WORLD_BOUNDS, TRAIL_CELL_SIZE)
+    }
+}
+
+impl TrailMap {
+    /// An empty map covering ±`bounds` on both axes
+    pub fn new(bounds: f32, cell_size: f32) -> Self {
+        let width = ((bounds * 2.0 / cell_size).ceil() as usize).max(1);
+        Self {
+            visible: false,
+            decay: TRAIL_DECAY,
+            cell_size,
+            origin: -bounds,
+            width,
+            visits: vec![0.0; width * width],
+            image: None,
+            redraw: Timer::from_seconds(TRAIL_REDRAW_INTERVAL, TimerMode::Repeating),
+        }
+    }
+
+    /// Index of the cell containing `position`, or None outside the map
+    fn cell(&self, position: Vec2) -> Option<usize> {
+        let x = ((position.x - self.origin) / self.cell_size).floor();
+        let y = ((position.y - self.origin) / self.cell_size).floor();
+        let inside = |v: f32| v >= 0.0 && v < self.width as f32;
+        (inside(x) && inside(y)).then(|| y as usize * self.width + x as usize)
+    }
+
+    /// Fade every count, then count one visit at each position
+    pub fn record(&mut self, positions: impl IntoIterator<Item = Vec2>) {
+        let keep = 1.0 - self.decay.clamp(0.0, 1.0);
+        for visits in &mut self.visits {
+            *visits *= keep;
+        }
+        for position in positions {
+            if let Some(cell) = self.cell(position) {
+                self.visits[cell] += 1.0;
+            }
+        }
+    }
+
+    /// Visits counted at `position`; 0 outside the map
+    pub fn visits(&self, position: Vec2) -> f32 {
+        self.cell(position).map_or(0.0, |cell| self.visits[cell])
+    }
+
+    /// Overlay pixels, RGBA with rows from the top of the world down. Opacity grows
+    /// with the square root of each cell's share of the busiest cell's visits, so
+    /// lightly used paths still show next to a crowded patch
+    fn pixels(&self) -> Vec<u8> {
+        let busiest = self.visits.iter().copied().fold(0.0, f32::max);
+        let mut pixels = Vec::with_capacity(self.visits.len() * 4);
+        for row in self.visits.chunks(self.width).rev() {
+            for &visits in row {
+                let wear = if busiest > 0.0 {
+                    (visits / busiest).sqrt()
+                } else {
+                    0.0
+                };
+                pixels.extend_from_slice(&[120, 95, 60, (wear * TRAIL_MAX_ALPHA) as u8]);
+            }
+        }
+        pixels
+    }
+}
+
+/// Marker for the sprite the trail overlay is drawn on
+#[derive(Component)]
+pub struct TrailOverlay;
+
+/// Startup system to create the trail overlay: a texture stretched over the world,
+/// under everything else
+pub fn setup_trail_overlay(
+    mut commands: Commands,
+    mut trails: ResMut<TrailMap>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    let size = Extent3d {
+        width: trails.width as u32,
+        height: trails.width as u32,
+        depth_or_array_layers: 1,
+    };
+    let image = images.add(Image::new_fill(
+        size,
+        TextureDimension::D2,
+        &[0, 0, 0, 0],
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::default(),
+    ));
+
+    let extent = trails.width as f32 * trails.cell_size;
+    let center = trails.origin + extent / 2.0;
+    commands.spawn((
+        Sprite {
+            image: image.clone(),
+            custom_size: Some(Vec2::splat(extent)),
+            ..default()
+        },
+        Transform::from_xyz(center, center, -0.6),
+        Visibility::Hidden,
+        TrailOverlay,
+    ));
+    trails.image = Some(image);
+}
+
+/// System to count a visit at every animal's position each tick
+pub fn record_trails(mut trails: ResMut<TrailMap>, animals: Query<&Transform, With<Animal>>) {
+    trails.record(
+        animals
+            .iter()
+            .map(|transform| transform.translation.truncate()),
+    );
+}
+
+/// System to show or hide the trail overlay and redraw it a few times a second
+pub fn draw_trails(
+    time: Res<Time>,
+    mut trails: ResMut<TrailMap>,
+    mut images: ResMut<Assets<Image>>,
+    mut overlays: Query<&mut Visibility, With<TrailOverlay>>,
+) {
+    let Some(handle) = trails.image.clone() else {
+        return;
+    };
+    for mut visibility in overlays.iter_mut() {
+        visibility.set_if_neq(if trails.visible {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        });
+    }
+
+    if !trails.visible || !trails.redraw.tick(time.delta()).just_finished() {
+        return;
+    }
+    if let Some(image) = images.get_mut(&handle) {
+        image.data = trails.pixels();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn visits_accumulate_and_fade() {
+        let mut trails = TrailMap::new(20.0, 10.0);
+        trails.decay = 0.5;
+        let path = Vec2::new(-15.0, 5.0);
+
+        trails.record([path, path, Vec2::new(100.0, 0.0)]);
+        assert_eq!(trails.visits(path), 2.0);
+        trails.record([]);
+        assert_eq!(trails.visits(path), 1.0);
+        assert_eq!(trails.visits(Vec2::new(15.0, 5.0)), 0.0);
+
+        // The busiest cell is fully worn; the top row of pixels is the top of the world
+        let pixels = trails.pixels();
+        assert_eq!(pixels.len(), 4 * 4 * 4);
+        let row_from_top = 1;
+        let worn = (row_from_top * 4) * 4 + 3;
+        assert_eq!(pixels[worn], TRAIL_MAX_ALPHA as u8);
+        assert_eq!(pixels[3], 0);
+    }
+}
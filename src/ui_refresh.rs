@@ -0,0 +1,70 @@
+use crate::config::*;
+use bevy::prelude::*;
+
+/// Resource pacing the expensive UI panels (census, charts, genome viewer state). They
+/// rebuild their contents at most `rate` times per real second and show the last
+/// result in between, so the UI costs the same whether the simulation runs one tick a
+/// frame or fast-forwards thousands
+#[derive(Resource)]
+pub struct UiRefresh {
+    /// Refreshes per real second
+    pub rate: f32,
+    timer: Timer,
+    due: bool,
+}
+
+impl Default for UiRefresh {
+    fn default() -> Self {
+        Self {
+            rate: UI_REFRESH_RATE,
+            timer: Timer::from_seconds(1.0 / UI_REFRESH_RATE, TimerMode::Repeating),
+            // Panels fill their caches on the first frame
+            due: true,
+        }
+    }
+}
+
+impl UiRefresh {
+    /// Whether throttled panels should rebuild their contents this frame
+    pub fn is_due(&self) -> bool {
+        self.due
+    }
+
+    /// Advance by `delta` of real time
+    pub fn tick(&mut self, delta: std::time::Duration) {
+        let interval = 1.0 / self.rate.clamp(UI_REFRESH_MIN_RATE, UI_REFRESH_MAX_RATE);
+        if self.timer.duration().as_secs_f32() != interval {
+            self.timer
+                .set_duration(std::time::Duration::from_secs_f32(interval));
+        }
+        self.due = self.timer.tick(delta).just_finished();
+    }
+}
+
+/// System to decide at the start of each frame whether throttled panels refresh. Runs on
+/// real time, so pausing or fast-forwarding the simulation does not change the pace
+pub fn tick_ui_refresh(time: Res<Time<Real>>, mut refresh: ResMut<UiRefresh>) {
+    refresh.tick(time.delta());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn refreshes_at_the_configured_rate() {
+        let mut refresh = UiRefresh::default();
+        assert!(refresh.is_due());
+
+        refresh.rate = 4.0;
+        let frame = Duration::from_millis(10);
+        let refreshes = (0..100)
+            .filter(|_| {
+                refresh.tick(frame);
+                refresh.is_due()
+            })
+            .count();
+        assert_eq!(refreshes, 4);
+    }
+}
@@ -0,0 +1,240 @@
+use crate::animal::Animal;
+use crate::camera::MainCamera;
+use crate::config::*;
+use crate::death_marker::DeathMarkers;
+use crate::outline::{INTERACTION_RING_LEGEND, InteractionRings};
+use crate::plant::Plant;
+use crate::trails::TrailMap;
+use crate::ui_refresh::UiRefresh;
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use bevy_egui::{EguiContexts, egui};
+
+/// Resource toggling the faint world coordinate grid
+#[derive(Resource)]
+pub struct CoordinateGrid {
+    pub visible: bool,
+    /// Smallest distance between grid lines; doubled while the view would hold more
+    /// than GRID_MAX_LINES of them
+    pub spacing: f32,
+}
+
+impl Default for CoordinateGrid {
+    fn default() -> Self {
+        Self {
+            visible: false,
+            spacing: GRID_SPACING,
+        }
+    }
+}
+
+/// World-space rectangle currently visible through the main camera
+pub fn visible_world_rect(
+    projection: &OrthographicProjection,
+    transform: &GlobalTransform,
+) -> Rect {
+    let center = transform.translation().truncate();
+    Rect::from_corners(projection.area.min + center, projection.area.max + center)
+}
+
+/// Mean of an iterator of values, or None when it is empty
+fn mean(values: impl Iterator<Item = f32>) -> Option<f32> {
+    let (sum, count) = values.fold((0.0, 0), |(sum, count), v| (sum + v, count + 1));
+    (count > 0).then(|| sum / count as f32)
+}
+
+/// Counts and averages over the entities inside the camera view
+pub struct ViewportCensus {
+    plants: usize,
+    plant_energy: Option<f32>,
+    animals: usize,
+    animal_energy: Option<f32>,
+    animal_age: Option<f32>,
+}
+
+/// System to show counts and averages for only the entities inside the camera view,
+/// recounted at the UI refresh rate
+pub fn viewport_stats_ui(
+    mut contexts: EguiContexts,
+    mut census: Local<Option<ViewportCensus>>,
+    refresh: Res<UiRefresh>,
+    camera: Query<(&OrthographicProjection, &GlobalTransform), With<MainCamera>>,
+    plants: Query<(&Plant, &Transform)>,
+    animals: Query<(&Animal, &Transform)>,
+) {
+    let Ok((projection, camera_transform)) = camera.get_single() else {
+        return;
+    };
+
+    if refresh.is_due() || census.is_none() {
+        let view = visible_world_rect(projection, camera_transform);
+        let visible_plants: Vec<&Plant> = plants
+            .iter()
+            .filter(|(_, transform)| view.contains(transform.translation.truncate()))
+            .map(|(plant, _)| plant)
+            .collect();
+        let visible_animals: Vec<&Animal> = animals
+            .iter()
+            .filter(|(_, transform)| view.contains(transform.translation.truncate()))
+            .map(|(animal, _)| animal)
+            .collect();
+        *census = Some(ViewportCensus {
+            plants: visible_plants.len(),
+            plant_energy: mean(visible_plants.iter().map(|p| p.energy as f32)),
+            animals: visible_animals.len(),
+            animal_energy: mean(visible_animals.iter().map(|a| a.energy as f32)),
+            animal_age: mean(visible_animals.iter().map(|a| a.age)),
+        });
+    }
+    let Some(census) = census.as_ref() else {
+        return;
+    };
+
+    let format_mean = |value: Option<f32>| value.map_or("-".to_string(), |v| format!("{:.1}", v));
+
+    egui::Area::new(egui::Id::new("viewport_stats"))
+        .anchor(egui::Align2::RIGHT_BOTTOM, egui::vec2(-10.0, -10.0))
+        .show(contexts.ctx_mut(), |ui| {
+            egui::Frame::popup(ui.style()).show(ui, |ui| {
+                ui.strong("In view");
+                ui.label(format!(
+                    "Plants: {} (avg energy {})",
+                    census.plants,
+                    format_mean(census.plant_energy)
+                ));
+                ui.label(format!(
+                    "Animals: {} (avg energy {}, avg age {}s)",
+                    census.animals,
+                    format_mean(census.animal_energy),
+                    format_mean(census.animal_age)
+                ));
+                if census.animals > 0 {
+                    ui.label(format!(
+                        "Plants per animal: {:.2}",
+                        census.plants as f32 / census.animals as f32
+                    ));
+                }
+            });
+        });
+}
+
+/// World positions of the grid lines crossing `min..max`, `spacing` apart
+fn grid_lines(min: f32, max: f32, spacing: f32) -> impl Iterator<Item = f32> {
+    let first = (min / spacing).ceil() as i64;
+    let last = (max / spacing).floor() as i64;
+    (first..=last).map(move |i| i as f32 * spacing)
+}
+
+/// System to draw the coordinate grid behind the panels and show the world position
+/// under the cursor
+pub fn coordinate_grid_ui(
+    mut contexts: EguiContexts,
+    mut grid: ResMut<CoordinateGrid>,
+    mut death_markers: ResMut<DeathMarkers>,
+    mut trails: ResMut<TrailMap>,
+    mut rings: ResMut<InteractionRings>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    camera: Query<(&Camera, &OrthographicProjection, &GlobalTransform), With<MainCamera>>,
+) {
+    let Ok((camera, projection, camera_transform)) = camera.get_single() else {
+        return;
+    };
+    let ctx = contexts.ctx_mut();
+
+    if grid.visible {
+        let view = visible_world_rect(projection, camera_transform);
+        let mut spacing = grid.spacing.max(1.0);
+        while view.width().max(view.height()) / spacing > GRID_MAX_LINES {
+            spacing *= 2.0;
+        }
+
+        let to_screen = |world: Vec2| {
+            camera
+                .world_to_viewport(camera_transform, world.extend(0.0))
+                .ok()
+                .map(|p| egui::pos2(p.x, p.y))
+        };
+        let painter = ctx.layer_painter(egui::LayerId::background());
+        let font = egui::FontId::monospace(10.0);
+        let line_color = |on_axis: bool| {
+            let alpha = if on_axis { 90 } else { 30 };
+            egui::Color32::from_white_alpha(alpha)
+        };
+
+        for x in grid_lines(view.min.x, view.max.x, spacing) {
+            let (Some(top), Some(bottom)) = (
+                to_screen(Vec2::new(x, view.max.y)),
+                to_screen(Vec2::new(x, view.min.y)),
+            ) else {
+                continue;
+            };
+            painter.line_segment([top, bottom], (1.0, line_color(x == 0.0)));
+            painter.text(
+                top + egui::vec2(3.0, 2.0),
+                egui::Align2::LEFT_TOP,
+                format!("{x:.0}"),
+                font.clone(),
+                egui::Color32::GRAY,
+            );
+        }
+        for y in grid_lines(view.min.y, view.max.y, spacing) {
+            let (Some(left), Some(right)) = (
+                to_screen(Vec2::new(view.min.x, y)),
+                to_screen(Vec2::new(view.max.x, y)),
+            ) else {
+                continue;
+            };
+            painter.line_segment([left, right], (1.0, line_color(y == 0.0)));
+            painter.text(
+                left + egui::vec2(3.0, -2.0),
+                egui::Align2::LEFT_BOTTOM,
+                format!("{y:.0}"),
+                font.clone(),
+                egui::Color32::GRAY,
+            );
+        }
+    }
+
+    let cursor = windows
+        .get_single()
+        .ok()
+        .and_then(|window| window.cursor_position())
+        .and_then(|cursor| camera.viewport_to_world_2d(camera_transform, cursor).ok());
+
+    egui::Area::new(egui::Id::new("cursor_coordinates"))
+        .anchor(egui::Align2::LEFT_BOTTOM, egui::vec2(10.0, -10.0))
+        .show(ctx, |ui| {
+            egui::Frame::popup(ui.style()).show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    match cursor {
+                        Some(world) => ui.monospace(format!("({:.0}, {:.0})", world.x, world.y)),
+                        None => ui.monospace("(-, -)"),
+                    };
+                    if trails.visible
+                        && let Some(world) = cursor
+                    {
+                        ui.monospace(format!("{:.0} visits", trails.visits(world)));
+                    }
+                    ui.checkbox(&mut grid.visible, "Grid");
+                    ui.checkbox(&mut death_markers.enabled, "Deaths")
+                        .on_hover_text("Mark where animals die with a fading X");
+                    ui.checkbox(&mut trails.visible, "Trails")
+                        .on_hover_text("Wear paths into the ground where animals often walk");
+                    ui.checkbox(&mut rings.visible, "Ranges")
+                        .on_hover_text(INTERACTION_RING_LEGEND);
+                });
+            });
+        });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn grid_lines_cover_the_range_on_multiples() {
+        let lines: Vec<f32> = grid_lines(-250.0, 120.0, 100.0).collect();
+        assert_eq!(lines, vec![-200.0, -100.0, 0.0, 100.0]);
+        assert_eq!(grid_lines(10.0, 90.0, 100.0).count(), 0);
+    }
+}
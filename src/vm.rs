@@ -0,0 +1,1410 @@
+//! Genome virtual machine.
+//!
+//! Executes genome words against a `GenomeExecutor` without touching the ECS. Words that
+//! affect the world (movement, eating, splitting, ...) produce `Intent`s which the caller
+//! hands to `apply_intents` (see `animal.rs`), so the VM can be unit tested and run headless.
+
+use crate::config::*;
+use crate::genome::{Genome, GenomeExecutor, Sensors, SightKind, StackOverflowPolicy, Word};
+
+/// Distance reported by smell, animal and vision sensors that detect nothing
+const NO_SCENT_DISTANCE: f32 = 999999.0;
+
+/// World side effect requested by a word
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Intent {
+    /// Move along the body axes: `forward` along the facing direction (negative is
+    /// backward), `right` sideways (negative is left)
+    Move { forward: f32, right: f32 },
+    /// Rotate by the given degrees (positive is counter-clockwise, i.e. left)
+    Turn(f32),
+    /// Eat from a plant within eating distance
+    Eat,
+    /// Reproduce; the split energy check has already passed
+    Split,
+    /// Strike the nearest animal; the attack cost has already been paid
+    Attack,
+    /// Pick up a plant within grabbing distance
+    Grab,
+    /// Drop the carried plant
+    DropItem,
+    /// Change the body hue (degrees in 0..360)
+    SetColor(f32),
+    /// Add pheromone to the grid where the animal stands (0 to the max deposit)
+    DepositPheromone(f32),
+    /// Call out to animals within hearing range
+    Call,
+}
+
+/// Animal state the VM reads and updates while executing
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Body {
+    pub energy: u32,
+    pub split_ratio: f32,
+    /// Seconds lived, read-only
+    pub age: f32,
+    /// Age at which the animal dies of old age, read-only
+    pub lifespan: f32,
+    /// Energy a split costs this animal (see `GenomeLengthCost::split_cost`), read-only
+    pub split_cost: u32,
+}
+
+impl Body {
+    /// Fraction of the lifespan still ahead, from 1 at birth to 0 at death
+    pub fn life_left(&self) -> f32 {
+        if self.lifespan > 0.0 {
+            (1.0 - self.age / self.lifespan).clamp(0.0, 1.0)
+        } else {
+            0.0
+        }
+    }
+}
+
+/// How execution continues after a word
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Flow {
+    /// Continue with the next word
+    Continue,
+    /// Continue at the given position (control flow)
+    Jump(usize),
+    /// The word was skipped (stack underflow, type mismatch or overflow)
+    Skip,
+    /// Fatal error - the animal dies
+    Fatal,
+}
+
+/// Outcome of a single `GenomeVm::step`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StepResult {
+    pub flow: Flow,
+    pub intent: Option<Intent>,
+}
+
+/// Outcome of a frame of execution
+#[derive(Debug, Default)]
+pub struct FrameResult {
+    /// Intents in the order their words executed
+    pub intents: Vec<Intent>,
+    /// Set when a fatal error stopped execution
+    pub fatal: bool,
+}
+
+/// A genome paired with its executor, ready to run
+pub struct GenomeVm<'a> {
+    genome: &'a Genome,
+    executor: &'a mut GenomeExecutor,
+}
+
+impl<'a> GenomeVm<'a> {
+    pub fn new(genome: &'a Genome, executor: &'a mut GenomeExecutor) -> Self {
+        executor.build_jump_table(genome);
+        executor.build_label_table(genome);
+        Self { genome, executor }
+    }
+
+    /// Run words until the frame's instruction budget runs out, the animal splits or a
+    /// fatal error occurs
+    pub fn run_frame(&mut self, body: &mut Body, sensors: &Sensors) -> FrameResult {
+        self.executor.reset_for_frame(body.energy);
+
+        let mut frame = FrameResult::default();
+        // Circular execution: only stops when instruction budget runs out
+        while self.executor.can_execute() {
+            let step = self.step(body, sensors);
+            if let Some(intent) = step.intent {
+                frame.intents.push(intent);
+            }
+            if step.flow == Flow::Fatal {
+                frame.fatal = true;
+                break;
+            }
+            if step.intent == Some(Intent::Split) {
+                break; // Stop execution this frame
+            }
+        }
+        frame
+    }
+
+    /// Execute the word at the instruction pointer and move the pointer on
+    pub fn step(&mut self, body: &mut Body, sensors: &Sensors) -> StepResult {
+        let len = self.genome.words.len();
+        // Ensure IP is within bounds (wrap if necessary)
+        if self.executor.instruction_pointer >= len {
+            self.executor.instruction_pointer = 0;
+        }
+        let ip = self.executor.instruction_pointer;
+        let Some(&word) = self.genome.words.get(ip) else {
+            self.executor.instructions_executed_this_frame += 1;
+            return StepResult {
+                flow: Flow::Skip,
+                intent: None,
+            };
+        };
+
+        let (flow, intent) = execute_word(word, self.genome, self.executor, body, sensors);
+        self.executor.record_trace(ip, word);
+
+        // Overflows under the SkipWord and Kill policies override the word's outcome
+        let flow = match self.executor.take_overflow() {
+            Some(StackOverflowPolicy::Kill) => Flow::Fatal,
+            Some(_) => Flow::Skip,
+            None => flow,
+        };
+
+        match flow {
+            Flow::Continue => self.executor.advance(len),
+            Flow::Jump(target) => {
+                // Ensure target is within bounds (wrap if necessary)
+                self.executor.instruction_pointer = target % len;
+                self.executor.instructions_executed_this_frame += 1;
+            }
+            Flow::Skip => {
+                self.executor.words_skipped += 1;
+                self.executor.advance(len);
+            }
+            Flow::Fatal => {}
+        }
+
+        StepResult { flow, intent }
+    }
+}
+
+/// Pop two floats (`b` on top) and push the result, or skip the word on underflow
+fn binary_float(
+    executor: &mut GenomeExecutor,
+    f: impl FnOnce(&mut GenomeExecutor, f32, f32),
+) -> Flow {
+    if let (Some(b), Some(a)) = (executor.pop_float(), executor.pop_float()) {
+        f(executor, a, b);
+        Flow::Continue
+    } else {
+        Flow::Skip
+    }
+}
+
+/// Pop one float and turn it into a movement or turn intent. NaN has no direction, so it
+/// is consumed without moving (it would otherwise poison the transform).
+fn motion(
+    executor: &mut GenomeExecutor,
+    limit: f32,
+    intent: impl FnOnce(f32) -> Intent,
+) -> (Flow, Option<Intent>) {
+    match executor.pop_float() {
+        Some(value) if value.is_nan() => (Flow::Continue, None),
+        Some(value) => (
+            Flow::Continue,
+            Some(intent((value * 0.01).clamp(-limit, limit))),
+        ),
+        None => (Flow::Skip, None),
+    }
+}
+
+/// Execute a single word
+fn execute_word(
+    word: Word,
+    genome: &Genome,
+    executor: &mut GenomeExecutor,
+    body: &mut Body,
+    sensors: &Sensors,
+) -> (Flow, Option<Intent>) {
+    let flow = match word {
+        // Stack Manipulation
+        Word::Dup => {
+            if let Some(&val) = executor.peek() {
+                executor.push(val);
+                Flow::Continue
+            } else {
+                Flow::Skip
+            }
+        }
+        Word::Drop => {
+            executor.pop();
+            Flow::Continue
+        }
+        Word::Swap => {
+            if let (Some(b), Some(a)) = (executor.pop(), executor.pop()) {
+                executor.stack.push(b);
+                executor.stack.push(a);
+                Flow::Continue
+            } else {
+                Flow::Skip
+            }
+        }
+        Word::Over => {
+            if executor.stack.len() >= 2 {
+                let val = executor.stack[executor.stack.len() - 2];
+                executor.push(val);
+                Flow::Continue
+            } else {
+                Flow::Skip
+            }
+        }
+        Word::Rot => {
+            if executor.stack.len() >= 3 {
+                let c = executor.pop().unwrap();
+                let b = executor.pop().unwrap();
+                let a = executor.pop().unwrap();
+                executor.stack.push(b);
+                executor.stack.push(c);
+                executor.stack.push(a);
+                Flow::Continue
+            } else {
+                Flow::Skip
+            }
+        }
+
+        // Literals
+        Word::PushFloat(val) => {
+            executor.push_float(val);
+            Flow::Continue
+        }
+        Word::PushBool(val) => {
+            executor.push_bool(val);
+            Flow::Continue
+        }
+        Word::PushConst(index) => {
+            executor.push_float(genome.constant(index));
+            Flow::Continue
+        }
+
+        // Sensor Operations
+        Word::SmellFront => {
+            executor.push_float(sensors.plant_scent.front.unwrap_or(NO_SCENT_DISTANCE));
+            Flow::Continue
+        }
+        Word::SmellBack => {
+            executor.push_float(sensors.plant_scent.back.unwrap_or(NO_SCENT_DISTANCE));
+            Flow::Continue
+        }
+        Word::SmellLeft => {
+            executor.push_float(sensors.plant_scent.left.unwrap_or(NO_SCENT_DISTANCE));
+            Flow::Continue
+        }
+        Word::SmellRight => {
+            executor.push_float(sensors.plant_scent.right.unwrap_or(NO_SCENT_DISTANCE));
+            Flow::Continue
+        }
+        Word::SmellAnimalFront => {
+            executor.push_float(sensors.animal_scent.front.unwrap_or(NO_SCENT_DISTANCE));
+            Flow::Continue
+        }
+        Word::SmellAnimalBack => {
+            executor.push_float(sensors.animal_scent.back.unwrap_or(NO_SCENT_DISTANCE));
+            Flow::Continue
+        }
+        Word::SmellAnimalLeft => {
+            executor.push_float(sensors.animal_scent.left.unwrap_or(NO_SCENT_DISTANCE));
+            Flow::Continue
+        }
+        Word::SmellAnimalRight => {
+            executor.push_float(sensors.animal_scent.right.unwrap_or(NO_SCENT_DISTANCE));
+            Flow::Continue
+        }
+        Word::SmellCorpseFront => {
+            executor.push_float(sensors.corpse_scent.front.unwrap_or(NO_SCENT_DISTANCE));
+            Flow::Continue
+        }
+        Word::SmellCorpseBack => {
+            executor.push_float(sensors.corpse_scent.back.unwrap_or(NO_SCENT_DISTANCE));
+            Flow::Continue
+        }
+        Word::SmellCorpseLeft => {
+            executor.push_float(sensors.corpse_scent.left.unwrap_or(NO_SCENT_DISTANCE));
+            Flow::Continue
+        }
+        Word::SmellCorpseRight => {
+            executor.push_float(sensors.corpse_scent.right.unwrap_or(NO_SCENT_DISTANCE));
+            Flow::Continue
+        }
+        Word::AnimalFront => {
+            executor.push_float(sensors.nearest_animal.front.unwrap_or(NO_SCENT_DISTANCE));
+            Flow::Continue
+        }
+        Word::AnimalBack => {
+            executor.push_float(sensors.nearest_animal.back.unwrap_or(NO_SCENT_DISTANCE));
+            Flow::Continue
+        }
+        Word::AnimalLeft => {
+            executor.push_float(sensors.nearest_animal.left.unwrap_or(NO_SCENT_DISTANCE));
+            Flow::Continue
+        }
+        Word::AnimalRight => {
+            executor.push_float(sensors.nearest_animal.right.unwrap_or(NO_SCENT_DISTANCE));
+            Flow::Continue
+        }
+        Word::HearFront => {
+            executor.push_float(sensors.hearing.front.unwrap_or(NO_SCENT_DISTANCE));
+            Flow::Continue
+        }
+        Word::HearBack => {
+            executor.push_float(sensors.hearing.back.unwrap_or(NO_SCENT_DISTANCE));
+            Flow::Continue
+        }
+        Word::HearLeft => {
+            executor.push_float(sensors.hearing.left.unwrap_or(NO_SCENT_DISTANCE));
+            Flow::Continue
+        }
+        Word::HearRight => {
+            executor.push_float(sensors.hearing.right.unwrap_or(NO_SCENT_DISTANCE));
+            Flow::Continue
+        }
+        Word::Energy => {
+            executor.push_float(body.energy as f32);
+            Flow::Continue
+        }
+        Word::LifeLeft => {
+            executor.push_float(body.life_left());
+            Flow::Continue
+        }
+        Word::Touching => {
+            executor.push_bool(sensors.touching_plant || sensors.touching_animal);
+            Flow::Continue
+        }
+        Word::TouchingPlant => {
+            executor.push_bool(sensors.touching_plant);
+            Flow::Continue
+        }
+        Word::TouchingAnimal => {
+            executor.push_bool(sensors.touching_animal);
+            Flow::Continue
+        }
+        Word::Starving => {
+            executor.push_bool(sensors.starving);
+            Flow::Continue
+        }
+        Word::Contacts => {
+            executor.push_float(sensors.contacts as f32);
+            Flow::Continue
+        }
+        Word::SmellRichness => {
+            executor.push_float(sensors.nearest_plant_energy.unwrap_or(0) as f32);
+            Flow::Continue
+        }
+        Word::Crowding => {
+            executor.push_float(sensors.crowding as f32);
+            Flow::Continue
+        }
+        Word::Heading => {
+            executor.push_float(sensors.heading);
+            Flow::Continue
+        }
+        Word::PosX => {
+            executor.push_float(sensors.position.x);
+            Flow::Continue
+        }
+        Word::PosY => {
+            executor.push_float(sensors.position.y);
+            Flow::Continue
+        }
+        Word::CenterDistance => {
+            executor.push_float(sensors.position.length());
+            Flow::Continue
+        }
+        Word::EdgeDistance => {
+            executor.push_float(WORLD_BOUNDS - sensors.position.abs().max_element());
+            Flow::Continue
+        }
+        Word::Speed => {
+            executor.push_float(sensors.speed);
+            Flow::Continue
+        }
+        Word::TurnRate => {
+            executor.push_float(sensors.turn_rate);
+            Flow::Continue
+        }
+        Word::SeeDistance => {
+            let distance = sensors.sight.map(|(distance, _)| distance);
+            executor.push_float(distance.unwrap_or(NO_SCENT_DISTANCE));
+            Flow::Continue
+        }
+        Word::SeePlant => {
+            executor.push_bool(matches!(sensors.sight, Some((_, SightKind::Plant))));
+            Flow::Continue
+        }
+        Word::SeeAnimal => {
+            executor.push_bool(matches!(sensors.sight, Some((_, SightKind::Animal))));
+            Flow::Continue
+        }
+        Word::Pheromone => {
+            executor.push_float(sensors.pheromone);
+            Flow::Continue
+        }
+
+        // Arithmetic Operations
+        Word::Add => binary_float(executor, |e, a, b| e.push_result(a + b)),
+        Word::Sub => binary_float(executor, |e, a, b| e.push_result(a - b)),
+        Word::Mul => binary_float(executor, |e, a, b| e.push_result(a * b)),
+        // Division by zero returns 0
+        Word::Div => binary_float(executor, |e, a, b| {
+            e.push_result(if b != 0.0 { a / b } else { 0.0 })
+        }),
+
+        // Comparison Operations
+        Word::Lt => binary_float(executor, |e, a, b| e.push_bool(a < b)),
+        Word::Gt => binary_float(executor, |e, a, b| e.push_bool(a > b)),
+        // Float equality with tolerance
+        Word::Eq => binary_float(executor, |e, a, b| e.push_bool((a - b).abs() < 0.001)),
+
+        // Logic Operations
+        Word::And => {
+            if let (Some(b), Some(a)) = (executor.pop_bool(), executor.pop_bool()) {
+                executor.push_bool(a && b);
+                Flow::Continue
+            } else {
+                Flow::Skip
+            }
+        }
+        Word::Or => {
+            if let (Some(b), Some(a)) = (executor.pop_bool(), executor.pop_bool()) {
+                executor.push_bool(a || b);
+                Flow::Continue
+            } else {
+                Flow::Skip
+            }
+        }
+        Word::Not => {
+            if let Some(a) = executor.pop_bool() {
+                executor.push_bool(!a);
+                Flow::Continue
+            } else {
+                Flow::Skip
+            }
+        }
+
+        // Control Flow
+        Word::If => {
+            let condition = executor.pop_bool().unwrap_or(false);
+            let current_pos = executor.instruction_pointer;
+
+            // Find matching Then/Else in jump table
+            match executor
+                .jump_table
+                .iter()
+                .find(|(if_pos, _, _)| *if_pos == current_pos)
+            {
+                // Condition false: jump past the else, or past the then
+                Some((_, else_pos, then_pos)) if !condition => {
+                    Flow::Jump(else_pos.unwrap_or(*then_pos) + 1)
+                }
+                // Condition true, or no matching then: continue into the if branch
+                _ => Flow::Continue,
+            }
+        }
+        Word::Else => {
+            // When we hit else, we came from the IF branch, so skip to THEN
+            let current_pos = executor.instruction_pointer;
+            executor
+                .jump_table
+                .iter()
+                .find(|(_, else_pos, _)| *else_pos == Some(current_pos))
+                .map_or(Flow::Continue, |(_, _, then_pos)| Flow::Jump(*then_pos + 1))
+        }
+        // Then is just a marker, continue execution
+        Word::Then => Flow::Continue,
+
+        // Movement Actions
+        Word::MoveForward => {
+            return motion(executor, MAX_MOVEMENT_SPEED, |d| Intent::Move {
+                forward: d,
+                right: 0.0,
+            });
+        }
+        Word::MoveBackward => {
+            return motion(executor, MAX_MOVEMENT_SPEED, |d| Intent::Move {
+                forward: -d,
+                right: 0.0,
+            });
+        }
+        Word::TurnLeft => return motion(executor, MAX_ANGULAR_VELOCITY, Intent::Turn),
+        Word::TurnRight => return motion(executor, MAX_ANGULAR_VELOCITY, |d| Intent::Turn(-d)),
+        Word::StrafeLeft => {
+            return motion(executor, MAX_STRAFE_SPEED, |d| Intent::Move {
+                forward: 0.0,
+                right: -d,
+            });
+        }
+        Word::StrafeRight => {
+            return motion(executor, MAX_STRAFE_SPEED, |d| Intent::Move {
+                forward: 0.0,
+                right: d,
+            });
+        }
+
+        // Resource Actions
+        Word::Eat => return (Flow::Continue, Some(Intent::Eat)),
+        Word::Split => {
+            // Without enough energy the split is treated as Nop
+            let intent = (body.energy >= body.split_cost).then_some(Intent::Split);
+            return (Flow::Continue, intent);
+        }
+        Word::SplitRatio => {
+            if let Some(ratio) = executor.pop_float() {
+                if ratio.is_finite() {
+                    body.split_ratio = ratio.clamp(MIN_SPLIT_RATIO, MAX_SPLIT_RATIO);
+                }
+                Flow::Continue
+            } else {
+                Flow::Skip
+            }
+        }
+        Word::Attack => {
+            let intent = (body.energy > ATTACK_ENERGY_COST).then(|| {
+                body.energy -= ATTACK_ENERGY_COST;
+                Intent::Attack
+            });
+            return (Flow::Continue, intent);
+        }
+        Word::Grab => return (Flow::Continue, Some(Intent::Grab)),
+        Word::DropItem => return (Flow::Continue, Some(Intent::DropItem)),
+        Word::DepositPheromone => match executor.pop_float() {
+            Some(amount) if amount > 0.0 => {
+                // NaN fails the guard above; infinity is capped like any large amount
+                let amount = amount.min(PHEROMONE_MAX_DEPOSIT);
+                return (Flow::Continue, Some(Intent::DepositPheromone(amount)));
+            }
+            Some(_) => Flow::Continue,
+            None => Flow::Skip,
+        },
+        Word::Call => return (Flow::Continue, Some(Intent::Call)),
+
+        // Phenotype Actions
+        Word::SetColor => match executor.pop_float() {
+            Some(value) if value.is_finite() => {
+                // rem_euclid rounds tiny negative values up to 360 itself
+                let hue = value.rem_euclid(360.0);
+                let hue = if hue >= 360.0 { 0.0 } else { hue };
+                return (Flow::Continue, Some(Intent::SetColor(hue)));
+            }
+            Some(_) => Flow::Continue,
+            None => Flow::Skip,
+        },
+
+        // Labels (just markers, act like Nop)
+        Word::Label0 | Word::Label1 | Word::Label2 | Word::Label3 => Flow::Continue,
+
+        // Jumps (jump to label position; a missing label is treated as Nop)
+        Word::Jump0 => executor.label_table[0].map_or(Flow::Continue, Flow::Jump),
+        Word::Jump1 => executor.label_table[1].map_or(Flow::Continue, Flow::Jump),
+        Word::Jump2 => executor.label_table[2].map_or(Flow::Continue, Flow::Jump),
+        Word::Jump3 => executor.label_table[3].map_or(Flow::Continue, Flow::Jump),
+
+        // Special
+        Word::Nop => Flow::Continue,
+    };
+    (flow, None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::genome::{Diet, MutationRates, Quadrants, StackMode, StackValue, VmSettings};
+    use StackValue::{Bool, Float};
+
+    fn genome(source: &str) -> Genome {
+        Genome::parse(source).expect("test genome should parse")
+    }
+
+    fn fresh_body() -> Body {
+        Body {
+            energy: 100,
+            split_ratio: DEFAULT_SPLIT_RATIO,
+            age: 0.0,
+            lifespan: MAX_LIFESPAN,
+            split_cost: SPLIT_ENERGY_COST,
+        }
+    }
+
+    /// Step through `source` once per word, returning the executor, the body and every
+    /// intent produced
+    fn run_with(
+        source: &str,
+        mut body: Body,
+        sensors: &Sensors,
+    ) -> (GenomeExecutor, Body, Vec<Intent>) {
+        let genome = genome(source);
+        let mut executor = GenomeExecutor::new(body.energy);
+        let mut intents = Vec::new();
+        let mut vm = GenomeVm::new(&genome, &mut executor);
+        for _ in 0..genome.words.len() {
+            intents.extend(vm.step(&mut body, sensors).intent);
+        }
+        (executor, body, intents)
+    }
+
+    fn run(source: &str) -> (GenomeExecutor, Vec<Intent>) {
+        let (executor, _, intents) = run_with(source, fresh_body(), &Sensors::default());
+        (executor, intents)
+    }
+
+    fn stack(source: &str) -> Vec<StackValue> {
+        run(source).0.stack
+    }
+
+    fn intents(source: &str) -> Vec<Intent> {
+        run(source).1
+    }
+
+    /// Execute a single step and return the instruction pointer afterwards
+    fn step_ip(genome: &Genome, executor: &mut GenomeExecutor) -> usize {
+        GenomeVm::new(genome, executor).step(&mut fresh_body(), &Sensors::default());
+        executor.instruction_pointer
+    }
+
+    #[test]
+    fn dup() {
+        assert_eq!(stack("1.0 dup"), vec![Float(1.0), Float(1.0)]);
+        let (executor, _) = run("dup");
+        assert!(executor.stack.is_empty());
+        assert_eq!(executor.words_skipped, 1);
+    }
+
+    #[test]
+    fn drop() {
+        assert_eq!(stack("1.0 2.0 drop"), vec![Float(1.0)]);
+        assert_eq!(run("drop").0.words_skipped, 0);
+    }
+
+    #[test]
+    fn swap() {
+        assert_eq!(stack("1.0 true swap"), vec![Bool(true), Float(1.0)]);
+        assert_eq!(run("1.0 swap").0.words_skipped, 1);
+    }
+
+    #[test]
+    fn over() {
+        assert_eq!(
+            stack("1.0 2.0 over"),
+            vec![Float(1.0), Float(2.0), Float(1.0)]
+        );
+        assert_eq!(run("1.0 over").0.words_skipped, 1);
+    }
+
+    #[test]
+    fn rot() {
+        assert_eq!(
+            stack("1.0 2.0 3.0 rot"),
+            vec![Float(2.0), Float(3.0), Float(1.0)]
+        );
+        assert_eq!(run("1.0 2.0 rot").0.words_skipped, 1);
+    }
+
+    #[test]
+    fn literals() {
+        assert_eq!(stack("-2.5"), vec![Float(-2.5)]);
+        assert_eq!(stack("true false"), vec![Bool(true), Bool(false)]);
+        assert_eq!(stack(".constants 4.0 8.0\nconst1"), vec![Float(8.0)]);
+    }
+
+    #[test]
+    fn smell_sensors() {
+        let sensors = Sensors {
+            plant_scent: Quadrants {
+                front: Some(1.0),
+                back: Some(2.0),
+                left: Some(3.0),
+                right: Some(4.0),
+            },
+            ..Sensors::default()
+        };
+        let (executor, _, _) = run_with(
+            "smell-front smell-back smell-left smell-right",
+            fresh_body(),
+            &sensors,
+        );
+        assert_eq!(
+            executor.stack,
+            vec![Float(1.0), Float(2.0), Float(3.0), Float(4.0)]
+        );
+        assert_eq!(stack("smell-front"), vec![Float(NO_SCENT_DISTANCE)]);
+    }
+
+    #[test]
+    fn scent_channels_are_smelled_separately() {
+        let sensors = Sensors {
+            plant_scent: Quadrants::splat(Some(1.0)),
+            animal_scent: Quadrants {
+                left: Some(2.0),
+                ..Quadrants::default()
+            },
+            corpse_scent: Quadrants {
+                back: Some(3.0),
+                ..Quadrants::default()
+            },
+            ..Sensors::default()
+        };
+        let (executor, _, _) = run_with(
+            "smell-left smell-animal-left smell-animal-back smell-corpse-back smell-corpse-front",
+            fresh_body(),
+            &sensors,
+        );
+        assert_eq!(
+            executor.stack,
+            vec![
+                Float(1.0),
+                Float(2.0),
+                Float(NO_SCENT_DISTANCE),
+                Float(3.0),
+                Float(NO_SCENT_DISTANCE)
+            ]
+        );
+    }
+
+    #[test]
+    fn animal_sensors() {
+        let sensors = Sensors {
+            nearest_animal: Quadrants {
+                front: Some(5.0),
+                right: Some(7.0),
+                ..Quadrants::default()
+            },
+            ..Sensors::default()
+        };
+        let (executor, _, _) = run_with(
+            "animal-front animal-back animal-left animal-right",
+            fresh_body(),
+            &sensors,
+        );
+        assert_eq!(
+            executor.stack,
+            vec![
+                Float(5.0),
+                Float(NO_SCENT_DISTANCE),
+                Float(NO_SCENT_DISTANCE),
+                Float(7.0)
+            ]
+        );
+    }
+
+    #[test]
+    fn hearing() {
+        let sensors = Sensors {
+            hearing: Quadrants {
+                back: Some(40.0),
+                left: Some(12.0),
+                ..Quadrants::default()
+            },
+            ..Sensors::default()
+        };
+        let (executor, _, _) = run_with(
+            "hear-front hear-back hear-left hear-right",
+            fresh_body(),
+            &sensors,
+        );
+        assert_eq!(
+            executor.stack,
+            vec![
+                Float(NO_SCENT_DISTANCE),
+                Float(40.0),
+                Float(12.0),
+                Float(NO_SCENT_DISTANCE)
+            ]
+        );
+        assert_eq!(intents("call call"), vec![Intent::Call, Intent::Call]);
+    }
+
+    #[test]
+    fn energy() {
+        assert_eq!(stack("energy"), vec![Float(100.0)]);
+    }
+
+    #[test]
+    fn life_left() {
+        assert_eq!(stack("life-left"), vec![Float(1.0)]);
+
+        let old = Body {
+            age: MAX_LIFESPAN * 0.75,
+            ..fresh_body()
+        };
+        let (executor, _, _) = run_with("life-left", old, &Sensors::default());
+        assert_eq!(executor.stack, vec![Float(0.25)]);
+
+        let overdue = Body {
+            age: MAX_LIFESPAN * 2.0,
+            ..fresh_body()
+        };
+        let (executor, _, _) = run_with("life-left", overdue, &Sensors::default());
+        assert_eq!(executor.stack, vec![Float(0.0)]);
+    }
+
+    #[test]
+    fn touch_sensors() {
+        let source = "touching touching-plant touching-animal";
+        assert_eq!(stack(source), vec![Bool(false); 3]);
+
+        let sensors = Sensors {
+            touching_animal: true,
+            ..Sensors::default()
+        };
+        let (executor, _, _) = run_with(source, fresh_body(), &sensors);
+        assert_eq!(executor.stack, vec![Bool(true), Bool(false), Bool(true)]);
+    }
+
+    #[test]
+    fn contacts() {
+        assert_eq!(stack("contacts"), vec![Float(0.0)]);
+
+        let sensors = Sensors {
+            contacts: 3,
+            ..Sensors::default()
+        };
+        let (executor, _, _) = run_with("contacts", fresh_body(), &sensors);
+        assert_eq!(executor.stack, vec![Float(3.0)]);
+    }
+
+    #[test]
+    fn scalar_sensor_words() {
+        use bevy::math::Vec2;
+
+        let sensors = Sensors {
+            nearest_plant_energy: Some(35),
+            crowding: 6,
+            heading: 0.25,
+            position: Vec2::new(-30.0, 40.0),
+            speed: 5.0,
+            turn_rate: -0.1,
+            ..Sensors::default()
+        };
+        // Each word with its reading from blank sensors and from the ones above
+        for (word, blank, sensed) in [
+            ("smell-richness", 0.0, 35.0),
+            ("crowding", 0.0, 6.0),
+            ("heading", 0.0, 0.25),
+            ("pos-x", 0.0, -30.0),
+            ("pos-y", 0.0, 40.0),
+            ("center-distance", 0.0, 50.0),
+            ("edge-distance", WORLD_BOUNDS, WORLD_BOUNDS - 40.0),
+            ("speed", 0.0, 5.0),
+            ("turn-rate", 0.0, -0.1),
+        ] {
+            assert_eq!(stack(word), vec![Float(blank)], "{word}");
+            let (executor, _, _) = run_with(word, fresh_body(), &sensors);
+            assert_eq!(executor.stack, vec![Float(sensed)], "{word}");
+        }
+    }
+
+    #[test]
+    fn heading() {
+        use crate::animal::Handedness;
+        use bevy::math::Quat;
+
+        // Turning left a quarter turn raises the heading by a quarter, in both worlds
+        for handedness in Handedness::ALL {
+            assert_eq!(handedness.heading(Quat::IDENTITY), 0.0);
+            let left = handedness.turn(90.0);
+            assert!(
+                (handedness.heading(left) - 0.25).abs() < 1e-5,
+                "{handedness}"
+            );
+            let right = handedness.turn(-90.0);
+            assert!(
+                (handedness.heading(right) - 0.75).abs() < 1e-5,
+                "{handedness}"
+            );
+        }
+    }
+
+    #[test]
+    fn edge_distance() {
+        use bevy::math::Vec2;
+
+        // The nearest edge is the one across the larger coordinate; past it reads negative
+        for (position, distance) in [
+            (Vec2::ZERO, WORLD_BOUNDS),
+            (Vec2::new(-30.0, WORLD_BOUNDS - 40.0), 40.0),
+            (Vec2::new(WORLD_BOUNDS + 5.0, 0.0), -5.0),
+            (Vec2::new(-WORLD_BOUNDS - 5.0, WORLD_BOUNDS + 8.0), -8.0),
+        ] {
+            let sensors = Sensors {
+                position,
+                ..Sensors::default()
+            };
+            let (executor, _, _) = run_with("edge-distance", fresh_body(), &sensors);
+            assert_eq!(executor.stack, vec![Float(distance)], "{position}");
+        }
+    }
+
+    #[test]
+    fn motion_tracking() {
+        use bevy::math::Vec2;
+
+        // A fresh animal reads as still, wherever it starts
+        let mut sensors = Sensors::default();
+        sensors.track_motion(Vec2::new(100.0, 0.0), 0.95);
+        assert_eq!((sensors.speed, sensors.turn_rate), (0.0, 0.0));
+
+        // Turning left across a full turn reads as a small left turn, not a large right one
+        sensors.track_motion(Vec2::new(103.0, 4.0), 0.05);
+        assert_eq!(sensors.speed, 5.0);
+        assert!(
+            (sensors.turn_rate - 0.1).abs() < 1e-5,
+            "{}",
+            sensors.turn_rate
+        );
+
+        sensors.track_motion(Vec2::new(103.0, 4.0), 0.0);
+        assert_eq!(sensors.speed, 0.0);
+        assert!((sensors.turn_rate + 0.05).abs() < 1e-5);
+    }
+
+    #[test]
+    fn starving() {
+        assert_eq!(stack("starving"), vec![Bool(false)]);
+
+        let sensors = Sensors {
+            starving: true,
+            ..Sensors::default()
+        };
+        let (executor, _, _) = run_with("starving", fresh_body(), &sensors);
+        assert_eq!(executor.stack, vec![Bool(true)]);
+    }
+
+    #[test]
+    fn vision_sensors() {
+        let source = "see-distance see-plant see-animal";
+        assert_eq!(
+            stack(source),
+            vec![Float(NO_SCENT_DISTANCE), Bool(false), Bool(false)]
+        );
+
+        let sensors = Sensors {
+            sight: Some((42.0, SightKind::Animal)),
+            ..Sensors::default()
+        };
+        let (executor, _, _) = run_with(source, fresh_body(), &sensors);
+        assert_eq!(executor.stack, vec![Float(42.0), Bool(false), Bool(true)]);
+    }
+
+    #[test]
+    fn arithmetic() {
+        assert_eq!(stack("7.0 2.0 +"), vec![Float(9.0)]);
+        assert_eq!(stack("7.0 2.0 -"), vec![Float(5.0)]);
+        assert_eq!(stack("7.0 2.0 *"), vec![Float(14.0)]);
+        assert_eq!(stack("7.0 2.0 /"), vec![Float(3.5)]);
+        assert_eq!(stack("7.0 0.0 /"), vec![Float(0.0)]);
+        assert_eq!(run("1.0 true +").0.words_skipped, 1);
+    }
+
+    #[test]
+    fn comparison() {
+        assert_eq!(stack("1.0 2.0 <"), vec![Bool(true)]);
+        assert_eq!(stack("1.0 2.0 >"), vec![Bool(false)]);
+        assert_eq!(stack("1.0 1.0005 ="), vec![Bool(true)]);
+        assert_eq!(stack("1.0 1.1 ="), vec![Bool(false)]);
+        assert_eq!(run("1.0 <").0.words_skipped, 1);
+    }
+
+    #[test]
+    fn logic() {
+        assert_eq!(stack("true false and"), vec![Bool(false)]);
+        assert_eq!(stack("true false or"), vec![Bool(true)]);
+        assert_eq!(stack("true not"), vec![Bool(false)]);
+        assert_eq!(run("1.0 not").0.words_skipped, 1);
+    }
+
+    #[test]
+    fn if_then() {
+        let genome = genome("if 1.0 then nop");
+
+        let mut executor = GenomeExecutor::new(100);
+        executor.push_bool(true);
+        assert_eq!(step_ip(&genome, &mut executor), 1);
+
+        let mut executor = GenomeExecutor::new(100);
+        executor.push_bool(false);
+        assert_eq!(step_ip(&genome, &mut executor), 3);
+
+        // An empty stack counts as false
+        let mut executor = GenomeExecutor::new(100);
+        assert_eq!(step_ip(&genome, &mut executor), 3);
+    }
+
+    #[test]
+    fn if_else_then() {
+        let genome = genome("if 1.0 else 2.0 then nop");
+
+        let mut executor = GenomeExecutor::new(100);
+        executor.push_bool(false);
+        assert_eq!(step_ip(&genome, &mut executor), 3);
+
+        // Reaching else from the if branch skips past then
+        let mut executor = GenomeExecutor::new(100);
+        executor.instruction_pointer = 2;
+        assert_eq!(step_ip(&genome, &mut executor), 5);
+    }
+
+    #[test]
+    fn then_and_labels_are_markers() {
+        let (executor, intents) = run("then label0 label1 label2 label3 nop");
+        assert!(executor.stack.is_empty());
+        assert!(intents.is_empty());
+        assert_eq!(executor.instruction_pointer, 0);
+    }
+
+    #[test]
+    fn jumps() {
+        let genome = genome("label0 label1 label2 label3 jump0 jump1 jump2 jump3");
+        for (ip, target) in [(4, 0), (5, 1), (6, 2), (7, 3)] {
+            let mut executor = GenomeExecutor::new(100);
+            executor.instruction_pointer = ip;
+            assert_eq!(step_ip(&genome, &mut executor), target);
+        }
+
+        // A jump without its label is a Nop
+        let genome = self::genome("jump2 nop");
+        let mut executor = GenomeExecutor::new(100);
+        assert_eq!(step_ip(&genome, &mut executor), 1);
+    }
+
+    #[test]
+    fn movement() {
+        assert_eq!(
+            intents("25.0 move-forward 1000.0 move-backward"),
+            vec![
+                Intent::Move {
+                    forward: 0.25,
+                    right: 0.0
+                },
+                Intent::Move {
+                    forward: -MAX_MOVEMENT_SPEED,
+                    right: 0.0
+                },
+            ]
+        );
+        assert_eq!(
+            intents("12.5 strafe-left 1000.0 strafe-right"),
+            vec![
+                Intent::Move {
+                    forward: 0.0,
+                    right: -0.125
+                },
+                Intent::Move {
+                    forward: 0.0,
+                    right: MAX_STRAFE_SPEED
+                },
+            ]
+        );
+        assert_eq!(run("move-forward").0.words_skipped, 1);
+    }
+
+    #[test]
+    fn turning() {
+        assert_eq!(
+            intents("100.0 turn-left 100000.0 turn-right"),
+            vec![Intent::Turn(1.0), Intent::Turn(-MAX_ANGULAR_VELOCITY)]
+        );
+        assert_eq!(run("true turn-left").0.words_skipped, 1);
+    }
+
+    #[test]
+    fn left_and_right_agree_between_sensors_and_motion() {
+        use crate::animal::Handedness;
+        use bevy::math::{Quat, Vec2};
+
+        // Words: left turns are positive, right strafes are positive
+        assert_eq!(intents("100.0 turn-left"), vec![Intent::Turn(1.0)]);
+        assert_eq!(
+            intents("100000.0 strafe-right"),
+            vec![Intent::Move {
+                forward: 0.0,
+                right: MAX_STRAFE_SPEED
+            }]
+        );
+
+        // World: facing +Y, the right sensor and right strafes look along +X and a
+        // positive turn swings forward towards -X, so turning left faces the left sensor
+        let facing_up = Quat::IDENTITY;
+        for handedness in Handedness::ALL {
+            let right = handedness.right(facing_up);
+            let turned = Handedness::forward(handedness.turn(90.0) * facing_up);
+            assert!(turned.distance(-right) < 1e-5, "{handedness}");
+        }
+        assert_eq!(Handedness::Standard.right(facing_up), Vec2::X);
+        assert_eq!(Handedness::Mirrored.right(facing_up), Vec2::NEG_X);
+    }
+
+    #[test]
+    fn eat_grab_and_drop() {
+        assert_eq!(
+            intents("eat grab drop-item"),
+            vec![Intent::Eat, Intent::Grab, Intent::DropItem]
+        );
+    }
+
+    #[test]
+    fn split() {
+        assert_eq!(intents("split"), vec![Intent::Split]);
+
+        let poor = Body {
+            energy: SPLIT_ENERGY_COST - 1,
+            ..fresh_body()
+        };
+        let (_, _, intents) = run_with("split", poor, &Sensors::default());
+        assert!(intents.is_empty());
+
+        // Long genomes pay the same length-scaled cost the split itself charges
+        let source = format!("split{}", " nop".repeat(40));
+        let length_cost = crate::animal::GenomeLengthCost {
+            split_per_word: 0.5,
+            ..Default::default()
+        };
+        let cost = length_cost.split_cost(&genome(&source));
+        assert_eq!(cost, SPLIT_ENERGY_COST + 21);
+        for (energy, splits) in [(SPLIT_ENERGY_COST, false), (cost - 1, false), (cost, true)] {
+            let body = Body {
+                energy,
+                split_cost: cost,
+                ..fresh_body()
+            };
+            let (_, _, intents) = run_with(&source, body, &Sensors::default());
+            assert_eq!(intents.contains(&Intent::Split), splits, "energy {energy}");
+        }
+    }
+
+    #[test]
+    fn split_ratio() {
+        let (_, body, _) = run_with("0.3 split-ratio", fresh_body(), &Sensors::default());
+        assert_eq!(body.split_ratio, 0.3);
+
+        let (_, body, _) = run_with("5.0 split-ratio", fresh_body(), &Sensors::default());
+        assert_eq!(body.split_ratio, MAX_SPLIT_RATIO);
+
+        let (executor, _, _) = run_with("split-ratio", fresh_body(), &Sensors::default());
+        assert_eq!(executor.words_skipped, 1);
+    }
+
+    #[test]
+    fn attack() {
+        let (_, body, intents) = run_with("attack", fresh_body(), &Sensors::default());
+        assert_eq!(intents, vec![Intent::Attack]);
+        assert_eq!(body.energy, 100 - ATTACK_ENERGY_COST);
+
+        let poor = Body {
+            energy: ATTACK_ENERGY_COST,
+            ..fresh_body()
+        };
+        let (_, body, intents) = run_with("attack", poor, &Sensors::default());
+        assert!(intents.is_empty());
+        assert_eq!(body.energy, ATTACK_ENERGY_COST);
+    }
+
+    #[test]
+    fn pheromones() {
+        let sensors = Sensors {
+            pheromone: 12.5,
+            ..Sensors::default()
+        };
+        let (executor, _, _) = run_with("pheromone", fresh_body(), &sensors);
+        assert_eq!(executor.stack, vec![Float(12.5)]);
+
+        assert_eq!(
+            intents("2.5 deposit-pheromone"),
+            vec![Intent::DepositPheromone(2.5)]
+        );
+        assert_eq!(
+            intents("1000.0 deposit-pheromone"),
+            vec![Intent::DepositPheromone(PHEROMONE_MAX_DEPOSIT)]
+        );
+        assert!(intents("-1.0 deposit-pheromone").is_empty());
+        assert_eq!(run("deposit-pheromone").0.words_skipped, 1);
+    }
+
+    #[test]
+    fn set_color() {
+        assert_eq!(intents("400.0 set-color"), vec![Intent::SetColor(40.0)]);
+        assert_eq!(intents("-90.0 set-color"), vec![Intent::SetColor(270.0)]);
+        assert_eq!(intents("-0.00001 set-color"), vec![Intent::SetColor(0.0)]);
+        assert_eq!(run("set-color").0.words_skipped, 1);
+
+        let genome = Genome {
+            words: vec![Word::PushFloat(f32::NAN), Word::SetColor],
+            constants: Vec::new(),
+            rates: MutationRates::default(),
+            diet: Diet::default(),
+            body_size: DEFAULT_BODY_SIZE,
+        };
+        let mut executor = GenomeExecutor::new(100);
+        let mut vm = GenomeVm::new(&genome, &mut executor);
+        vm.step(&mut fresh_body(), &Sensors::default());
+        let step = vm.step(&mut fresh_body(), &Sensors::default());
+        assert_eq!(step.flow, Flow::Continue);
+        assert_eq!(step.intent, None);
+    }
+
+    #[test]
+    fn overflow_policies() {
+        let genome = genome("1.0 2.0");
+        let mut executor = GenomeExecutor::new(100);
+        executor.apply_settings(&VmSettings {
+            stack_capacity: 1,
+            overflow_policy: StackOverflowPolicy::Kill,
+            ..Default::default()
+        });
+        let frame =
+            GenomeVm::new(&genome, &mut executor).run_frame(&mut fresh_body(), &Sensors::default());
+        assert!(frame.fatal);
+
+        let mut executor = GenomeExecutor::new(100);
+        executor.apply_settings(&VmSettings {
+            stack_capacity: 1,
+            overflow_policy: StackOverflowPolicy::SkipWord,
+            ..Default::default()
+        });
+        let mut vm = GenomeVm::new(&genome, &mut executor);
+        vm.step(&mut fresh_body(), &Sensors::default());
+        let step = vm.step(&mut fresh_body(), &Sensors::default());
+        assert_eq!(step.flow, Flow::Skip);
+        assert_eq!(executor.stack, vec![Float(1.0)]);
+    }
+
+    #[test]
+    fn safe_math_keeps_results_finite() {
+        let genome = genome("999999.0 999999.0 * NaN 1.0 + 2.0 3.0 *");
+        let mut executor = GenomeExecutor::new(100);
+        executor.apply_settings(&VmSettings {
+            safe_math: true,
+            ..Default::default()
+        });
+        let mut vm = GenomeVm::new(&genome, &mut executor);
+        for _ in 0..genome.words.len() {
+            vm.step(&mut fresh_body(), &Sensors::default());
+        }
+        assert_eq!(
+            executor.stack,
+            vec![Float(SAFE_MATH_LIMIT), Float(0.0), Float(6.0)]
+        );
+        assert_eq!(executor.saturations, 2);
+
+        let (executor, _) = run("999999.0 999999.0 *");
+        assert_eq!(executor.stack, vec![Float(999999.0 * 999999.0)]);
+        assert_eq!(executor.saturations, 0);
+    }
+
+    #[test]
+    fn typed_stacks_skip_no_words_for_type_mismatches() {
+        // The mixed stack pops the bool and skips the move
+        let (executor, intents) = run("50.0 true move-forward");
+        assert!(intents.is_empty());
+        assert_eq!(executor.words_skipped, 1);
+
+        let genome = genome("50.0 true move-forward 1.0 2.0 < not 3.0");
+        let mut executor = GenomeExecutor::new(100);
+        executor.apply_settings(&VmSettings {
+            stack_mode: StackMode::Typed,
+            ..Default::default()
+        });
+        let mut intents = Vec::new();
+        let mut vm = GenomeVm::new(&genome, &mut executor);
+        for _ in 0..genome.words.len() {
+            intents.extend(vm.step(&mut fresh_body(), &Sensors::default()).intent);
+        }
+        assert_eq!(
+            intents,
+            vec![Intent::Move {
+                forward: 0.5,
+                right: 0.0
+            }]
+        );
+        assert_eq!(executor.words_skipped, 0);
+        assert_eq!(executor.stack, vec![Float(3.0)]);
+        assert_eq!(executor.bool_stack, vec![true, false]);
+
+        // Switching back stacks the bools on top of the floats
+        executor.apply_settings(&VmSettings::default());
+        assert_eq!(executor.stack, vec![Float(3.0), Bool(true), Bool(false)]);
+        assert!(executor.bool_stack.is_empty());
+    }
+
+    #[test]
+    fn run_frame_budget_and_split() {
+        let genome = genome("1.0 drop");
+        let mut executor = GenomeExecutor::new(100);
+        let frame =
+            GenomeVm::new(&genome, &mut executor).run_frame(&mut fresh_body(), &Sensors::default());
+        assert!(!frame.fatal);
+        assert_eq!(
+            executor.instructions_executed_this_frame,
+            MAX_INSTRUCTIONS_PER_FRAME
+        );
+
+        let genome = self::genome("eat split eat");
+        let mut executor = GenomeExecutor::new(100);
+        let frame =
+            GenomeVm::new(&genome, &mut executor).run_frame(&mut fresh_body(), &Sensors::default());
+        assert_eq!(frame.intents, vec![Intent::Eat, Intent::Split]);
+        assert_eq!(executor.instruction_pointer, 2);
+    }
+
+    #[test]
+    fn exported_trace_collects_every_word_once() {
+        let genome = genome("1.0 drop");
+        let mut executor = GenomeExecutor::new(100);
+        executor.set_exporting(true);
+        GenomeVm::new(&genome, &mut executor).run_frame(&mut fresh_body(), &Sensors::default());
+
+        let entries = executor.take_exported();
+        assert_eq!(entries.len(), MAX_INSTRUCTIONS_PER_FRAME as usize);
+        assert_eq!(entries[0].word, Word::PushFloat(1.0));
+        assert_eq!(entries[0].stack_top, Some(Float(1.0)));
+        assert_eq!(entries[1].instruction_pointer, 1);
+        assert!(executor.take_exported().is_empty());
+
+        // The in-app trace is independent of the export
+        assert!(executor.trace.is_none());
+        executor.set_exporting(false);
+        GenomeVm::new(&genome, &mut executor).run_frame(&mut fresh_body(), &Sensors::default());
+        assert!(executor.take_exported().is_empty());
+    }
+
+    #[test]
+    fn watch_keeps_the_first_matching_word() {
+        let genome = genome("1.0 600.0 700.0 drop drop drop");
+        let mut executor = GenomeExecutor::new(100);
+        let mut vm = GenomeVm::new(&genome, &mut executor);
+        vm.step(&mut fresh_body(), &Sensors::default());
+        assert!(vm.executor.take_watch_hit().is_none());
+
+        vm.executor.watch = Some("> 500".parse().unwrap());
+        for _ in 0..5 {
+            vm.step(&mut fresh_body(), &Sensors::default());
+        }
+        let hit = executor
+            .take_watch_hit()
+            .expect("600 should trigger the watch");
+        assert_eq!(hit.instruction_pointer, 1);
+        assert_eq!(hit.word, Word::PushFloat(600.0));
+        assert!(executor.take_watch_hit().is_none());
+    }
+
+    #[test]
+    fn typed_traces_record_the_stack_each_word_wrote() {
+        let genome = genome("1.0 2.0 < not 3.0");
+        let mut executor = GenomeExecutor::new(100);
+        executor.apply_settings(&VmSettings {
+            stack_mode: StackMode::Typed,
+            ..Default::default()
+        });
+        executor.set_tracing(true);
+        let mut vm = GenomeVm::new(&genome, &mut executor);
+        for _ in 0..genome.words.len() {
+            vm.step(&mut fresh_body(), &Sensors::default());
+        }
+
+        let tops: Vec<_> = executor
+            .trace
+            .as_ref()
+            .unwrap()
+            .iter()
+            .map(|entry| entry.stack_top)
+            .collect();
+        assert_eq!(
+            tops,
+            vec![
+                Some(Float(1.0)),
+                Some(Float(2.0)),
+                Some(Bool(true)),
+                Some(Bool(false)),
+                Some(Float(3.0)),
+            ]
+        );
+    }
+}
@@ -0,0 +1,213 @@
+//! Soak test for the genome VM.
+//!
+//! Runs thousands of random genomes for many frames against random sensor readings and VM
+//! settings, checking after every step that the VM stays within its limits. Words come from
+//! `Word::random`, so new words are fuzzed as soon as mutation can produce them, plus the
+//! stack and logic words it never produces (they can still appear in hand-written genomes).
+
+use crate::config::*;
+use crate::genome::{
+    Diet, Genome, GenomeExecutor, MutationRates, Quadrants, Sensors, SightKind, StackMode,
+    StackOverflowPolicy, VmSettings, Word,
+};
+use crate::vm::{Body, Flow, GenomeVm, Intent};
+use bevy::math::Vec2;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::panic::{self, AssertUnwindSafe};
+
+const GENOMES: usize = 2000;
+const FRAMES_PER_GENOME: usize = 50;
+const MAX_GENOME_LENGTH: usize = 200;
+
+/// Floats that tend to break arithmetic and clamping
+const EXTREME_FLOATS: [f32; 8] = [
+    f32::NAN,
+    f32::INFINITY,
+    f32::NEG_INFINITY,
+    f32::MAX,
+    f32::MIN,
+    f32::MIN_POSITIVE,
+    0.0,
+    -0.0,
+];
+
+/// Words `Word::random` never produces
+const UNGENERATED_WORDS: [Word; 6] = [
+    Word::Drop,
+    Word::Over,
+    Word::Rot,
+    Word::And,
+    Word::Or,
+    Word::Not,
+];
+
+fn random_float(rng: &mut StdRng) -> f32 {
+    if rng.gen_bool(0.2) {
+        EXTREME_FLOATS[rng.gen_range(0..EXTREME_FLOATS.len())]
+    } else {
+        rng.gen_range(-1000.0..1000.0)
+    }
+}
+
+fn random_genome(rng: &mut StdRng) -> Genome {
+    let length = rng.gen_range(1..=MAX_GENOME_LENGTH);
+    let words = (0..length)
+        .map(|_| match rng.gen_range(0..10) {
+            0 => Word::PushFloat(random_float(rng)),
+            1 => Word::PushConst(rng.r#gen()),
+            2 => UNGENERATED_WORDS[rng.gen_range(0..UNGENERATED_WORDS.len())],
+            _ => Word::random(rng),
+        })
+        .collect();
+    let constants = (0..rng.gen_range(0..=CONSTANT_POOL_SIZE))
+        .map(|_| random_float(rng))
+        .collect();
+    Genome {
+        words,
+        constants,
+        rates: MutationRates::default(),
+        diet: Diet::default(),
+        body_size: DEFAULT_BODY_SIZE,
+    }
+}
+
+fn random_sensors(rng: &mut StdRng) -> Sensors {
+    let mut smell = || {
+        rng.gen_bool(0.5)
+            .then(|| rng.gen_range(0.0..WORLD_BOUNDS * 2.0))
+    };
+    let mut quadrants = || Quadrants {
+        front: smell(),
+        back: smell(),
+        left: smell(),
+        right: smell(),
+    };
+    Sensors {
+        plant_scent: quadrants(),
+        animal_scent: quadrants(),
+        corpse_scent: quadrants(),
+        nearest_animal: quadrants(),
+        hearing: quadrants(),
+        touching_plant: rng.gen_bool(0.3),
+        touching_animal: rng.gen_bool(0.3),
+        starving: rng.gen_bool(0.3),
+        sight: rng.gen_bool(0.5).then(|| {
+            let kind = if rng.gen_bool(0.5) {
+                SightKind::Plant
+            } else {
+                SightKind::Animal
+            };
+            (rng.gen_range(0.0..VISION_RANGE), kind)
+        }),
+        pheromone: rng.gen_range(0.0..PHEROMONE_MAX),
+        contacts: rng.gen_range(0..5),
+        nearest_plant_energy: rng
+            .gen_bool(0.5)
+            .then(|| rng.gen_range(0..=PLANT_MAX_ENERGY)),
+        crowding: rng.gen_range(0..20),
+        heading: rng.gen_range(0.0..1.0),
+        position: Vec2::new(
+            rng.gen_range(-WORLD_BOUNDS..WORLD_BOUNDS),
+            rng.gen_range(-WORLD_BOUNDS..WORLD_BOUNDS),
+        ),
+        speed: rng.gen_range(0.0..10.0),
+        turn_rate: rng.gen_range(-0.5..0.5),
+        tracked: true,
+    }
+}
+
+fn random_settings(rng: &mut StdRng) -> VmSettings {
+    VmSettings {
+        stack_capacity: rng.gen_range(1..=STACK_CAPACITY),
+        overflow_policy: StackOverflowPolicy::ALL[rng.gen_range(0..StackOverflowPolicy::ALL.len())],
+        safe_math: rng.gen_bool(0.5),
+        stack_mode: StackMode::ALL[rng.gen_range(0..StackMode::ALL.len())],
+    }
+}
+
+fn check_intent(intent: Intent) {
+    match intent {
+        Intent::Move { forward, right } => {
+            assert!(forward.abs() <= MAX_MOVEMENT_SPEED, "forward {forward}");
+            assert!(right.abs() <= MAX_STRAFE_SPEED, "right {right}");
+        }
+        Intent::Turn(degrees) => {
+            assert!(degrees.abs() <= MAX_ANGULAR_VELOCITY, "turn {degrees}")
+        }
+        Intent::SetColor(hue) => assert!((0.0..360.0).contains(&hue), "hue {hue}"),
+        Intent::DepositPheromone(amount) => {
+            assert!(
+                amount > 0.0 && amount <= PHEROMONE_MAX_DEPOSIT,
+                "deposit {amount}"
+            )
+        }
+        Intent::Eat
+        | Intent::Split
+        | Intent::Attack
+        | Intent::Grab
+        | Intent::DropItem
+        | Intent::Call => {}
+    }
+}
+
+/// Run one genome for several frames, asserting the VM invariants after every step
+fn soak(genome: &Genome, rng: &mut StdRng) {
+    let settings = random_settings(rng);
+    let mut body = Body {
+        energy: rng.gen_range(0..200),
+        split_ratio: DEFAULT_SPLIT_RATIO,
+        age: rng.gen_range(0.0..MAX_LIFESPAN * 1.5),
+        lifespan: MAX_LIFESPAN,
+        split_cost: SPLIT_ENERGY_COST + rng.gen_range(0..50),
+    };
+    let mut executor = GenomeExecutor::new(body.energy);
+    executor.apply_settings(&settings);
+
+    for _ in 0..FRAMES_PER_GENOME {
+        let sensors = random_sensors(rng);
+        let energy_before = body.energy;
+        let budget = energy_before.min(MAX_INSTRUCTIONS_PER_FRAME);
+
+        let mut vm = GenomeVm::new(genome, &mut executor);
+        let frame = vm.run_frame(&mut body, &sensors);
+
+        assert!(body.energy <= energy_before, "the VM never creates energy");
+        assert!((MIN_SPLIT_RATIO..=MAX_SPLIT_RATIO).contains(&body.split_ratio));
+        assert!(executor.instructions_executed_this_frame <= budget);
+        assert!(executor.stack.len() <= settings.stack_capacity);
+        assert!(executor.bool_stack.len() <= settings.stack_capacity);
+        assert!(executor.instruction_pointer < genome.words.len());
+        assert!(frame.intents.len() <= budget as usize);
+        frame.intents.iter().copied().for_each(check_intent);
+
+        if frame.fatal {
+            assert_eq!(settings.overflow_policy, StackOverflowPolicy::Kill);
+            return;
+        }
+
+        // Steps taken one at a time must respect the same limits
+        let mut vm = GenomeVm::new(genome, &mut executor);
+        let step = vm.step(&mut body, &sensors);
+        if let Some(intent) = step.intent {
+            check_intent(intent);
+        }
+        if step.flow == Flow::Fatal {
+            return;
+        }
+        assert!(executor.stack.len() <= settings.stack_capacity);
+    }
+}
+
+#[test]
+fn random_genomes_respect_vm_limits() {
+    let mut rng = StdRng::seed_from_u64(0x5eed);
+    for _ in 0..GENOMES {
+        let genome = random_genome(&mut rng);
+        let result = panic::catch_unwind(AssertUnwindSafe(|| soak(&genome, &mut rng)));
+        if let Err(err) = result {
+            eprintln!("Failing genome:\n{}", genome.to_source());
+            panic::resume_unwind(err);
+        }
+    }
+}
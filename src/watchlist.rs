@@ -0,0 +1,262 @@
+use crate::animal::{Animal, AnimalDied, DeathCause};
+use crate::charts::{SvgChart, export_chart};
+use crate::config::*;
+use crate::dock::{DockLayout, DockTab};
+use crate::{RunName, SimulationTick};
+use bevy::prelude::*;
+use bevy_egui::{EguiContexts, egui};
+use std::collections::VecDeque;
+
+/// A single pinned animal and its recent energy history
+pub struct WatchedAnimal {
+    pub entity: Entity,
+    pub energy_history: VecDeque<f32>,
+}
+
+/// Record of a pinned animal that has died
+pub struct WatchedDeath {
+    pub entity: Entity,
+    pub cause: DeathCause,
+    pub final_energy_history: VecDeque<f32>,
+}
+
+/// Resource holding the animals pinned to the watchlist
+#[derive(Resource, Default)]
+pub struct Watchlist {
+    pub pinned: Vec<WatchedAnimal>,
+    pub recent_deaths: VecDeque<WatchedDeath>,
+    /// Simulation tick of each of the latest samples (shared by all pinned animals)
+    pub sample_ticks: VecDeque<u64>,
+}
+
+impl Watchlist {
+    pub fn is_pinned(&self, entity: Entity) -> bool {
+        self.pinned.iter().any(|w| w.entity == entity)
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.pinned.len() >= WATCHLIST_MAX_PINNED
+    }
+
+    /// Pin an animal; returns false if the watchlist is already full
+    pub fn pin(&mut self, entity: Entity) -> bool {
+        if self.is_pinned(entity) {
+            return true;
+        }
+        if self.is_full() {
+            return false;
+        }
+        self.pinned.push(WatchedAnimal {
+            entity,
+            energy_history: VecDeque::with_capacity(WATCHLIST_HISTORY_LENGTH),
+        });
+        true
+    }
+
+    pub fn unpin(&mut self, entity: Entity) {
+        self.pinned.retain(|w| w.entity != entity);
+    }
+}
+
+/// Timer for sampling pinned animals' energy
+#[derive(Resource)]
+pub struct WatchlistSampleTimer(pub Timer);
+
+/// System to record the energy of each pinned animal at a fixed interval
+pub fn sample_watchlist(
+    time: Res<Time>,
+    mut timer: ResMut<WatchlistSampleTimer>,
+    mut watchlist: ResMut<Watchlist>,
+    tick: Res<SimulationTick>,
+    animals: Query<&Animal>,
+) {
+    if !timer.0.tick(time.delta()).just_finished() {
+        return;
+    }
+
+    if watchlist.sample_ticks.len() >= WATCHLIST_HISTORY_LENGTH {
+        watchlist.sample_ticks.pop_front();
+    }
+    watchlist.sample_ticks.push_back(tick.0);
+
+    for watched in watchlist.pinned.iter_mut() {
+        if let Ok(animal) = animals.get(watched.entity) {
+            if watched.energy_history.len() >= WATCHLIST_HISTORY_LENGTH {
+                watched.energy_history.pop_front();
+            }
+            watched.energy_history.push_back(animal.energy as f32);
+        }
+    }
+}
+
+/// System to drop pinned animals when they die, remembering the cause
+pub fn remove_dead_watched(mut deaths: EventReader<AnimalDied>, mut watchlist: ResMut<Watchlist>) {
+    for death in deaths.read() {
+        let Some(index) = watchlist
+            .pinned
+            .iter()
+            .position(|w| w.entity == death.entity)
+        else {
+            continue;
+        };
+
+        let watched = watchlist.pinned.remove(index);
+        if watchlist.recent_deaths.len() >= WATCHLIST_MAX_PINNED {
+            watchlist.recent_deaths.pop_front();
+        }
+        watchlist.recent_deaths.push_back(WatchedDeath {
+            entity: watched.entity,
+            cause: death.cause,
+            final_energy_history: watched.energy_history,
+        });
+    }
+}
+
+/// Draw a small line chart of the given values filling the available width
+pub fn draw_sparkline(
+    ui: &mut egui::Ui,
+    values: &VecDeque<f32>,
+    height: f32,
+    color: egui::Color32,
+) {
+    let (rect, _response) = ui.allocate_exact_size(
+        egui::vec2(ui.available_width(), height),
+        egui::Sense::hover(),
+    );
+    ui.painter().rect_filled(
+        rect,
+        egui::Rounding::same(2.0),
+        egui::Color32::from_gray(30),
+    );
+
+    if values.len() < 2 {
+        return;
+    }
+
+    let max = values.iter().copied().fold(f32::MIN, f32::max).max(1.0);
+    let step = rect.width() / (WATCHLIST_HISTORY_LENGTH - 1) as f32;
+    let points: Vec<egui::Pos2> = values
+        .iter()
+        .enumerate()
+        .map(|(i, value)| {
+            egui::pos2(
+                rect.left() + i as f32 * step,
+                rect.bottom() - (value / max) * rect.height(),
+            )
+        })
+        .collect();
+
+    ui.painter()
+        .add(egui::Shape::line(points, egui::Stroke::new(1.5, color)));
+}
+
+/// Line colors for pinned animals in exported charts
+const SERIES_COLORS: [egui::Color32; 4] = [
+    egui::Color32::from_rgb(40, 160, 40),
+    egui::Color32::from_rgb(60, 110, 220),
+    egui::Color32::from_rgb(220, 120, 30),
+    egui::Color32::from_rgb(170, 60, 170),
+];
+
+/// System to show the watchlist window with a sparkline per pinned animal
+pub fn watchlist_ui(
+    mut contexts: EguiContexts,
+    mut watchlist: ResMut<Watchlist>,
+    run_name: Res<RunName>,
+    dock: Res<DockLayout>,
+    animals: Query<&Animal>,
+) {
+    if watchlist.pinned.is_empty() && watchlist.recent_deaths.is_empty() {
+        return;
+    }
+
+    dock.show(
+        contexts.ctx_mut(),
+        DockTab::Watchlist,
+        egui::Window::new("Watchlist")
+            .default_pos(egui::pos2(10.0, 600.0))
+            .default_width(260.0),
+        |ui| {
+            let mut to_unpin = None;
+
+            for watched in watchlist.pinned.iter() {
+                ui.horizontal(|ui| {
+                    let energy = animals
+                        .get(watched.entity)
+                        .map(|a| a.energy)
+                        .unwrap_or_default();
+                    ui.label(format!("{} | Energy: {}", watched.entity, energy));
+                    if ui.small_button("✖").clicked() {
+                        to_unpin = Some(watched.entity);
+                    }
+                });
+                draw_sparkline(
+                    ui,
+                    &watched.energy_history,
+                    40.0,
+                    egui::Color32::from_rgb(100, 255, 100),
+                );
+                ui.add_space(4.0);
+            }
+
+            if let Some(entity) = to_unpin {
+                watchlist.unpin(entity);
+            }
+
+            if !watchlist.pinned.is_empty() && ui.small_button("🖼 Export SVG").clicked() {
+                // Only as many ticks as the longest history covers
+                let samples = watchlist
+                    .pinned
+                    .iter()
+                    .map(|w| w.energy_history.len())
+                    .max()
+                    .unwrap_or(0);
+                let ticks = &watchlist.sample_ticks;
+                let first = ticks.len().saturating_sub(samples);
+                let names: Vec<String> = watchlist
+                    .pinned
+                    .iter()
+                    .map(|w| w.entity.to_string())
+                    .collect();
+                export_chart(&SvgChart {
+                    title: "Watchlist energy",
+                    y_label: "Energy",
+                    run_name: &run_name.0,
+                    ticks: (
+                        ticks.get(first).copied().unwrap_or_default(),
+                        ticks.back().copied().unwrap_or_default(),
+                    ),
+                    series: watchlist
+                        .pinned
+                        .iter()
+                        .zip(&names)
+                        .enumerate()
+                        .map(|(i, (watched, name))| {
+                            (
+                                name.as_str(),
+                                &watched.energy_history,
+                                SERIES_COLORS[i % SERIES_COLORS.len()],
+                            )
+                        })
+                        .collect(),
+                    markers: Vec::new(),
+                });
+            }
+
+            if !watchlist.recent_deaths.is_empty() {
+                ui.separator();
+                ui.label("Recently died:");
+                for death in watchlist.recent_deaths.iter() {
+                    ui.colored_label(
+                        egui::Color32::GRAY,
+                        format!("† {} ({})", death.entity, death.cause),
+                    );
+                    draw_sparkline(ui, &death.final_energy_history, 20.0, egui::Color32::GRAY);
+                }
+                if ui.small_button("Clear").clicked() {
+                    watchlist.recent_deaths.clear();
+                }
+            }
+        },
+    );
+}
@@ -0,0 +1,191 @@
+use crate::fast_forward::FastForward;
+use crate::genome::{GenomeExecutor, TraceEntry, Word};
+use crate::{SimulationState, SimulationTick};
+use bevy::prelude::*;
+use std::fmt;
+
+/// Comparison operator of a watch condition
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Comparison {
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+    Equal,
+    NotEqual,
+}
+
+impl Comparison {
+    /// Operators as written in a watch expression; two-character ones first so they win
+    /// over their one-character prefixes
+    const SYMBOLS: [(&str, Comparison); 6] = [
+        ("<=", Comparison::LessOrEqual),
+        (">=", Comparison::GreaterOrEqual),
+        ("==", Comparison::Equal),
+        ("!=", Comparison::NotEqual),
+        ("<", Comparison::Less),
+        (">", Comparison::Greater),
+    ];
+
+    fn holds(self, left: f32, right: f32) -> bool {
+        match self {
+            Comparison::Less => left < right,
+            Comparison::LessOrEqual => left <= right,
+            Comparison::Greater => left > right,
+            Comparison::GreaterOrEqual => left >= right,
+            Comparison::Equal => left == right,
+            Comparison::NotEqual => left != right,
+        }
+    }
+}
+
+impl fmt::Display for Comparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (symbol, _) = Self::SYMBOLS
+            .iter()
+            .find(|(_, comparison)| comparison == self)
+            .expect("every comparison has a symbol");
+        write!(f, "{}", symbol)
+    }
+}
+
+/// Condition on the float an executed word leaves on top of the stack, written as
+/// `[word] <op> <number>`: `> 500` watches every word, `energy < 5` only `energy`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WatchCondition {
+    /// Only this word triggers the watch; any word if None
+    pub word: Option<Word>,
+    pub comparison: Comparison,
+    pub value: f32,
+}
+
+impl WatchCondition {
+    /// Whether the executed instruction satisfies the condition. Bools on top of the
+    /// stack never do
+    pub fn matches(&self, entry: &TraceEntry) -> bool {
+        self.word.is_none_or(|word| word == entry.word)
+            && entry
+                .stack_top
+                .and_then(|top| top.as_float())
+                .is_some_and(|top| self.comparison.holds(top, self.value))
+    }
+}
+
+impl std::str::FromStr for WatchCondition {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (at, symbol, comparison) = Comparison::SYMBOLS
+            .iter()
+            .filter_map(|&(symbol, comparison)| Some((s.find(symbol)?, symbol, comparison)))
+            .min_by_key(|(at, symbol, _)| (*at, std::cmp::Reverse(symbol.len())))
+            .ok_or_else(|| "expected a comparison: < <= > >= == !=".to_string())?;
+
+        let word = match s[..at].trim() {
+            "" | "top" => None,
+            name => Some(
+                name.parse::<Word>()
+                    .map_err(|_| format!("unknown word '{}'", name))?,
+            ),
+        };
+        let number = s[at + symbol.len()..].trim();
+        let value = number
+            .parse()
+            .map_err(|_| format!("expected a number after {}, found '{}'", symbol, number))?;
+
+        Ok(Self {
+            word,
+            comparison,
+            value,
+        })
+    }
+}
+
+impl fmt::Display for WatchCondition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.word {
+            Some(word) => write!(f, "{} {} {}", word, self.comparison, self.value),
+            None => write!(f, "top {} {}", self.comparison, self.value),
+        }
+    }
+}
+
+/// Watch placed on an animal from the genome viewer: the simulation pauses at the end of
+/// any tick in which one of its words satisfies the condition
+#[derive(Component)]
+pub struct Watchpoint {
+    pub condition: WatchCondition,
+    pub hits: u32,
+    /// Tick and instruction of the most recent hit
+    pub last_hit: Option<(u64, TraceEntry)>,
+}
+
+impl Watchpoint {
+    pub fn new(condition: WatchCondition) -> Self {
+        Self {
+            condition,
+            hits: 0,
+            last_hit: None,
+        }
+    }
+}
+
+/// System to pause the simulation (and stop a fast-forward) when a watched animal's
+/// genome triggered its watch this tick, then hand any changed condition to the
+/// executor for the next tick
+pub fn check_watchpoints(
+    tick: Res<SimulationTick>,
+    mut state: ResMut<SimulationState>,
+    mut fast_forward: ResMut<FastForward>,
+    mut animals: Query<(Entity, &mut GenomeExecutor, &mut Watchpoint)>,
+) {
+    for (entity, mut executor, mut watchpoint) in animals.iter_mut() {
+        if let Some(entry) = executor.take_watch_hit() {
+            watchpoint.hits += 1;
+            watchpoint.last_hit = Some((tick.0, entry));
+            *state = SimulationState::Paused;
+            fast_forward.cancel();
+            info!(
+                "Watchpoint '{}' on {} hit at tick {}: {} at {}",
+                watchpoint.condition, entity, tick.0, entry.word, entry.instruction_pointer
+            );
+        }
+        if executor.watch != Some(watchpoint.condition) {
+            executor.watch = Some(watchpoint.condition);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::genome::StackValue;
+
+    #[test]
+    fn conditions_parse_and_match() {
+        let any: WatchCondition = "> 500".parse().unwrap();
+        assert_eq!(any.word, None);
+        assert_eq!(any.to_string(), "top > 500");
+        assert_eq!(any, "top>500".parse().unwrap());
+
+        let energy: WatchCondition = "energy <= 5".parse().unwrap();
+        assert_eq!(energy.word, Some(Word::Energy));
+        assert_eq!(energy.comparison, Comparison::LessOrEqual);
+        assert_eq!(energy.to_string(), "energy <= 5");
+
+        assert!("energy 5".parse::<WatchCondition>().is_err());
+        assert!("bogus < 5".parse::<WatchCondition>().is_err());
+        assert!("< five".parse::<WatchCondition>().is_err());
+
+        let entry = |word, top| TraceEntry {
+            instruction_pointer: 0,
+            word,
+            stack_top: Some(top),
+        };
+        assert!(energy.matches(&entry(Word::Energy, StackValue::Float(5.0))));
+        assert!(!energy.matches(&entry(Word::Energy, StackValue::Float(6.0))));
+        assert!(!energy.matches(&entry(Word::Dup, StackValue::Float(1.0))));
+        assert!(any.matches(&entry(Word::Dup, StackValue::Float(501.0))));
+        assert!(!any.matches(&entry(Word::Dup, StackValue::Bool(true))));
+    }
+}
@@ -13,7 +13,7 @@ fn test_simulation_startup_and_execution() {
     // Just make sure the app structure is valid and doesn't panic on startup
 
     // This test passes if we reach here without panicking
-    assert!(true, "App structure is valid");
+    app.update();
 }
 
 /// Test that genome execution doesn't panic with random genomes